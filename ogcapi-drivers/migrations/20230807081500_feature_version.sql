@@ -0,0 +1,12 @@
+-- Add version column to existing item tables
+DO $$
+DECLARE
+    t record;
+BEGIN
+    FOR t IN SELECT table_name FROM information_schema.tables WHERE table_schema = 'items' LOOP
+        EXECUTE format(
+            'ALTER TABLE items.%I ADD COLUMN IF NOT EXISTS version bigint NOT NULL DEFAULT 1',
+            t.table_name
+        );
+    END LOOP;
+END $$;
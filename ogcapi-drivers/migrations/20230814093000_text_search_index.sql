@@ -0,0 +1,13 @@
+-- Add full text search index to existing item tables
+DO $$
+DECLARE
+    t record;
+BEGIN
+    FOR t IN SELECT table_name FROM information_schema.tables WHERE table_schema = 'items' LOOP
+        EXECUTE format(
+            'CREATE INDEX IF NOT EXISTS %I ON items.%I USING gin (jsonb_to_tsvector(''simple'', properties, ''["string"]''))',
+            t.table_name || '_text_search_idx',
+            t.table_name
+        );
+    END LOOP;
+END $$;
@@ -0,0 +1,7 @@
+-- Instances of EDR collections
+CREATE TABLE meta.instances (
+    collection text REFERENCES meta.collections(id) ON DELETE CASCADE,
+    id text,
+    instance jsonb NOT NULL,
+    PRIMARY KEY (collection, id)
+);
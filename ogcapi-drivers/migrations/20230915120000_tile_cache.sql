@@ -0,0 +1,10 @@
+-- Pre-generated tiles of collections
+CREATE TABLE meta.tiles (
+    collection text REFERENCES meta.collections(id) ON DELETE CASCADE,
+    tms text,
+    matrix text,
+    "row" integer,
+    col integer,
+    tile bytea NOT NULL,
+    PRIMARY KEY (collection, tms, matrix, "row", col)
+);
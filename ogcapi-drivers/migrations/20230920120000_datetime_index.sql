@@ -0,0 +1,54 @@
+-- Parses an RFC 3339 timestamp, NULL if it is malformed or lacks an offset.
+-- Only timestamps with an explicit offset are accepted, so the result does not
+-- depend on the `TimeZone` of the session and the function is immutable.
+CREATE OR REPLACE FUNCTION public.item_timestamp(value text)
+RETURNS timestamptz
+LANGUAGE plpgsql IMMUTABLE STRICT PARALLEL SAFE
+AS $$
+BEGIN
+    IF value !~ '^\d{4}-\d{2}-\d{2}[Tt ]\d{2}:\d{2}:\d{2}(\.\d+)?([Zz]|[+-]\d{2}:\d{2})$' THEN
+        RETURN NULL;
+    END IF;
+    RETURN value::timestamptz;
+EXCEPTION WHEN others THEN
+    RETURN NULL;
+END
+$$;
+
+-- Temporal extent of an item, an instant of the `datetime` property or the
+-- interval from `start_datetime` to `end_datetime`, unbounded if unknown or
+-- not a valid timestamp.
+CREATE OR REPLACE FUNCTION public.item_datetime(properties jsonb)
+RETURNS tstzrange
+LANGUAGE sql IMMUTABLE PARALLEL SAFE
+AS $$
+    SELECT CASE
+        WHEN instant IS NOT NULL THEN tstzrange(instant, instant, '[]')
+        WHEN start IS NOT NULL AND "end" IS NOT NULL THEN tstzrange(
+            least(start, "end"),
+            greatest(start, "end"),
+            '[]'
+        )
+        ELSE tstzrange(NULL, NULL)
+    END
+    FROM (
+        SELECT
+            public.item_timestamp(properties->>'datetime') AS instant,
+            public.item_timestamp(properties->>'start_datetime') AS start,
+            public.item_timestamp(properties->>'end_datetime') AS "end"
+    ) t
+$$;
+
+-- Add datetime index to existing item tables
+DO $$
+DECLARE
+    t record;
+BEGIN
+    FOR t IN SELECT table_name FROM information_schema.tables WHERE table_schema = 'items' LOOP
+        EXECUTE format(
+            'CREATE INDEX IF NOT EXISTS %I ON items.%I USING gist (public.item_datetime(properties))',
+            t.table_name || '_datetime_idx',
+            t.table_name
+        );
+    END LOOP;
+END $$;
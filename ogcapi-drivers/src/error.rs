@@ -0,0 +1,39 @@
+/// Errors with a well defined meaning a driver can return.
+///
+/// Drivers report failures as `anyhow::Error`, callers may downcast to this
+/// type to react to specific conditions.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    /// The resource does not exist
+    #[error("{0}")]
+    NotFound(String),
+    /// The resource was modified concurrently
+    #[error("{0}")]
+    Conflict(String),
+    /// The input is invalid
+    #[error("{0}")]
+    Invalid(String),
+    /// The backend is overloaded, e.g. no database connection is available
+    #[error("{0}")]
+    Unavailable(String),
+}
+
+impl Error {
+    /// Extracts the well defined error of a driver failure, which may also be
+    /// a known error of the backend, or returns the failure as is
+    pub fn classify(error: anyhow::Error) -> Result<Error, anyhow::Error> {
+        let error = match error.downcast::<Error>() {
+            Ok(error) => return Ok(error),
+            Err(error) => error,
+        };
+
+        #[cfg(feature = "postgres")]
+        if let Some(sqlx::Error::PoolTimedOut) = error.downcast_ref::<sqlx::Error>() {
+            return Ok(Error::Unavailable(
+                "No database connection available".to_string(),
+            ));
+        }
+
+        Err(error)
+    }
+}
@@ -1,14 +1,19 @@
+mod error;
 #[cfg(feature = "postgres")]
 pub mod postgres;
 #[cfg(feature = "s3")]
 pub mod s3;
 
+pub use error::Error;
+
 #[cfg(feature = "stac")]
 use ogcapi_types::stac::SearchParams;
 use ogcapi_types::{
     common::{Collection, Collections, Crs, Query as CollectionQuery},
-    edr::{Query as EdrQuery, QueryType},
-    features::{Feature, FeatureCollection, Query as FeatureQuery},
+    edr::{Instance, Query as EdrQuery, QueryType},
+    features::{
+        AggregationGroup, Feature, FeatureCollection, PropertyValue, Query as FeatureQuery,
+    },
     processes::{Results, StatusInfo},
     styles::Styles,
     tiles::TileMatrixSet,
@@ -26,6 +31,12 @@ pub trait CollectionTransactions: Send + Sync {
     async fn delete_collection(&self, id: &str) -> anyhow::Result<()>;
 
     async fn list_collections(&self, query: &CollectionQuery) -> anyhow::Result<Collections>;
+
+    /// Recomputes the spatial and temporal extent of a collection from its
+    /// items, drivers without stored items keep the extent as is.
+    async fn refresh_extent(&self, _id: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
 }
 
 /// Trait for `Feature` transactions
@@ -33,6 +44,15 @@ pub trait CollectionTransactions: Send + Sync {
 pub trait FeatureTransactions: Send + Sync {
     async fn create_feature(&self, feature: &Feature) -> anyhow::Result<String>;
 
+    /// Creates several features and returns their ids in the same order.
+    async fn create_features(&self, features: &[Feature]) -> anyhow::Result<Vec<String>> {
+        let mut ids = Vec::with_capacity(features.len());
+        for feature in features {
+            ids.push(self.create_feature(feature).await?);
+        }
+        Ok(ids)
+    }
+
     async fn read_feature(
         &self,
         collection: &str,
@@ -48,6 +68,38 @@ pub trait FeatureTransactions: Send + Sync {
         collection: &str,
         query: &FeatureQuery,
     ) -> anyhow::Result<FeatureCollection>;
+
+    /// Distinct values of a property with their number of occurrences,
+    /// ordered by descending count.
+    async fn distinct_values(
+        &self,
+        collection: &str,
+        property: &str,
+        query: &FeatureQuery,
+    ) -> anyhow::Result<Vec<PropertyValue>>;
+
+    /// Number of features of a collection, estimated from statistics for
+    /// large collections unless `exact`, `None` if not supported.
+    async fn count_items(
+        &self,
+        _collection: &Collection,
+        _exact: bool,
+    ) -> anyhow::Result<Option<u64>> {
+        Ok(None)
+    }
+
+    /// Names of the properties the features of a collection can be queried by.
+    async fn queryables(&self, collection: &str) -> anyhow::Result<Vec<String>>;
+
+    /// Statistics of a numeric property, grouped by the values of `group_by`
+    /// if given.
+    async fn aggregate(
+        &self,
+        collection: &str,
+        property: &str,
+        group_by: Option<&str>,
+        query: &FeatureQuery,
+    ) -> anyhow::Result<Vec<AggregationGroup>>;
 }
 
 /// Trait for `STAC` search
@@ -60,12 +112,28 @@ pub trait StacSeach: Send + Sync {
 /// Trait for `EDR` queries
 #[async_trait::async_trait]
 pub trait EdrQuerier: Send + Sync {
+    /// Queries the items of a collection, optionally scoped to an instance
     async fn query(
         &self,
         collection_id: &str,
+        instance_id: Option<&str>,
         query_type: &QueryType,
         query: &EdrQuery,
     ) -> anyhow::Result<FeatureCollection>;
+
+    async fn create_instance(
+        &self,
+        collection_id: &str,
+        instance: &Instance,
+    ) -> anyhow::Result<String>;
+
+    async fn read_instance(
+        &self,
+        collection_id: &str,
+        instance_id: &str,
+    ) -> anyhow::Result<Option<Instance>>;
+
+    async fn list_instances(&self, collection_id: &str) -> anyhow::Result<Vec<Instance>>;
 }
 
 /// Trait for `Processes` jobs
@@ -73,6 +141,13 @@ pub trait EdrQuerier: Send + Sync {
 pub trait JobHandler: Send + Sync {
     async fn register(&self, job: &StatusInfo) -> anyhow::Result<String>;
 
+    /// Updates status, message and progress of a job unless it was dismissed.
+    async fn update(&self, job: &StatusInfo) -> anyhow::Result<()>;
+
+    /// Marks a job as finished with its final status and results unless it
+    /// was dismissed.
+    async fn finish(&self, job: &StatusInfo, results: Option<&Results>) -> anyhow::Result<()>;
+
     async fn status(&self, id: &str) -> anyhow::Result<Option<StatusInfo>>;
 
     async fn dismiss(&self, id: &str) -> anyhow::Result<Option<StatusInfo>>;
@@ -99,4 +174,47 @@ pub trait TileTransactions: Send + Sync {
         row: u32,
         col: u32,
     ) -> anyhow::Result<Vec<u8>>;
+
+    /// Geometries of the features within a tile, in tile coordinates with
+    /// `extent` units per side and the origin at the top left corner
+    async fn tile_geometries(
+        &self,
+        collections: &str,
+        tms: &TileMatrixSet,
+        matrix: &str,
+        row: u32,
+        col: u32,
+        extent: u32,
+    ) -> anyhow::Result<Vec<geojson::Geometry>>;
+
+    /// Geometries of the features of a collection intersecting the bbox,
+    /// transformed to the CRS of the bbox
+    async fn map_geometries(
+        &self,
+        collection: &str,
+        bbox: &[f64; 4],
+        crs: &Crs,
+    ) -> anyhow::Result<Vec<geojson::Geometry>>;
+
+    /// Reads a pre-generated tile of a single collection from the cache
+    async fn read_cached_tile(
+        &self,
+        collection: &str,
+        tms: &TileMatrixSet,
+        matrix: &str,
+        row: u32,
+        col: u32,
+    ) -> anyhow::Result<Option<Vec<u8>>>;
+
+    /// Stores a tile of a single collection in the cache, replacing any
+    /// previous one
+    async fn cache_tile(
+        &self,
+        collection: &str,
+        tms: &TileMatrixSet,
+        matrix: &str,
+        row: u32,
+        col: u32,
+        tile: &[u8],
+    ) -> anyhow::Result<()>;
 }
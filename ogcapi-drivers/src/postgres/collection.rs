@@ -1,59 +1,154 @@
 use ogcapi_types::common::{Collection, Collections, Query};
 
-use crate::CollectionTransactions;
+use crate::{CollectionTransactions, Error};
 
-use super::Db;
+use super::{geometry_column, quote_ident, retry, Db};
+
+/// Spatial extent of the collection metadata as envelope in CRS84, `NULL` if
+/// not set, given as a 2D or 3D bbox
+const PRIOR_EXTENT: &str = r#"
+    SELECT CASE
+        WHEN jsonb_typeof(b) <> 'array' THEN NULL
+        WHEN jsonb_array_length(b) = 4 THEN ST_MakeEnvelope(
+            (b->>0)::float8, (b->>1)::float8, (b->>2)::float8, (b->>3)::float8, 4326
+        )
+        WHEN jsonb_array_length(b) = 6 THEN ST_MakeEnvelope(
+            (b->>0)::float8, (b->>1)::float8, (b->>3)::float8, (b->>4)::float8, 4326
+        )
+    END
+    FROM (SELECT collection -> 'extent' -> 'spatial' -> 'bbox' -> 0 AS b) prior
+"#;
+
+/// Returns the query of the spatial extent JSON of the CRS84 geometries `g`
+/// selected by `source`, `NULL` without geometries
+///
+/// The extent is 3D if the stored one is. Its z range is the one of the
+/// geometries having a z coordinate, grown from the stored range if `grow`
+/// and else replacing it, which is kept if none has.
+fn extent_bbox(source: &str, grow: bool) -> String {
+    let (zmin, zmax) = if grow {
+        (
+            "least((b ->> 2)::float8, zmin)",
+            "greatest((b ->> 5)::float8, zmax)",
+        )
+    } else {
+        (
+            "COALESCE(zmin, (b ->> 2)::float8)",
+            "COALESCE(zmax, (b ->> 5)::float8)",
+        )
+    };
+
+    format!(
+        r#"
+        SELECT CASE
+            WHEN jsonb_typeof(b) <> 'array' THEN jsonb_build_array(jsonb_build_array(
+                ST_XMin(e), ST_YMin(e), ST_XMax(e), ST_YMax(e)
+            ))
+            WHEN jsonb_array_length(b) = 6 THEN jsonb_build_array(jsonb_build_array(
+                ST_XMin(e), ST_YMin(e), {zmin}, ST_XMax(e), ST_YMax(e), {zmax}
+            ))
+            ELSE jsonb_build_array(jsonb_build_array(
+                ST_XMin(e), ST_YMin(e), ST_XMax(e), ST_YMax(e)
+            ))
+        END
+        FROM (
+            SELECT
+                ST_Extent(g) AS e,
+                min(ST_ZMin(g)) FILTER (WHERE ST_Zmflag(g) IN (2, 3)) AS zmin,
+                max(ST_ZMax(g)) FILTER (WHERE ST_Zmflag(g) IN (2, 3)) AS zmax
+            FROM ({source}) u
+        ) m, (SELECT collection -> 'extent' -> 'spatial' -> 'bbox' -> 0 AS b) prior
+        WHERE e IS NOT NULL
+        "#
+    )
+}
+
+/// Format of the timestamps of the temporal extent, in UTC
+const TIMESTAMP_FORMAT: &str = r#"YYYY-MM-DD"T"HH24:MI:SS.US"Z""#;
 
 #[async_trait::async_trait]
 impl CollectionTransactions for Db {
     async fn create_collection(&self, collection: &Collection) -> anyhow::Result<String> {
+        let geom = quote_ident(geometry_column(collection));
+        let items = self.items_schema();
+        let insert = format!(
+            "INSERT INTO {} ( id, collection ) VALUES ( $1, $2 )",
+            self.meta_table("collections")
+        );
+
         let mut tx = self.pool.begin().await?;
 
+        // Collections backed by an existing table only register their metadata
+        if collection.table.is_some() {
+            sqlx::query(&insert)
+                .bind(&collection.id)
+                .bind(sqlx::types::Json(collection))
+                .execute(&mut tx)
+                .await?;
+
+            tx.commit().await?;
+
+            return Ok(collection.id.to_owned());
+        }
+
         sqlx::query(&format!(
             r#"
-            CREATE TABLE items."{0}" (
+            CREATE TABLE "{items}"."{0}" (
                 id text PRIMARY KEY DEFAULT gen_random_uuid()::text,
-                collection text REFERENCES meta.collections(id) DEFAULT '{0}',
+                collection text REFERENCES {1}(id) DEFAULT '{0}',
                 properties jsonb,
-                geom geometry NOT NULL,
+                {geom} geometry NOT NULL,
                 links jsonb NOT NULL DEFAULT '[]'::jsonb,
                 assets jsonb NOT NULL DEFAULT '{{}}'::jsonb,
-                bbox jsonb
+                bbox jsonb,
+                version bigint NOT NULL DEFAULT 1
             )
             "#,
+            collection.id,
+            self.meta_table("collections")
+        ))
+        .execute(&mut tx)
+        .await?;
+
+        sqlx::query(&format!(
+            r#"CREATE INDEX ON "{items}"."{}" USING btree (collection)"#,
             collection.id
         ))
         .execute(&mut tx)
         .await?;
 
         sqlx::query(&format!(
-            r#"CREATE INDEX ON items."{}" USING btree (collection)"#,
+            r#"CREATE INDEX ON "{items}"."{}" USING gin (properties)"#,
             collection.id
         ))
         .execute(&mut tx)
         .await?;
 
         sqlx::query(&format!(
-            r#"CREATE INDEX ON items."{}" USING gin (properties)"#,
+            r#"CREATE INDEX ON "{items}"."{}" USING gist ({geom})"#,
             collection.id
         ))
         .execute(&mut tx)
         .await?;
 
         sqlx::query(&format!(
-            r#"CREATE INDEX ON items."{}" USING gist (geom)"#,
+            r#"CREATE INDEX ON "{items}"."{}" USING gin (jsonb_to_tsvector('simple', properties, '["string"]'))"#,
             collection.id
         ))
         .execute(&mut tx)
         .await?;
 
-        sqlx::query("SELECT UpdateGeometrySRID('items', $1, 'geom', $2)")
+        self.create_datetime_index(&mut tx, &collection.id).await?;
+
+        sqlx::query("SELECT UpdateGeometrySRID($1, $2, $3, $4)")
+            .bind(&items)
             .bind(&collection.id)
+            .bind(geometry_column(collection))
             .bind(collection.storage_crs.clone().unwrap_or_default().as_srid())
             .execute(&mut tx)
             .await?;
 
-        sqlx::query("INSERT INTO meta.collections ( id, collection ) VALUES ( $1, $2 )")
+        sqlx::query(&insert)
             .bind(&collection.id)
             .bind(sqlx::types::Json(collection))
             .execute(&mut tx)
@@ -66,56 +161,75 @@ impl CollectionTransactions for Db {
 
     async fn read_collection(&self, id: &str) -> anyhow::Result<Option<Collection>> {
         // TODO: cache
-        let collection: Option<sqlx::types::Json<Collection>> = sqlx::query_scalar(
-            r#"
-            SELECT collection as "collection!" 
-            FROM meta.collections WHERE id = $1
-            "#,
-        )
-        .bind(id)
-        .fetch_optional(&self.pool)
+        let collection: Option<sqlx::types::Json<Collection>> = retry(self.read_attempts, || {
+            sqlx::query_scalar(&format!(
+                r#"
+                SELECT collection as "collection!" 
+                FROM {} WHERE id = $1
+                "#,
+                self.meta_table("collections")
+            ))
+            .bind(id)
+            .fetch_optional(&self.pool)
+        })
         .await?;
 
         Ok(collection.map(|c| c.0))
     }
 
     async fn update_collection(&self, collection: &Collection) -> anyhow::Result<()> {
-        sqlx::query("UPDATE meta.collections SET collection = $2 WHERE id = $1")
-            .bind(&collection.id)
-            .bind(sqlx::types::Json(collection))
-            .execute(&self.pool)
-            .await?;
+        sqlx::query(&format!(
+            "UPDATE {} SET collection = $2 WHERE id = $1",
+            self.meta_table("collections")
+        ))
+        .bind(&collection.id)
+        .bind(sqlx::types::Json(collection))
+        .execute(&self.pool)
+        .await?;
 
         Ok(())
     }
 
     async fn delete_collection(&self, id: &str) -> anyhow::Result<()> {
+        let items = self.items_schema();
+
         let mut tx = self.pool.begin().await?;
 
-        sqlx::query(&format!(r#"DROP TABLE IF EXISTS items."{}""#, id))
+        sqlx::query(&format!(r#"DROP TABLE IF EXISTS "{items}"."{}""#, id))
             .execute(&mut tx)
             .await?;
 
-        sqlx::query("DELETE FROM meta.collections WHERE id = $1")
-            .bind(id)
-            .fetch_optional(&mut tx)
+        sqlx::query(&format!("DROP SEQUENCE IF EXISTS {}", self.id_sequence(id)))
+            .execute(&mut tx)
             .await?;
 
+        sqlx::query(&format!(
+            "DELETE FROM {} WHERE id = $1",
+            self.meta_table("collections")
+        ))
+        .bind(id)
+        .fetch_optional(&mut tx)
+        .await?;
+
         tx.commit().await?;
 
         Ok(())
     }
 
     async fn list_collections(&self, _query: &Query) -> anyhow::Result<Collections> {
-        let collections: Option<sqlx::types::Json<Vec<Collection>>> = sqlx::query_scalar(
-            r#"
-            SELECT array_to_json(array_agg(collection))
-            FROM meta.collections
-            WHERE collection ->> 'type' = 'Collection'
-            "#,
-        )
-        .fetch_one(&self.pool)
-        .await?;
+        let collections: Option<sqlx::types::Json<Vec<Collection>>> =
+            retry(self.read_attempts, || {
+                sqlx::query_scalar(&format!(
+                    r#"
+                    SELECT array_to_json(array_agg(collection))
+                    FROM {}
+                    WHERE collection ->> 'type' = 'Collection'
+                    "#,
+                    self.meta_table("collections")
+                ))
+                .fetch_one(&self.pool)
+            })
+            .await?;
 
         let collections = collections.map(|c| c.0).unwrap_or_default();
         let mut collections = Collections::new(collections);
@@ -123,4 +237,141 @@ impl CollectionTransactions for Db {
 
         Ok(collections)
     }
+
+    /// Replaces the spatial extent with the envelope of all items and the
+    /// temporal extent with the interval of their datetimes, the stored ones
+    /// are kept for collections without such items.
+    async fn refresh_extent(&self, id: &str) -> anyhow::Result<()> {
+        let collection = match self.read_collection(id).await? {
+            Some(c) => c,
+            None => return Ok(()),
+        };
+        let geom = quote_ident(geometry_column(&collection));
+        let table = self.items_table(&collection);
+
+        let bbox = extent_bbox(
+            &format!("SELECT ST_Transform({geom}, 4326) AS g FROM {table}"),
+            false,
+        );
+        let interval = format!(
+            r#"
+            SELECT jsonb_build_array(jsonb_build_array(
+                to_char(min(lower(r)) AT TIME ZONE 'UTC', '{TIMESTAMP_FORMAT}'),
+                to_char(max(upper(r)) AT TIME ZONE 'UTC', '{TIMESTAMP_FORMAT}')
+            ))
+            FROM (SELECT public.item_datetime(properties) AS r FROM {table}) t
+            WHERE NOT lower_inf(r)
+            HAVING count(*) > 0
+            "#
+        );
+
+        let mut tx = self.pool.begin().await?;
+
+        for sql in [
+            self.update_extent("spatial", "bbox", &bbox),
+            self.update_extent("temporal", "interval", &interval),
+        ] {
+            sqlx::query(&sql).bind(id).execute(&mut tx).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+impl Db {
+    /// Reads a collection which is required to exist
+    pub(crate) async fn existing_collection(&self, id: &str) -> anyhow::Result<Collection> {
+        match self.read_collection(id).await? {
+            Some(collection) => Ok(collection),
+            None => Err(Error::NotFound(format!("No collection with id `{id}`")).into()),
+        }
+    }
+
+    /// Creates the index on the temporal extent of the items of a collection,
+    /// which is used by `datetime` filters
+    pub async fn create_datetime_index<'c, E>(
+        &self,
+        executor: E,
+        collection: &str,
+    ) -> anyhow::Result<()>
+    where
+        E: sqlx::PgExecutor<'c>,
+    {
+        sqlx::query(&format!(
+            r#"CREATE INDEX IF NOT EXISTS "{collection}_datetime_idx" ON "{}"."{collection}" USING gist (public.item_datetime(properties))"#,
+            self.items_schema()
+        ))
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Grows the spatial extent of a collection to include a feature
+    ///
+    /// The extent is only ever expanded, so it stays valid but may be larger
+    /// than needed after features were moved or deleted until the next
+    /// `refresh_extent`. The update is applied to the locked row, concurrent
+    /// inserts are not lost.
+    pub(crate) async fn expand_extent<'c, E>(
+        &self,
+        executor: E,
+        collection: &Collection,
+        id: &str,
+    ) -> anyhow::Result<()>
+    where
+        E: sqlx::PgExecutor<'c>,
+    {
+        let geom = quote_ident(geometry_column(collection));
+        let table = self.items_table(collection);
+
+        let bbox = extent_bbox(
+            &format!(
+                r#"
+                SELECT ST_Transform({geom}, 4326) AS g FROM {table} WHERE id = $2
+                UNION ALL
+                {PRIOR_EXTENT}
+                "#
+            ),
+            true,
+        );
+
+        sqlx::query(&self.update_extent("spatial", "bbox", &bbox))
+            .bind(&collection.id)
+            .bind(id)
+            .execute(executor)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns the statement setting the `key` of the `member` of the extent
+    /// of the collection `$1` to the result of the query `value`, creating
+    /// missing objects on the way
+    ///
+    /// The stored value is kept if the query returns no row or `NULL`.
+    fn update_extent(&self, member: &str, key: &str, value: &str) -> String {
+        format!(
+            r#"
+            UPDATE {meta}
+            SET collection = jsonb_set(
+                jsonb_set(
+                    jsonb_set(
+                        collection, '{{extent}}',
+                        COALESCE(NULLIF(collection -> 'extent', 'null'), '{{}}'), true
+                    ),
+                    '{{extent,{member}}}',
+                    COALESCE(NULLIF(collection -> 'extent' -> '{member}', 'null'), '{{}}'), true
+                ),
+                '{{extent,{member},{key}}}',
+                COALESCE(({value}), collection #> '{{extent,{member},{key}}}', '[]'),
+                true
+            )
+            WHERE id = $1
+            "#,
+            meta = self.meta_table("collections")
+        )
+    }
 }
@@ -0,0 +1,889 @@
+//! Translation of CQL2 filters into SQL conditions
+//!
+//! Supports the logical operators, comparisons of properties with literals,
+//! the array predicates like `A_CONTAINS` and the `S_INTERSECTS` spatial
+//! predicate, both in text and JSON encoding. Nested properties are addressed
+//! by their dot separated path, like `properties.address.city`.
+//!
+//! Membership of a single value in an array property can also be written as
+//! `'value' IN tags`, a shorthand of `A_CONTAINS(tags, ('value'))`.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use ogcapi_types::features::FilterLang;
+
+use crate::Error;
+
+use super::{quote_ident, stored_name};
+
+/// Collection specific context of a translation
+pub(crate) struct Context<'a> {
+    /// Name of the geometry column
+    pub(crate) geom: &'a str,
+    /// Names the properties are exposed under, keyed by their stored name
+    pub(crate) property_names: Option<&'a HashMap<String, String>>,
+    /// SRID of the stored geometries
+    pub(crate) storage_srid: i32,
+    /// SRID of the filter geometries, declared by `filter-crs`
+    pub(crate) filter_srid: i32,
+}
+
+/// Translates a filter into a SQL condition on the items table
+pub(crate) fn to_sql(filter: &str, lang: &FilterLang, context: &Context) -> Result<String, Error> {
+    let expr = match lang {
+        FilterLang::CqlText => Parser::new(filter)?.parse()?,
+        FilterLang::CqlJson => {
+            let value: Value = serde_json::from_str(filter).map_err(invalid)?;
+            Expr::from_json(&value)?
+        }
+    };
+    expr.to_sql(context)
+}
+
+fn invalid(e: impl std::fmt::Display) -> Error {
+    Error::Invalid(format!("Invalid filter: {e}"))
+}
+
+/// Filter expression
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+    Not(Box<Expr>),
+    Comparison {
+        op: Op,
+        property: String,
+        value: Value,
+    },
+    Array {
+        op: ArrayOp,
+        property: String,
+        values: Vec<Value>,
+    },
+    Intersects {
+        property: String,
+        geometry: Geometry,
+    },
+}
+
+/// Comparison operator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Op {
+    fn parse(op: &str) -> Option<Op> {
+        match op {
+            "=" => Some(Op::Eq),
+            "<>" | "!=" => Some(Op::Ne),
+            "<" => Some(Op::Lt),
+            "<=" => Some(Op::Le),
+            ">" => Some(Op::Gt),
+            ">=" => Some(Op::Ge),
+            _ => None,
+        }
+    }
+
+    fn sql(self) -> &'static str {
+        match self {
+            Op::Eq => "=",
+            Op::Ne => "<>",
+            Op::Lt => "<",
+            Op::Le => "<=",
+            Op::Gt => ">",
+            Op::Ge => ">=",
+        }
+    }
+}
+
+/// Array operator, comparing an array property with an array literal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArrayOp {
+    Equals,
+    Contains,
+    ContainedBy,
+    Overlaps,
+}
+
+impl ArrayOp {
+    fn parse(op: &str) -> Option<ArrayOp> {
+        match op.to_lowercase().as_str() {
+            "a_equals" => Some(ArrayOp::Equals),
+            "a_contains" => Some(ArrayOp::Contains),
+            "a_containedby" => Some(ArrayOp::ContainedBy),
+            "a_overlaps" => Some(ArrayOp::Overlaps),
+            _ => None,
+        }
+    }
+}
+
+/// Geometry literal
+#[derive(Debug, Clone, PartialEq)]
+enum Geometry {
+    /// Well-known text, only made up of keywords, numbers and punctuation
+    Wkt(String),
+    GeoJson(geojson::Geometry),
+}
+
+impl Expr {
+    fn from_json(value: &Value) -> Result<Expr, Error> {
+        let op = value["op"]
+            .as_str()
+            .ok_or_else(|| invalid("expected an object with `op`"))?
+            .to_lowercase();
+        let args = value["args"]
+            .as_array()
+            .ok_or_else(|| invalid(format!("expected `args` of `{op}`")))?;
+
+        match op.as_str() {
+            "and" | "or" if !args.is_empty() => {
+                let args = args
+                    .iter()
+                    .map(Expr::from_json)
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(if op == "and" {
+                    Expr::And(args)
+                } else {
+                    Expr::Or(args)
+                })
+            }
+            "not" if args.len() == 1 => Ok(Expr::Not(Box::new(Expr::from_json(&args[0])?))),
+            "s_intersects" if args.len() == 2 => Ok(Expr::Intersects {
+                property: json_property(&args[0])?,
+                geometry: Geometry::GeoJson(
+                    serde_json::from_value(args[1].to_owned()).map_err(invalid)?,
+                ),
+            }),
+            op => match (Op::parse(op), ArrayOp::parse(op)) {
+                (Some(op), _) if args.len() == 2 => Ok(Expr::Comparison {
+                    op,
+                    property: json_property(&args[0])?,
+                    value: literal(args[1].to_owned())?,
+                }),
+                (_, Some(op)) if args.len() == 2 => Ok(Expr::Array {
+                    op,
+                    property: json_property(&args[0])?,
+                    values: args[1]
+                        .as_array()
+                        .ok_or_else(|| invalid(format!("expected an array literal of `{op:?}`")))?
+                        .iter()
+                        .cloned()
+                        .map(literal)
+                        .collect::<Result<_, _>>()?,
+                }),
+                _ => Err(invalid(format!("unsupported operator `{op}`"))),
+            },
+        }
+    }
+
+    fn to_sql(&self, context: &Context) -> Result<String, Error> {
+        let join = |exprs: &[Expr], separator: &str| -> Result<String, Error> {
+            let conditions = exprs
+                .iter()
+                .map(|e| e.to_sql(context))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(format!("({})", conditions.join(separator)))
+        };
+
+        match self {
+            Expr::And(exprs) => join(exprs, " AND "),
+            Expr::Or(exprs) => join(exprs, " OR "),
+            Expr::Not(expr) => Ok(format!("NOT ({})", expr.to_sql(context)?)),
+            Expr::Comparison {
+                op,
+                property,
+                value,
+            } => Ok(format!(
+                "({}) {} '{}'::jsonb",
+                property_sql(property, context),
+                op.sql(),
+                value.to_string().replace('\'', "''")
+            )),
+            Expr::Array {
+                op,
+                property,
+                values,
+            } => {
+                let property = property_sql(property, context);
+                let array = Value::from(values.to_owned())
+                    .to_string()
+                    .replace('\'', "''");
+                Ok(match op {
+                    ArrayOp::Equals => format!("({property}) = '{array}'::jsonb"),
+                    ArrayOp::Contains => format!("({property}) @> '{array}'::jsonb"),
+                    ArrayOp::ContainedBy => format!(
+                        "(jsonb_typeof({property}) = 'array' AND ({property}) <@ '{array}'::jsonb)"
+                    ),
+                    ArrayOp::Overlaps => format!(
+                        "(jsonb_typeof({property}) = 'array' AND EXISTS (SELECT FROM jsonb_array_elements({property}) AS e WHERE '{array}'::jsonb @> jsonb_build_array(e)))"
+                    ),
+                })
+            }
+            Expr::Intersects { property, geometry } => {
+                if property != "geometry" && property != context.geom {
+                    return Err(invalid(format!("`{property}` is not a spatial property")));
+                }
+                let geometry = match geometry {
+                    Geometry::Wkt(wkt) => format!("ST_GeomFromText('{wkt}')"),
+                    Geometry::GeoJson(geometry) => format!(
+                        "ST_GeomFromGeoJSON('{}')",
+                        geometry.to_string().replace('\'', "''")
+                    ),
+                };
+                Ok(format!(
+                    "ST_Intersects({}, ST_Transform(ST_SetSRID({geometry}, {}), {}))",
+                    quote_ident(context.geom),
+                    context.filter_srid,
+                    context.storage_srid
+                ))
+            }
+        }
+    }
+}
+
+/// Accessor of a property by its stored name, nested ones by their path
+fn property_sql(property: &str, context: &Context) -> String {
+    let property = property.strip_prefix("properties.").unwrap_or(property);
+    let path: Vec<String> = match property.split_once('.') {
+        Some((key, rest)) => std::iter::once(stored_name(context.property_names, key))
+            .chain(rest.split('.'))
+            .collect::<Vec<&str>>(),
+        None => vec![stored_name(context.property_names, property)],
+    }
+    .into_iter()
+    .map(|key| format!("'{}'", key.replace('\'', "''")))
+    .collect();
+
+    match &path[..] {
+        [key] => format!("properties -> {key}"),
+        path => format!("properties #> ARRAY[{}]", path.join(",")),
+    }
+}
+
+fn json_property(value: &Value) -> Result<String, Error> {
+    value["property"]
+        .as_str()
+        .map(ToOwned::to_owned)
+        .ok_or_else(|| invalid("expected a `property` as first argument"))
+}
+
+fn literal(value: Value) -> Result<Value, Error> {
+    match value {
+        Value::String(_) | Value::Number(_) | Value::Bool(_) => Ok(value),
+        value => Err(invalid(format!("unsupported literal `{value}`"))),
+    }
+}
+
+/// Token of the text encoding
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    String(String),
+    Number(serde_json::Number),
+    Op(String),
+    LParen,
+    RParen,
+    Comma,
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, Error> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' | ')' | ',' => {
+                chars.next();
+                tokens.push(match c {
+                    '(' => Token::LParen,
+                    ')' => Token::RParen,
+                    _ => Token::Comma,
+                });
+            }
+            '\'' => {
+                chars.next();
+                let mut string = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\'') if chars.peek() == Some(&'\'') => {
+                            chars.next();
+                            string.push('\'');
+                        }
+                        Some('\'') => break,
+                        Some(c) => string.push(c),
+                        None => return Err(invalid("unterminated string")),
+                    }
+                }
+                tokens.push(Token::String(string));
+            }
+            '"' => {
+                chars.next();
+                let ident: String = chars.by_ref().take_while(|c| *c != '"').collect();
+                tokens.push(Token::Ident(ident));
+            }
+            '=' | '<' | '>' | '!' => {
+                let mut op = String::from(c);
+                chars.next();
+                if let Some(&next) = chars.peek() {
+                    if matches!((c, next), ('<', '=' | '>') | ('>' | '!', '=')) {
+                        op.push(next);
+                        chars.next();
+                    }
+                }
+                tokens.push(Token::Op(op));
+            }
+            c if c.is_ascii_digit() || c == '-' || c == '.' => {
+                let mut number = String::new();
+                while let Some(&c) = chars.peek() {
+                    let sign = matches!(c, '+' | '-')
+                        && (number.is_empty() || number.ends_with(['e', 'E']));
+                    if c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E') || sign {
+                        number.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Number(number_literal(&number)?));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut ident = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_alphanumeric() || matches!(c, '_' | '.' | ':') {
+                        ident.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Ident(ident));
+            }
+            c => return Err(invalid(format!("unexpected character `{c}`"))),
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Parses a number, integers are kept exact instead of rounding them to the
+/// nearest float
+fn number_literal(number: &str) -> Result<serde_json::Number, Error> {
+    let error = || invalid(format!("invalid number `{number}`"));
+    if !number.contains(['.', 'e', 'E']) {
+        if let Ok(n) = number.parse::<i64>() {
+            return Ok(n.into());
+        }
+        if let Ok(n) = number.parse::<u64>() {
+            return Ok(n.into());
+        }
+    }
+    number
+        .parse()
+        .ok()
+        .and_then(serde_json::Number::from_f64)
+        .ok_or_else(error)
+}
+
+/// Maximum nesting of parentheses, `NOT` and geometry collections in the
+/// text encoding, deeper filters are rejected before exhausting the stack
+const MAX_DEPTH: usize = 64;
+
+/// Recursive descent parser of the text encoding
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn new(text: &str) -> Result<Parser, Error> {
+        Ok(Parser {
+            tokens: tokenize(text)?,
+            position: 0,
+            depth: 0,
+        })
+    }
+
+    /// Enters a nested expression, the caller decrements the depth again
+    fn descend(&mut self) -> Result<(), Error> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(invalid(format!("nested deeper than {MAX_DEPTH} levels")));
+        }
+        Ok(())
+    }
+
+    fn parse(mut self) -> Result<Expr, Error> {
+        let expr = self.or()?;
+        match self.next() {
+            None => Ok(expr),
+            Some(token) => Err(invalid(format!("unexpected {token:?}"))),
+        }
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Ident(ident)) if ident.eq_ignore_ascii_case(keyword) => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), Error> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            token => Err(invalid(format!("expected {expected:?}, found {token:?}"))),
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, Error> {
+        let mut exprs = vec![self.and()?];
+        while self.keyword("OR") {
+            exprs.push(self.and()?);
+        }
+        Ok(if exprs.len() == 1 {
+            exprs.remove(0)
+        } else {
+            Expr::Or(exprs)
+        })
+    }
+
+    fn and(&mut self) -> Result<Expr, Error> {
+        let mut exprs = vec![self.not()?];
+        while self.keyword("AND") {
+            exprs.push(self.not()?);
+        }
+        Ok(if exprs.len() == 1 {
+            exprs.remove(0)
+        } else {
+            Expr::And(exprs)
+        })
+    }
+
+    fn not(&mut self) -> Result<Expr, Error> {
+        if self.keyword("NOT") {
+            self.descend()?;
+            let expr = self.not()?;
+            self.depth -= 1;
+            Ok(Expr::Not(Box::new(expr)))
+        } else {
+            self.predicate()
+        }
+    }
+
+    fn predicate(&mut self) -> Result<Expr, Error> {
+        match self.next() {
+            Some(Token::LParen) => {
+                self.descend()?;
+                let expr = self.or()?;
+                self.depth -= 1;
+                self.expect(Token::RParen)?;
+                Ok(expr)
+            }
+            Some(Token::Ident(ident)) if ident.eq_ignore_ascii_case("S_INTERSECTS") => {
+                self.expect(Token::LParen)?;
+                let property = self.property()?;
+                self.expect(Token::Comma)?;
+                let geometry = self.wkt()?;
+                self.expect(Token::RParen)?;
+                Ok(Expr::Intersects { property, geometry })
+            }
+            Some(Token::Ident(ident)) if ArrayOp::parse(&ident).is_some() => {
+                let op = ArrayOp::parse(&ident).unwrap();
+                self.expect(Token::LParen)?;
+                let property = self.property()?;
+                self.expect(Token::Comma)?;
+                let values = self.array()?;
+                self.expect(Token::RParen)?;
+                Ok(Expr::Array {
+                    op,
+                    property,
+                    values,
+                })
+            }
+            Some(Token::String(_) | Token::Number(_)) => {
+                self.position -= 1;
+                let value = self.literal()?;
+                if !self.keyword("IN") {
+                    return Err(invalid("expected `IN` after a literal"));
+                }
+                Ok(Expr::Array {
+                    op: ArrayOp::Contains,
+                    property: self.property()?,
+                    values: vec![value],
+                })
+            }
+            Some(Token::Ident(property)) => {
+                let op = match self.next() {
+                    Some(Token::Op(op)) => Op::parse(&op),
+                    _ => None,
+                }
+                .ok_or_else(|| invalid(format!("expected comparison of `{property}`")))?;
+                let value = self.literal()?;
+                Ok(Expr::Comparison {
+                    op,
+                    property,
+                    value,
+                })
+            }
+            token => Err(invalid(format!("unexpected {token:?}"))),
+        }
+    }
+
+    fn property(&mut self) -> Result<String, Error> {
+        match self.next() {
+            Some(Token::Ident(property)) => Ok(property),
+            token => Err(invalid(format!("expected property, found {token:?}"))),
+        }
+    }
+
+    fn literal(&mut self) -> Result<Value, Error> {
+        match self.next() {
+            Some(Token::String(s)) => Ok(Value::String(s)),
+            Some(Token::Number(n)) => Ok(Value::Number(n)),
+            Some(Token::Ident(b)) if b.eq_ignore_ascii_case("TRUE") => Ok(Value::Bool(true)),
+            Some(Token::Ident(b)) if b.eq_ignore_ascii_case("FALSE") => Ok(Value::Bool(false)),
+            token => Err(invalid(format!("expected literal, found {token:?}"))),
+        }
+    }
+
+    /// Parenthesized list of literals
+    fn array(&mut self) -> Result<Vec<Value>, Error> {
+        self.expect(Token::LParen)?;
+        let mut values = Vec::new();
+        if self.peek() != Some(&Token::RParen) {
+            values.push(self.literal()?);
+            while self.peek() == Some(&Token::Comma) {
+                self.position += 1;
+                values.push(self.literal()?);
+            }
+        }
+        self.expect(Token::RParen)?;
+        Ok(values)
+    }
+
+    /// Reassembles a geometry in well-known text from its tokens, checking
+    /// the nesting of its coordinates
+    fn wkt(&mut self) -> Result<Geometry, Error> {
+        let mut wkt = String::new();
+        self.wkt_geometry(&mut wkt)?;
+        Ok(Geometry::Wkt(wkt))
+    }
+
+    fn wkt_geometry(&mut self, wkt: &mut String) -> Result<(), Error> {
+        let kind = match self.next() {
+            Some(Token::Ident(kind)) => kind.to_uppercase(),
+            token => return Err(invalid(format!("expected geometry, found {token:?}"))),
+        };
+        // levels of parentheses around the positions
+        let levels = match kind.as_str() {
+            "POINT" | "LINESTRING" | "MULTIPOINT" => 1,
+            "POLYGON" | "MULTILINESTRING" => 2,
+            "MULTIPOLYGON" => 3,
+            "GEOMETRYCOLLECTION" => 0,
+            kind => return Err(invalid(format!("unknown geometry type `{kind}`"))),
+        };
+        wkt.push_str(&kind);
+
+        for modifier in ["ZM", "Z", "M"] {
+            if self.keyword(modifier) {
+                wkt.push(' ');
+                wkt.push_str(modifier);
+                break;
+            }
+        }
+        if self.keyword("EMPTY") {
+            wkt.push_str(" EMPTY");
+            return Ok(());
+        }
+
+        if levels == 0 {
+            self.descend()?;
+            self.wkt_list(wkt, |parser, wkt| parser.wkt_geometry(wkt))?;
+            self.depth -= 1;
+            return Ok(());
+        }
+
+        let positions = self.wkt_coordinates(wkt, levels, kind == "MULTIPOINT")?;
+        if kind == "POINT" && positions != 1 {
+            return Err(invalid("a point has exactly one position"));
+        }
+        Ok(())
+    }
+
+    /// Positions nested in `levels` parenthesized lists, returns their number
+    fn wkt_coordinates(
+        &mut self,
+        wkt: &mut String,
+        levels: usize,
+        multipoint: bool,
+    ) -> Result<usize, Error> {
+        let mut positions = 0;
+        if levels == 0 {
+            // members of multi points may be parenthesized
+            if multipoint && self.peek() == Some(&Token::LParen) {
+                self.wkt_list(wkt, |parser, wkt| parser.wkt_position(wkt))?;
+            } else {
+                self.wkt_position(wkt)?;
+            }
+            return Ok(1);
+        }
+        self.wkt_list(wkt, |parser, wkt| {
+            positions += parser.wkt_coordinates(wkt, levels - 1, multipoint)?;
+            Ok(())
+        })?;
+        Ok(positions)
+    }
+
+    /// Parenthesized, comma separated list of elements
+    fn wkt_list(
+        &mut self,
+        wkt: &mut String,
+        mut element: impl FnMut(&mut Parser, &mut String) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        self.expect(Token::LParen)?;
+        wkt.push('(');
+        element(self, wkt)?;
+        while self.peek() == Some(&Token::Comma) {
+            self.position += 1;
+            wkt.push(',');
+            element(self, wkt)?;
+        }
+        self.expect(Token::RParen)?;
+        wkt.push(')');
+        Ok(())
+    }
+
+    /// Two to four space separated coordinates
+    fn wkt_position(&mut self, wkt: &mut String) -> Result<(), Error> {
+        let mut coordinates = Vec::new();
+        while let Some(Token::Number(n)) = self.peek() {
+            coordinates.push(n.to_string());
+            self.position += 1;
+        }
+        if !(2..=4).contains(&coordinates.len()) {
+            return Err(invalid(format!(
+                "a position has two to four coordinates, found {}",
+                coordinates.len()
+            )));
+        }
+        wkt.push_str(&coordinates.join(" "));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTEXT: Context = Context {
+        geom: "geom",
+        property_names: None,
+        storage_srid: 4326,
+        filter_srid: 3857,
+    };
+
+    #[test]
+    fn text_comparison() {
+        let sql = to_sql(
+            "name = 'O''Brien' AND (height > -1.5e1 OR NOT open = true)",
+            &FilterLang::CqlText,
+            &CONTEXT,
+        )
+        .unwrap();
+        assert_eq!(
+            sql,
+            r#"((properties -> 'name') = '"O''Brien"'::jsonb AND ((properties -> 'height') > '-15.0'::jsonb OR NOT ((properties -> 'open') = 'true'::jsonb)))"#
+        );
+    }
+
+    #[test]
+    fn property_names() {
+        let names = HashMap::from([("addr".to_string(), "address".to_string())]);
+        let context = Context {
+            property_names: Some(&names),
+            ..CONTEXT
+        };
+        let sql = to_sql(
+            "address.city = 'Bern' AND A_CONTAINS(address, ('x'))",
+            &FilterLang::CqlText,
+            &context,
+        )
+        .unwrap();
+        assert_eq!(
+            sql,
+            r#"((properties #> ARRAY['addr','city']) = '"Bern"'::jsonb AND (properties -> 'addr') @> '["x"]'::jsonb)"#
+        );
+    }
+
+    #[test]
+    fn nested_property() {
+        let sql = to_sql(
+            "properties.address.city = 'Bern' AND properties.name <> 'x'",
+            &FilterLang::CqlText,
+            &CONTEXT,
+        )
+        .unwrap();
+        assert_eq!(
+            sql,
+            r#"((properties #> ARRAY['address','city']) = '"Bern"'::jsonb AND (properties -> 'name') <> '"x"'::jsonb)"#
+        );
+
+        let filter = r#"{ "op": ">", "args": [{ "property": "address.zip" }, 3000] }"#;
+        let sql = to_sql(filter, &FilterLang::CqlJson, &CONTEXT).unwrap();
+        assert_eq!(
+            sql,
+            r#"(properties #> ARRAY['address','zip']) > '3000'::jsonb"#
+        );
+    }
+
+    #[test]
+    fn array_predicates() {
+        let sql = to_sql(
+            "A_CONTAINS(tags, ('red', 'blue')) OR 'green' IN properties.tags",
+            &FilterLang::CqlText,
+            &CONTEXT,
+        )
+        .unwrap();
+        assert_eq!(
+            sql,
+            r#"((properties -> 'tags') @> '["red","blue"]'::jsonb OR (properties -> 'tags') @> '["green"]'::jsonb)"#
+        );
+
+        let filter = r#"{ "op": "a_containedby", "args": [{ "property": "tags" }, ["a", 1]] }"#;
+        let sql = to_sql(filter, &FilterLang::CqlJson, &CONTEXT).unwrap();
+        assert_eq!(
+            sql,
+            r#"(jsonb_typeof(properties -> 'tags') = 'array' AND (properties -> 'tags') <@ '["a",1]'::jsonb)"#
+        );
+
+        for filter in [
+            "A_CONTAINS(tags, 'red')",
+            "'red' = tags",
+            "A_EQUALS(tags, (x))",
+        ] {
+            assert!(
+                to_sql(filter, &FilterLang::CqlText, &CONTEXT).is_err(),
+                "{filter}"
+            );
+        }
+    }
+
+    #[test]
+    fn text_intersects() {
+        let sql = to_sql(
+            "S_INTERSECTS(geometry, POLYGON((0 0, 1 0, 1 1, 0 0)))",
+            &FilterLang::CqlText,
+            &CONTEXT,
+        )
+        .unwrap();
+        assert_eq!(
+            sql,
+            r#"ST_Intersects("geom", ST_Transform(ST_SetSRID(ST_GeomFromText('POLYGON((0 0,1 0,1 1,0 0))'), 3857), 4326))"#
+        );
+    }
+
+    #[test]
+    fn json_intersects() {
+        let filter = r#"{
+            "op": "s_intersects",
+            "args": [
+                { "property": "geom" },
+                { "type": "Point", "coordinates": [1.0, 2.0] }
+            ]
+        }"#;
+        let sql = to_sql(filter, &FilterLang::CqlJson, &CONTEXT).unwrap();
+        assert!(
+            sql.starts_with(r#"ST_Intersects("geom", ST_Transform(ST_SetSRID(ST_GeomFromGeoJSON("#)
+        );
+        assert!(sql.ends_with("3857), 4326))"));
+    }
+
+    #[test]
+    fn invalid_filters() {
+        for filter in [
+            "name = ",
+            "name LIKE 'a'",
+            "S_INTERSECTS(name, POINT(0 0))",
+            "S_INTERSECTS(geom, POINT(0 0'); DROP TABLE x; --",
+            "S_INTERSECTS(geom, POINT(1))",
+            "S_INTERSECTS(geom, POINT(0 0, 1 1))",
+            "S_INTERSECTS(geom, POLYGON(0 0, 1 0, 1 1, 0 0))",
+            "S_INTERSECTS(geom, CIRCLE(0 0))",
+            "a = 1)",
+        ] {
+            assert!(
+                to_sql(filter, &FilterLang::CqlText, &CONTEXT).is_err(),
+                "{filter}"
+            );
+        }
+    }
+
+    #[test]
+    fn wkt_geometries() {
+        for (wkt, expected) in [
+            ("POINT Z (1 2 3)", "POINT Z(1 2 3)"),
+            ("MULTIPOINT((0 0), (1 1))", "MULTIPOINT((0 0),(1 1))"),
+            ("MULTIPOINT(0 0, 1 1)", "MULTIPOINT(0 0,1 1)"),
+            ("LINESTRING EMPTY", "LINESTRING EMPTY"),
+            (
+                "GEOMETRYCOLLECTION(POINT(1 2), LINESTRING(0 0, 1.5 1))",
+                "GEOMETRYCOLLECTION(POINT(1 2),LINESTRING(0 0,1.5 1))",
+            ),
+        ] {
+            let sql = to_sql(
+                &format!("S_INTERSECTS(geom, {wkt})"),
+                &FilterLang::CqlText,
+                &CONTEXT,
+            )
+            .unwrap();
+            assert!(
+                sql.contains(&format!("ST_GeomFromText('{expected}')")),
+                "{sql}"
+            );
+        }
+    }
+
+    #[test]
+    fn integer_literals() {
+        let sql = to_sql("id = 9007199254740993", &FilterLang::CqlText, &CONTEXT).unwrap();
+        assert_eq!(sql, "(properties -> 'id') = '9007199254740993'::jsonb");
+    }
+
+    #[test]
+    fn nesting_depth() {
+        for filter in [
+            format!("{}a = 1", "NOT ".repeat(100_000)),
+            format!("{}a = 1{}", "(".repeat(100_000), ")".repeat(100_000)),
+            format!("(((a = 1))) AND {}b = 2", "NOT ".repeat(MAX_DEPTH + 1)),
+        ] {
+            assert!(to_sql(&filter, &FilterLang::CqlText, &CONTEXT).is_err());
+        }
+
+        let filter = format!("{}a = 1", "NOT ".repeat(MAX_DEPTH));
+        assert!(to_sql(&filter, &FilterLang::CqlText, &CONTEXT).is_ok());
+    }
+}
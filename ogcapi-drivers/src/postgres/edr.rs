@@ -1,25 +1,27 @@
 use ogcapi_types::{
-    edr::{Query, QueryType},
+    edr::{Instance, Query, QueryType},
     features::{Feature, FeatureCollection},
 };
 use sqlx::types::Json;
 
 use crate::{CollectionTransactions, EdrQuerier};
 
-use super::Db;
+use super::{geometry_column, quote_ident, quote_literal, retry, Db};
 
 #[async_trait::async_trait]
 impl EdrQuerier for Db {
     async fn query(
         &self,
         collection_id: &str,
+        instance_id: Option<&str>,
         query_type: &QueryType,
         query: &Query,
     ) -> anyhow::Result<FeatureCollection> {
         let srid: i32 = query.crs.as_srid();
 
-        let c = self.read_collection(collection_id).await?;
-        let storage_srid = c.unwrap().storage_crs.unwrap_or_default().as_srid();
+        let c = self.read_collection(collection_id).await?.unwrap();
+        let storage_srid = c.storage_crs.clone().unwrap_or_default().as_srid();
+        let geom = quote_ident(geometry_column(&c));
 
         let mut geometry_type = query.coords.split('(').next().unwrap().to_uppercase();
         geometry_type.retain(|c| !c.is_whitespace());
@@ -28,12 +30,12 @@ impl EdrQuerier for Db {
             QueryType::Position | QueryType::Area | QueryType::Trajectory => {
                 if geometry_type.ends_with('Z') || geometry_type.ends_with('M') {
                     format!(
-                        "ST_3DIntersects(geom, ST_Transform(ST_GeomFromEWKT('SRID={};{}'), {}))",
+                        "ST_3DIntersects({geom}, ST_Transform(ST_GeomFromEWKT('SRID={};{}'), {}))",
                         srid, query.coords, storage_srid
                     )
                 } else {
                     format!(
-                        "ST_Intersects(geom, ST_Transform(ST_GeomFromEWKT('SRID={};{}'), {}))",
+                        "ST_Intersects({geom}, ST_Transform(ST_GeomFromEWKT('SRID={};{}'), {}))",
                         srid, query.coords, storage_srid
                     )
                 }
@@ -56,12 +58,12 @@ impl EdrQuerier for Db {
 
                 if geometry_type.ends_with('Z') || geometry_type.ends_with('M') {
                     format!(
-                        "ST_3DDWithin(geom, ST_Transform(ST_GeomFromEWKT('SRID={};{}'), {}))",
+                        "ST_3DDWithin({geom}, ST_Transform(ST_GeomFromEWKT('SRID={};{}'), {}))",
                         srid, query.coords, storage_srid
                     )
                 } else {
                     format!(
-                    "ST_DWithin(ST_Transform(geom, 4326)::geography, ST_Transform(ST_GeomFromEWKT('SRID={};{}'), 4326)::geography, {}, false)",
+                    "ST_DWithin(ST_Transform({geom}, 4326)::geography, ST_Transform(ST_GeomFromEWKT('SRID={};{}'), 4326)::geography, {}, false)",
                     srid, query.coords, distance
                 )
                 }
@@ -70,13 +72,13 @@ impl EdrQuerier for Db {
                 let bbox: Vec<&str> = query.coords.split(',').collect();
                 if bbox.len() == 4 {
                     format!(
-                        "ST_Intersects(geom, ST_Transform(ST_MakeEnvelope({}, {}), {})",
+                        "ST_Intersects({geom}, ST_Transform(ST_MakeEnvelope({}, {}), {})",
                         query.coords, srid, storage_srid
                     )
                 } else {
                     format!(
                         "ST_3DIntersects(
-                            geom,
+                            {geom},
                             ST_Transform(
                                 ST_SetSRID(
                                     ST_3DMakeBox(ST_MakePoint({}, {}, {}), ST_MakePoint({} , {}, {})),
@@ -113,18 +115,23 @@ impl EdrQuerier for Db {
             SELECT
                 id,
                 {1},
-                ST_AsGeoJSON(ST_Transform(geom, $1))::jsonb as geometry,
+                ST_AsGeoJSON(ST_Transform({3}, $1))::jsonb as geometry,
                 links,
-                '{0}' as collection,
+                {0} as collection,
                 assets
-            FROM items."{0}"
-            WHERE {2}
+            FROM {4} items
+            WHERE {2} AND ($2::text IS NULL OR properties ->> 'instance' = $2)
             "#,
-            collection_id, properties, spatial_predicate
+            quote_literal(collection_id),
+            properties,
+            spatial_predicate,
+            geom,
+            self.items_table(&c)
         );
 
         let number_matched = sqlx::query(&sql)
             .bind(srid)
+            .bind(instance_id)
             .execute(&self.pool)
             .await?
             .rows_affected();
@@ -137,6 +144,7 @@ impl EdrQuerier for Db {
             sql
         ))
         .bind(srid)
+        .bind(instance_id)
         .fetch_one(&self.pool)
         .await?;
 
@@ -146,4 +154,59 @@ impl EdrQuerier for Db {
 
         Ok(fc)
     }
+
+    async fn create_instance(
+        &self,
+        collection_id: &str,
+        instance: &Instance,
+    ) -> anyhow::Result<String> {
+        let (id,): (String,) = sqlx::query_as(&format!(
+            "INSERT INTO {} (collection, id, instance) VALUES ($1, $2, $3) RETURNING id",
+            self.meta_table("instances")
+        ))
+        .bind(collection_id)
+        .bind(&instance.id)
+        .bind(Json(instance))
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(id)
+    }
+
+    async fn read_instance(
+        &self,
+        collection_id: &str,
+        instance_id: &str,
+    ) -> anyhow::Result<Option<Instance>> {
+        let sql = format!(
+            "SELECT instance FROM {} WHERE collection = $1 AND id = $2",
+            self.meta_table("instances")
+        );
+
+        let instance: Option<Json<Instance>> = retry(self.read_attempts, || {
+            sqlx::query_scalar(&sql)
+                .bind(collection_id)
+                .bind(instance_id)
+                .fetch_optional(&self.pool)
+        })
+        .await?;
+
+        Ok(instance.map(|i| i.0))
+    }
+
+    async fn list_instances(&self, collection_id: &str) -> anyhow::Result<Vec<Instance>> {
+        let sql = format!(
+            "SELECT instance FROM {} WHERE collection = $1 ORDER BY id",
+            self.meta_table("instances")
+        );
+
+        let instances: Vec<Json<Instance>> = retry(self.read_attempts, || {
+            sqlx::query_scalar(&sql)
+                .bind(collection_id)
+                .fetch_all(&self.pool)
+        })
+        .await?;
+
+        Ok(instances.into_iter().map(|i| i.0).collect())
+    }
 }
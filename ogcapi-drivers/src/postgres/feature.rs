@@ -1,65 +1,507 @@
+use geojson::{Geometry, Value};
 use ogcapi_types::{
-    common::{Bbox, Crs, Datetime, IntervalDatetime},
-    features::{Feature, FeatureCollection, Query},
+    common::{Bbox, Collection, Crs, Datetime, IdStrategy, IntervalDatetime},
+    features::{AggregationGroup, Feature, FeatureCollection, Nulls, PropertyValue, Query},
+    records::RECORD,
 };
 
-use crate::{CollectionTransactions, FeatureTransactions};
+use crate::{CollectionTransactions, Error, FeatureTransactions};
 
-use super::Db;
+use super::{
+    cql, geometry_column, page_clause, page_params, quote_ident, quote_literal, retry, stored_name,
+    Db,
+};
+
+/// Returns the geometry in the crs given as first parameter as GeoJSON, or an
+/// empty geometry collection without transformation if the geometry is skipped
+fn geometry(geom: &str, skip: bool) -> String {
+    if skip {
+        r#"'{"type": "GeometryCollection", "geometries": []}'::jsonb"#.to_string()
+    } else {
+        format!(r#"ST_AsGeoJSON(ST_Transform({geom}, $1))::jsonb"#)
+    }
+}
 
 #[cfg(not(feature = "stac"))]
-static ROWS: &str = "
-items.id,
-items.collection,
-properties,
-ST_AsGeoJSON(ST_Transform(geom, $1))::jsonb AS geometry,
-links,
-";
+fn rows(geom: &str, skip_geometry: bool) -> String {
+    format!(
+        r#"
+        items.id,
+        items.collection,
+        properties,
+        {} AS geometry,
+        links,
+        version
+        "#,
+        geometry(geom, skip_geometry)
+    )
+}
 
 #[cfg(feature = "stac")]
-static ROWS: &str = "
-items.id,
-items.collection,
-properties,
-ST_AsGeoJSON(ST_Transform(geom, $1))::jsonb AS geometry,
-links,
-meta.collection ->> 'stac_version' AS stac_version,
-COALESCE(
-    (meta.collection -> 'stac_extensions'),
-    '[]'::jsonb
-) AS stac_extensions,
-assets,
-COALESCE(
-    bbox,
-    array_to_json(
-        ARRAY[
-            st_xmin(st_transform(geom, 4326)::box2d),
-            st_ymin(st_transform(geom, 4326)::box2d),
-            st_xmax(st_transform(geom, 4326)::box2d),
-            st_ymax(st_transform(geom, 4326)::box2d)
-        ]
-    )::jsonb
-) as bbox
-";
+fn rows(geom: &str, skip_geometry: bool) -> String {
+    format!(
+        r#"
+        items.id,
+        items.collection,
+        properties,
+        {} AS geometry,
+        links,
+        version,
+        meta.collection ->> 'stac_version' AS stac_version,
+        COALESCE(
+            (meta.collection -> 'stac_extensions'),
+            '[]'::jsonb
+        ) AS stac_extensions,
+        assets,
+        COALESCE(
+            bbox,
+            array_to_json(
+                ARRAY[
+                    st_xmin(st_transform({geom}, 4326)::box2d),
+                    st_ymin(st_transform({geom}, 4326)::box2d),
+                    st_xmax(st_transform({geom}, 4326)::box2d),
+                    st_ymax(st_transform({geom}, 4326)::box2d)
+                ]
+            )::jsonb
+        ) as bbox
+        "#,
+        geometry(geom, skip_geometry)
+    )
+}
+
+/// Checks that all coordinates of a geometry are finite and, for geographic
+/// storage CRS, within the valid longitude and latitude range
+fn validate_geometry(geometry: &Geometry, srid: i32) -> Result<(), Error> {
+    let positions: Vec<&Vec<f64>> = match &geometry.value {
+        Value::Point(position) => vec![position],
+        Value::MultiPoint(positions) | Value::LineString(positions) => positions.iter().collect(),
+        Value::MultiLineString(lines) | Value::Polygon(lines) => lines.iter().flatten().collect(),
+        Value::MultiPolygon(polygons) => polygons.iter().flatten().flatten().collect(),
+        Value::GeometryCollection(geometries) => {
+            return geometries
+                .iter()
+                .try_for_each(|geometry| validate_geometry(geometry, srid))
+        }
+    };
+
+    for position in positions {
+        if position.iter().any(|c| !c.is_finite()) {
+            return Err(Error::Invalid(format!(
+                "Coordinate `{:?}` is not finite",
+                position
+            )));
+        }
+
+        if let (4326 | 4979, [x, y, ..]) = (srid, position.as_slice()) {
+            if !(-180.0..=180.0).contains(x) {
+                return Err(Error::Invalid(format!(
+                    "Longitude `{x}` is outside of [-180, 180]"
+                )));
+            }
+            if !(-90.0..=90.0).contains(y) {
+                return Err(Error::Invalid(format!(
+                    "Latitude `{y}` is outside of [-90, 90]"
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Geometry of the bound feature, snapped to the grid of the collection
+fn geometry_sql(c: &Collection) -> String {
+    match c.grid_size {
+        Some(size) if size > 0.0 => {
+            format!("ST_SnapToGrid(ST_GeomFromGeoJSON($1 -> 'geometry'), {size})")
+        }
+        _ => "ST_GeomFromGeoJSON($1 -> 'geometry')".to_string(),
+    }
+}
+
+/// Name a stored property is exposed under
+fn exposed_name(c: &Collection, name: String) -> String {
+    match c.property_names.as_ref().and_then(|names| names.get(&name)) {
+        Some(exposed) => exposed.to_owned(),
+        None => name,
+    }
+}
+
+/// Renames the properties of a feature from their stored to their exposed
+/// names, or reverse before storing it
+fn rename_properties(c: &Collection, feature: &mut Feature, to_stored: bool) {
+    if let (Some(names), Some(properties)) = (&c.property_names, feature.properties.as_mut()) {
+        // remove all before inserting any, names may be swapped
+        let renamed: Vec<(String, serde_json::Value)> = names
+            .iter()
+            .filter_map(|(stored, exposed)| {
+                let (from, to) = if to_stored {
+                    (exposed, stored)
+                } else {
+                    (stored, exposed)
+                };
+                properties.remove(from).map(|value| (to.to_owned(), value))
+            })
+            .collect();
+        properties.extend(renamed);
+    }
+}
+
+/// Text search document over all string properties, matches the index
+/// created for every items table
+const TEXT_SEARCH_DOCUMENT: &str = r#"jsonb_to_tsvector('simple', properties, '["string"]')"#;
+
+/// Number of rows from which the item count of a collection is estimated
+const ESTIMATE_THRESHOLD: f32 = 100_000.0;
+
+/// Builds the `ORDER BY` clause of an items query
+///
+/// Features are ranked by relevance of the text search if any, then sorted
+/// by `sortby` or else the default sort of the collection, with the id as
+/// tiebreaker. Missing and JSON `null` values are placed as requested by
+/// `nulls`, last by default in both directions.
+fn order_by(c: &Collection, query: &Query) -> String {
+    let mut keys = Vec::new();
+
+    if let Some(q) = query.q.as_ref() {
+        keys.push(format!(
+            "ts_rank({}, {}) DESC",
+            text_search_document(c),
+            text_search_query(q)
+        ));
+    }
+
+    let sortby: Vec<&str> = match (&query.sortby, &c.default_sort) {
+        (Some(sortby), _) => sortby.split(',').collect(),
+        (None, Some(default_sort)) => default_sort.iter().map(String::as_str).collect(),
+        (None, None) => Vec::new(),
+    };
+
+    let nulls = match query.nulls.unwrap_or_default() {
+        Nulls::First => "NULLS FIRST",
+        Nulls::Last => "NULLS LAST",
+    };
+
+    for key in sortby.iter().map(|k| k.trim()).filter(|k| !k.is_empty()) {
+        let (property, direction) = match key.strip_prefix('-') {
+            Some(property) => (property, "DESC"),
+            None => (key.trim_start_matches('+'), "ASC"),
+        };
+        match property {
+            "id" => keys.push(format!("items.id {direction}")),
+            property => keys.push(format!(
+                "NULLIF(properties -> '{}', 'null') {direction} {nulls}",
+                stored_name(c.property_names.as_ref(), property).replace('\'', "''")
+            )),
+        }
+    }
+
+    if keys.is_empty() {
+        return String::new();
+    }
+
+    if !keys.iter().any(|k| k.starts_with("items.id ")) {
+        keys.push("items.id ASC".to_string());
+    }
+
+    format!("ORDER BY {}", keys.join(", "))
+}
+
+/// Makes the geometry column requested by the query the one of the
+/// collection, it must be the default or one of the further geometry columns
+fn select_geometry_column(c: &mut Collection, query: &Query) -> Result<(), Error> {
+    if let Some(column) = query.geometry_column.as_ref() {
+        let default = geometry_column(c).to_owned();
+        let columns = c.geometry_columns.get_or_insert_with(Vec::new);
+        if *column != default && !columns.contains(column) {
+            return Err(Error::Invalid(format!(
+                "Unknown geometry column `{column}`"
+            )));
+        }
+        columns.push(default);
+        c.geometry_column = Some(column.to_owned());
+    }
+    Ok(())
+}
+
+/// Builds the `WHERE` clause of an items query
+fn where_conditions(c: &Collection, query: &Query) -> Result<String, Error> {
+    let geom = quote_ident(geometry_column(c));
+    let storage_srid = c.storage_crs.clone().unwrap_or_default().as_srid();
+
+    let mut where_conditions = vec!["TRUE".to_owned()];
+
+    // bbox, given in the `bbox-crs` and transformed to the storage crs
+    if let Some(bbox) = query.bbox.as_ref() {
+        let bbox_srid: i32 = query.bbox_crs.as_srid();
+
+        let envelope = match bbox {
+            Bbox::Bbox2D(bbox) => format!(
+                "ST_MakeEnvelope({}, {}, {}, {}, {})",
+                bbox[0], bbox[1], bbox[2], bbox[3], bbox_srid
+            ),
+            Bbox::Bbox3D(bbox) => format!(
+                "ST_MakeEnvelope({}, {}, {}, {}, {})",
+                bbox[0], bbox[1], bbox[3], bbox[4], bbox_srid
+            ),
+        };
+        where_conditions.push(format!(
+            r#"{geom} && ST_Transform({}, {})"#,
+            envelope, storage_srid
+        ));
+    }
+
+    // radius
+    if let (Some(center), Some(radius)) = (query.center.as_ref(), query.radius) {
+        where_conditions.push(format!(
+            r#"ST_DWithin(
+                ST_Transform({geom}, 4326)::geography,
+                ST_SetSRID(ST_MakePoint({}, {}), 4326)::geography,
+                {radius}
+            )"#,
+            center[0], center[1]
+        ));
+    }
+
+    // datetime
+    if let Some(datetime) = query.datetime.as_ref() {
+        let (from, to) = match datetime {
+            Datetime::Datetime(_) => (
+                format!("CAST('{datetime}' AS timestamptz)"),
+                format!("CAST('{datetime}' AS timestamptz)"),
+            ),
+            Datetime::Interval { from, to } => {
+                let from = match from {
+                    IntervalDatetime::Datetime(_) => {
+                        format!("CAST('{from}' AS timestamptz)")
+                    }
+                    IntervalDatetime::Open => "to_timestamp('-infinity')".to_owned(),
+                };
+                let to = match to {
+                    IntervalDatetime::Datetime(_) => {
+                        format!("CAST('{to}' AS timestamptz)")
+                    }
+                    IntervalDatetime::Open => "NOW()".to_owned(),
+                };
+                (from, to)
+            }
+        };
+
+        // matches the expression of the datetime index
+        where_conditions.push(format!(
+            "public.item_datetime(properties) && tstzrange({from}, {to}, '[]')"
+        ));
+    }
+
+    // q
+    if let Some(q) = query.q.as_ref() {
+        where_conditions.push(format!(
+            "({}) @@ ({})",
+            text_search_document(c),
+            text_search_query(q)
+        ));
+    }
+
+    // property existence
+    for (exists, properties) in [(true, &query.exists), (false, &query.not_exists)] {
+        for property in properties.iter().flat_map(|p| p.split(',')) {
+            let property = property.trim();
+            if property.is_empty() {
+                continue;
+            }
+            let property = quote_literal(stored_name(c.property_names.as_ref(), property));
+            let not = if exists { "" } else { "NOT " };
+            where_conditions.push(format!("{not}(properties ? {property})"));
+        }
+    }
+
+    // filter, geometries are transformed from the `filter-crs`
+    if let Some(filter) = query.filter.as_ref() {
+        let context = cql::Context {
+            geom: geometry_column(c),
+            property_names: c.property_names.as_ref(),
+            storage_srid,
+            filter_srid: query.filter_crs.clone().unwrap_or_default().as_srid(),
+        };
+        let lang = query.filter_lang.clone().unwrap_or_default();
+        where_conditions.push(cql::to_sql(filter, &lang, &context)?);
+    }
+
+    // kv
+    for (k, v) in query.additional_parameters.iter() {
+        let k = stored_name(c.property_names.as_ref(), k);
+        where_conditions.push(format!(
+            r#"
+            CASE
+                WHEN properties ? '{k}' THEN (
+                    CASE
+                        WHEN jsonb_typeof(properties -> '{k}') = 'number'
+                        THEN RTRIM(properties ->> '{k}', '.0') = RTRIM('{v}', '.0')
+                        ELSE properties ->> '{k}' = '{v}'
+                    END
+                ) 
+                ELSE TRUE
+            END
+            "#
+        ));
+    }
+
+    Ok(where_conditions.join(" AND "))
+}
+
+impl Db {
+    /// Lists clusters of features within `distance` of each other as points
+    /// with a `count` property
+    async fn list_clusters(
+        &self,
+        c: &Collection,
+        distance: f64,
+        query: &Query,
+    ) -> anyhow::Result<FeatureCollection> {
+        let geom = quote_ident(geometry_column(c));
+        let table = self.items_table(c);
+
+        let conditions = where_conditions(c, query)?;
+        let (limit, offset) = page_params(query.limit, query.offset);
+
+        let clusters = format!(
+            r#"
+            SELECT
+                {geom},
+                ST_ClusterDBSCAN({geom}, eps := {distance}, minpoints := 1) OVER () AS cid
+            FROM {table} items
+            WHERE {conditions}
+            "#
+        );
+
+        // count
+        let number_matched: (i64,) =
+            sqlx::query_as(&format!("SELECT count(DISTINCT cid) FROM ({clusters}) c"))
+                .fetch_one(&self.pool)
+                .await?;
+
+        // fetch
+        let features: Option<sqlx::types::Json<Vec<Feature>>> = sqlx::query_scalar(&format!(
+            r#"
+            SELECT array_to_json(array_agg(row_to_json(t)))
+            FROM (
+                SELECT
+                    cid::text AS id,
+                    '{}' AS collection,
+                    jsonb_build_object('count', count(*)) AS properties,
+                    ST_AsGeoJSON(ST_Transform(ST_Centroid(ST_Collect({geom})), $1))::jsonb AS geometry
+                FROM ({clusters}) c
+                GROUP BY cid
+                ORDER BY cid
+                {}
+            ) t
+            "#,
+            c.id,
+            page_clause(2)
+        ))
+        .bind(query.crs.as_srid())
+        .bind(limit)
+        .bind(offset)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let features = features.map(|f| f.0).unwrap_or_default();
+        let mut fc = FeatureCollection::new(features);
+        fc.number_matched = Some(number_matched.0 as u64);
+
+        Ok(fc)
+    }
+}
+
+/// Text search document of the items of a collection
+fn text_search_document(c: &Collection) -> String {
+    if c.item_type.as_deref() == Some(RECORD) {
+        return r#"
+            to_tsvector('simple', COALESCE(properties ->> 'title', ''))
+            || to_tsvector('simple', COALESCE(properties ->> 'description', ''))
+            || jsonb_to_tsvector('simple', COALESCE(properties -> 'keywords', '[]'), '["string"]')
+            || jsonb_to_tsvector('simple', COALESCE(properties -> 'themes', '[]'), '["string"]')
+            "#
+        .to_string();
+    }
+
+    match &c.search_properties {
+        Some(properties) => properties
+            .iter()
+            .map(|p| {
+                format!(
+                    "jsonb_to_tsvector('simple', COALESCE(properties -> '{}', 'null'), '[\"string\"]')",
+                    stored_name(c.property_names.as_ref(), p).replace('\'', "''")
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(" || "),
+        None => TEXT_SEARCH_DOCUMENT.to_string(),
+    }
+}
+
+/// Translates comma separated search terms into a text search query matching
+/// any of them
+fn text_search_query(q: &str) -> String {
+    q.split(',')
+        .map(|term| format!("plainto_tsquery('simple', '{}')", term.replace('\'', "''")))
+        .collect::<Vec<String>>()
+        .join(" || ")
+}
 
 #[async_trait::async_trait]
 impl FeatureTransactions for Db {
     async fn create_feature(&self, feature: &Feature) -> anyhow::Result<String> {
         let collection = feature.collection.as_ref().unwrap();
 
+        let c = self.existing_collection(collection).await?;
+        let geom = quote_ident(geometry_column(&c));
+        let items = self.items_schema();
+
+        let srid = c.storage_crs.clone().unwrap_or_default().as_srid();
+        validate_geometry(&feature.geometry, srid)?;
+
+        let mut feature = feature.clone();
+        rename_properties(&c, &mut feature, true);
+
+        let id = match c.id_strategy {
+            Some(IdStrategy::Uuid) => "gen_random_uuid()::text".to_string(),
+            Some(IdStrategy::Sequential) => {
+                let sequence = self.id_sequence(collection);
+                sqlx::query(&format!("CREATE SEQUENCE IF NOT EXISTS {sequence}"))
+                    .execute(&self.pool)
+                    .await?;
+
+                format!("nextval({})::text", quote_literal(&sequence))
+            }
+            Some(IdStrategy::Client) | None => {
+                "COALESCE($1 ->> 'id', gen_random_uuid()::text)".to_string()
+            }
+        };
+
+        let properties = if c.timestamps.unwrap_or_default() {
+            "COALESCE($1 -> 'properties', '{}') || jsonb_build_object('created', now(), 'updated', now())"
+        } else {
+            "$1 -> 'properties'"
+        };
+
+        let geometry = geometry_sql(&c);
+
+        let mut tx = self.pool.begin().await?;
+
         let id: (String,) = sqlx::query_as(&format!(
             r#"
-            INSERT INTO items."{0}" (
+            INSERT INTO "{items}"."{0}" (
                 id,
                 properties,
-                geom,
+                {geom},
                 links,
                 assets,
                 bbox
             ) VALUES (
-                COALESCE($1 ->> 'id', gen_random_uuid()::text),
-                $1 -> 'properties',
-                ST_GeomFromGeoJSON($1 -> 'geometry'),
+                {id},
+                {properties},
+                {geometry},
                 $1 -> 'links',
                 COALESCE($1 -> 'assets', '{{}}'::jsonb),
                 $1 -> 'bbox'
@@ -68,10 +510,16 @@ impl FeatureTransactions for Db {
             "#,
             &collection
         ))
-        .bind(serde_json::to_value(feature)?)
-        .fetch_one(&self.pool)
+        .bind(serde_json::to_value(&feature)?)
+        .fetch_one(&mut tx)
         .await?;
 
+        self.expand_extent(&mut tx, &c, &id.0).await?;
+
+        tx.commit().await?;
+
+        self.invalidate_tiles(collection).await?;
+
         Ok(id.0)
     }
 
@@ -81,54 +529,119 @@ impl FeatureTransactions for Db {
         id: &str,
         crs: &Crs,
     ) -> anyhow::Result<Option<Feature>> {
-        let feature: Option<sqlx::types::Json<Feature>> = sqlx::query_scalar(&format!(
+        let c = match self.read_collection(collection).await? {
+            Some(c) => c,
+            None => return Ok(None),
+        };
+        let rows = rows(&quote_ident(geometry_column(&c)), false);
+        let table = self.items_table(&c);
+        let meta = self.meta_table("collections");
+
+        let sql = format!(
             r#"
             SELECT row_to_json(t)
             FROM (
-                SELECT {ROWS}
-                FROM items."{collection}" items JOIN meta.collections meta
+                SELECT {rows}
+                FROM {table} items JOIN {meta} meta
                     ON items.collection = meta.id
                 WHERE items.id = $2
             ) t
             "#
-        ))
-        .bind(crs.as_srid())
-        .bind(id)
-        .fetch_optional(&self.pool)
+        );
+
+        let feature: Option<sqlx::types::Json<Feature>> = retry(self.read_attempts, || {
+            sqlx::query_scalar(&sql)
+                .bind(crs.as_srid())
+                .bind(id)
+                .fetch_optional(&self.pool)
+        })
         .await?;
 
-        Ok(feature.map(|f| f.0))
+        Ok(feature.map(|f| {
+            let mut feature = f.0;
+            rename_properties(&c, &mut feature, false);
+            feature
+        }))
     }
 
     async fn update_feature(&self, feature: &Feature) -> anyhow::Result<()> {
-        sqlx::query(&format!(
+        let c = self
+            .existing_collection(feature.collection.as_ref().unwrap())
+            .await?;
+        let geom = quote_ident(geometry_column(&c));
+
+        let srid = c.storage_crs.clone().unwrap_or_default().as_srid();
+        validate_geometry(&feature.geometry, srid)?;
+
+        let mut feature = feature.clone();
+        rename_properties(&c, &mut feature, true);
+
+        // keep the stored creation time
+        let properties = if c.timestamps.unwrap_or_default() {
+            "(COALESCE($1 -> 'properties', '{}') - 'created') \
+                || jsonb_strip_nulls(jsonb_build_object('created', properties -> 'created', 'updated', now()))"
+        } else {
+            "$1 -> 'properties'"
+        };
+
+        let geometry = geometry_sql(&c);
+
+        let mut tx = self.pool.begin().await?;
+
+        let result = sqlx::query(&format!(
             r#"
-            UPDATE items."{0}"
+            UPDATE "{1}"."{0}"
             SET
-                properties = $1 -> 'properties',
-                geom = ST_GeomFromGeoJSON($1 -> 'geometry'),
+                properties = {properties},
+                {geom} = {geometry},
                 links = $1 -> 'links',
-                assets = COALESCE($1 -> 'assets', '{{}}'::jsonb)
-            WHERE id = $1 ->> 'id'
+                assets = COALESCE($1 -> 'assets', '{{}}'::jsonb),
+                version = version + 1
+            WHERE id = $1 ->> 'id' AND ($2::bigint IS NULL OR version = $2)
             "#,
-            &feature.collection.as_ref().unwrap()
+            &feature.collection.as_ref().unwrap(),
+            self.items_schema()
         ))
-        .bind(serde_json::to_value(feature)?)
-        .execute(&self.pool)
+        .bind(serde_json::to_value(&feature)?)
+        .bind(feature.version)
+        .execute(&mut tx)
         .await?;
 
+        if let Some(version) = feature.version {
+            if result.rows_affected() == 0 {
+                return Err(Error::Conflict(format!(
+                    "Feature `{}` is not at version `{version}`",
+                    feature.id.as_ref().unwrap()
+                ))
+                .into());
+            }
+        }
+
+        if result.rows_affected() > 0 {
+            self.expand_extent(&mut tx, &c, feature.id.as_ref().unwrap())
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        self.invalidate_tiles(feature.collection.as_ref().unwrap())
+            .await?;
+
         Ok(())
     }
 
     async fn delete_feature(&self, collection: &str, id: &str) -> anyhow::Result<()> {
         sqlx::query(&format!(
-            r#"DELETE FROM items."{}" WHERE id = $1"#,
+            r#"DELETE FROM "{}"."{}" WHERE id = $1"#,
+            self.items_schema(),
             collection
         ))
         .bind(id)
         .execute(&self.pool)
         .await?;
 
+        self.invalidate_tiles(collection).await?;
+
         Ok(())
     }
 
@@ -137,141 +650,241 @@ impl FeatureTransactions for Db {
         collection: &str,
         query: &Query,
     ) -> anyhow::Result<FeatureCollection> {
-        let mut where_conditions = vec!["TRUE".to_owned()];
-
-        // bbox
-        if let Some(bbox) = query.bbox.as_ref() {
-            // TODO: Properly handle crs and bbox transformation
-            let bbox_srid: i32 = query.bbox_crs.as_srid();
-
-            let c = self.read_collection(collection).await?;
-            let storage_srid = c
-                .expect("collection exists")
-                .storage_crs
-                .unwrap_or_default()
-                .as_srid();
-
-            let envelope = match bbox {
-                Bbox::Bbox2D(bbox) => format!(
-                    "ST_MakeEnvelope({}, {}, {}, {}, {})",
-                    bbox[0], bbox[1], bbox[2], bbox[3], bbox_srid
-                ),
-                Bbox::Bbox3D(bbox) => format!(
-                    "ST_MakeEnvelope({}, {}, {}, {}, {})",
-                    bbox[0], bbox[1], bbox[3], bbox[4], bbox_srid
-                ),
-            };
-            where_conditions.push(format!(
-                "geom && ST_Transform({}, {})",
-                envelope, storage_srid
-            ));
-        }
+        let mut c = self.existing_collection(collection).await?;
+        select_geometry_column(&mut c, query)?;
+        let geom = quote_ident(geometry_column(&c));
+        let table = self.items_table(&c);
 
-        // datetime
-        if let Some(datetime) = query.datetime.as_ref() {
-            let (from, to) = match datetime {
-                Datetime::Datetime(_) => (
-                    format!("CAST('{datetime}' AS timestamptz)"),
-                    format!("CAST('{datetime}' AS timestamptz)"),
-                ),
-                Datetime::Interval { from, to } => {
-                    let from = match from {
-                        IntervalDatetime::Datetime(_) => {
-                            format!("CAST('{from}' AS timestamptz)")
-                        }
-                        IntervalDatetime::Open => "to_timestamp('-infinity')".to_owned(),
-                    };
-                    let to = match to {
-                        IntervalDatetime::Datetime(_) => {
-                            format!("CAST('{to}' AS timestamptz)")
-                        }
-                        IntervalDatetime::Open => "NOW()".to_owned(),
-                    };
-                    (from, to)
-                }
-            };
-
-            where_conditions.push(format!(
-                r#"
-                (
-                    CASE
-                        WHEN (properties->'datetime') IS NOT NULL THEN (
-                            CAST(properties->>'datetime' AS timestamptz)
-                            BETWEEN {from} AND {to}
-                        )
-                        WHEN (
-                            (properties->'datetime') IS NULL
-                            AND (properties->'start_datetime') IS NOT NULL
-                            AND (properties->'end_datetime') IS NOT NULL
-                        ) THEN (
-                            ({from}, {to}) OVERLAPS (
-                                CAST(properties->>'start_datetime' AS timestamptz),
-                                CAST(properties->>'end_datetime' AS timestamptz)
-                            )
-                        )
-                        ELSE TRUE
-                    END
-                )
-                "#
-            ));
+        if let Some(distance) = query.cluster {
+            return self.list_clusters(&c, distance, query).await;
         }
 
-        // kv
-        for (k, v) in query.additional_parameters.iter() {
-            where_conditions.push(format!(
-                r#"
-                CASE
-                    WHEN properties ? '{k}' THEN (
-                        CASE
-                            WHEN jsonb_typeof(properties -> '{k}') = 'number'
-                            THEN RTRIM(properties ->> '{k}', '.0') = RTRIM('{v}', '.0')
-                            ELSE properties ->> '{k}' = '{v}'
-                        END
-                    ) 
-                    ELSE TRUE
-                END
-                "#
-            ));
-        }
+        let meta = self.meta_table("collections");
 
-        let conditions = where_conditions.join(" AND ");
+        let conditions = where_conditions(&c, query)?;
+        let order_by = order_by(&c, query);
 
         // count
-        let number_matched: (i64,) = sqlx::query_as(&format!(
+        let sql = format!(
             r#"
-            SELECT count(*) FROM items."{collection}"
+            SELECT count(*) FROM {table} items
             WHERE {conditions}
             "#,
-        ))
-        .fetch_one(&self.pool)
+        );
+        let number_matched: (i64,) = retry(self.read_attempts, || {
+            sqlx::query_as(&sql).fetch_one(&self.pool)
+        })
         .await?;
 
         // fetch
-        let features: Option<sqlx::types::Json<Vec<Feature>>> = sqlx::query_scalar(&format!(
+        let sql = format!(
             r#"
             SELECT array_to_json(array_agg(row_to_json(t)))
             FROM (
-                SELECT {ROWS}
-                FROM items."{collection}" items JOIN meta.collections meta
+                SELECT {}
+                FROM {table} items JOIN {meta} meta
                     ON items.collection = meta.id
                 WHERE {conditions}
-                LIMIT {}
-                OFFSET {}
+                {order_by}
+                {}
             ) t
             "#,
-            query
-                .limit
-                .map_or_else(|| String::from("NULL"), |l| l.to_string()),
-            query.offset.unwrap_or(0)
-        ))
-        .bind(query.crs.as_srid())
-        .fetch_one(&self.pool)
+            rows(&geom, query.skip_geometry.unwrap_or_default()),
+            page_clause(2)
+        );
+        let (limit, offset) = page_params(query.limit, query.offset);
+        let features: Option<sqlx::types::Json<Vec<Feature>>> = retry(self.read_attempts, || {
+            sqlx::query_scalar(&sql)
+                .bind(query.crs.as_srid())
+                .bind(limit)
+                .bind(offset)
+                .fetch_one(&self.pool)
+        })
         .await?;
 
-        let features = features.map(|f| f.0).unwrap_or_default();
+        let mut features = features.map(|f| f.0).unwrap_or_default();
+        for feature in features.iter_mut() {
+            rename_properties(&c, feature, false);
+        }
         let mut fc = FeatureCollection::new(features);
         fc.number_matched = Some(number_matched.0 as u64);
 
         Ok(fc)
     }
+
+    async fn distinct_values(
+        &self,
+        collection: &str,
+        property: &str,
+        query: &Query,
+    ) -> anyhow::Result<Vec<PropertyValue>> {
+        let c = self.existing_collection(collection).await?;
+        let table = self.items_table(&c);
+
+        let conditions = where_conditions(&c, query)?;
+
+        let values: Vec<(sqlx::types::Json<serde_json::Value>, i64)> = sqlx::query_as(&format!(
+            r#"
+            SELECT properties -> $1, count(*)
+            FROM {table} items
+            WHERE properties ? $1 AND {conditions}
+            GROUP BY 1
+            ORDER BY 2 DESC, 1
+            {}
+            "#,
+            page_clause(2),
+        ))
+        .bind(stored_name(c.property_names.as_ref(), property))
+        .bind(page_params(query.limit, None).0)
+        .bind(0_i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(values
+            .into_iter()
+            .map(|(value, count)| PropertyValue {
+                value: value.0,
+                count: count as u64,
+            })
+            .collect())
+    }
+
+    /// Uses the row estimate of the planner statistics if it exceeds
+    /// `ESTIMATE_THRESHOLD`, and counts the rows of smaller or not yet
+    /// analyzed tables.
+    async fn count_items(&self, c: &Collection, exact: bool) -> anyhow::Result<Option<u64>> {
+        if !exact {
+            let estimate: Option<f32> = retry(self.read_attempts, || {
+                sqlx::query_scalar("SELECT reltuples FROM pg_class WHERE oid = to_regclass($1)")
+                    .bind(self.items_relation(c))
+                    .fetch_optional(&self.pool)
+            })
+            .await?;
+            if let Some(estimate) = estimate.filter(|e| *e >= ESTIMATE_THRESHOLD) {
+                return Ok(Some(estimate as u64));
+            }
+        }
+
+        let sql = format!("SELECT count(*) FROM {}", self.items_table(c));
+        let count: i64 = retry(self.read_attempts, || {
+            sqlx::query_scalar(&sql).fetch_one(&self.pool)
+        })
+        .await?;
+
+        Ok(Some(count as u64))
+    }
+
+    async fn queryables(&self, collection: &str) -> anyhow::Result<Vec<String>> {
+        let c = self.existing_collection(collection).await?;
+
+        let queryables: Vec<String> = sqlx::query_scalar(&format!(
+            r#"
+            SELECT DISTINCT jsonb_object_keys(properties)
+            FROM {} items
+            WHERE jsonb_typeof(properties) = 'object'
+            ORDER BY 1
+            "#,
+            self.items_table(&c)
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut queryables: Vec<String> = queryables
+            .into_iter()
+            .map(|name| exposed_name(&c, name))
+            .collect();
+        queryables.sort();
+        queryables.dedup();
+
+        Ok(queryables)
+    }
+
+    async fn aggregate(
+        &self,
+        collection: &str,
+        property: &str,
+        group_by: Option<&str>,
+        query: &Query,
+    ) -> anyhow::Result<Vec<AggregationGroup>> {
+        let c = self.existing_collection(collection).await?;
+        let table = self.items_table(&c);
+
+        let conditions = where_conditions(&c, query)?;
+
+        #[allow(clippy::type_complexity)]
+        let groups: Vec<(
+            Option<sqlx::types::Json<serde_json::Value>>,
+            i64,
+            Option<f64>,
+            Option<f64>,
+            Option<f64>,
+            Option<f64>,
+        )> = sqlx::query_as(&format!(
+            r#"
+            SELECT key, count(*), min(value), max(value), avg(value), sum(value)
+            FROM (
+                SELECT
+                    properties -> $2::text AS key,
+                    (properties ->> $1::text)::double precision AS value
+                FROM {table} items
+                WHERE jsonb_typeof(properties -> $1::text) = 'number' AND {conditions}
+            ) t
+            GROUP BY key
+            ORDER BY key
+            "#
+        ))
+        .bind(stored_name(c.property_names.as_ref(), property))
+        .bind(group_by.map(|name| stored_name(c.property_names.as_ref(), name)))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(groups
+            .into_iter()
+            .map(|(key, count, min, max, avg, sum)| AggregationGroup {
+                key: key.map(|k| k.0),
+                count: count as u64,
+                min,
+                max,
+                avg,
+                sum,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[sqlx::test]
+    async fn datetime_index(pool: sqlx::PgPool) {
+        let db = Db::from(pool);
+        let collection = Collection {
+            id: "events".to_string(),
+            ..Default::default()
+        };
+        db.create_collection(&collection).await.unwrap();
+
+        // the planner prefers sequential scans of tiny tables
+        let mut conn = db.pool.acquire().await.unwrap();
+        sqlx::query("SET enable_seqscan = off")
+            .execute(&mut conn)
+            .await
+            .unwrap();
+
+        for datetime in ["2023-01-01T00:00:00Z", "2023-01-01T00:00:00Z/.."] {
+            let query: Query = serde_json::from_value(json!({ "datetime": datetime })).unwrap();
+            let plan: Vec<String> = sqlx::query_scalar(&format!(
+                "EXPLAIN SELECT id FROM {} WHERE {}",
+                db.items_table(&collection),
+                where_conditions(&collection, &query).unwrap()
+            ))
+            .fetch_all(&mut conn)
+            .await
+            .unwrap();
+            assert!(plan.concat().contains("events_datetime_idx"), "{plan:?}");
+        }
+    }
 }
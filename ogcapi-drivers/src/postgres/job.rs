@@ -7,9 +7,9 @@ use super::Db;
 #[async_trait::async_trait]
 impl JobHandler for Db {
     async fn register(&self, job: &StatusInfo) -> anyhow::Result<String> {
-        let (id,): (String,) = sqlx::query_as(
+        let (id,): (String,) = sqlx::query_as(&format!(
             r#"
-            INSERT INTO meta.jobs(
+            INSERT INTO {jobs}(
                 job_id, process_id, status, created, updated, links
             )
             VALUES (
@@ -17,20 +17,63 @@ impl JobHandler for Db {
             )
             RETURNING job_id
             "#,
-        )
+            jobs = self.meta_table("jobs")
+        ))
         .bind(sqlx::types::Json(job))
         .fetch_one(&self.pool)
         .await?;
         Ok(id)
     }
 
+    async fn update(&self, job: &StatusInfo) -> anyhow::Result<()> {
+        sqlx::query(&format!(
+            r#"
+            UPDATE {jobs}
+            SET status = $1 -> 'status',
+                message = $1 ->> 'message',
+                progress = ($1 ->> 'progress')::smallint,
+                updated = NOW()
+            WHERE job_id = $1 ->> 'jobID' AND status <> '"dismissed"'::jsonb
+            "#,
+            jobs = self.meta_table("jobs")
+        ))
+        .bind(sqlx::types::Json(job))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn finish(&self, job: &StatusInfo, results: Option<&Results>) -> anyhow::Result<()> {
+        sqlx::query(&format!(
+            r#"
+            UPDATE {jobs}
+            SET status = $1 -> 'status',
+                message = $1 ->> 'message',
+                progress = ($1 ->> 'progress')::smallint,
+                updated = NOW(),
+                finished = NOW(),
+                results = $2
+            WHERE job_id = $1 ->> 'jobID' AND status <> '"dismissed"'::jsonb
+            "#,
+            jobs = self.meta_table("jobs")
+        ))
+        .bind(sqlx::types::Json(job))
+        .bind(results.map(sqlx::types::Json))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
     async fn status(&self, id: &str) -> anyhow::Result<Option<StatusInfo>> {
-        let status: Option<sqlx::types::Json<StatusInfo>> = sqlx::query_scalar(
+        let status: Option<sqlx::types::Json<StatusInfo>> = sqlx::query_scalar(&format!(
             r#"
             SELECT row_to_json(jobs) as "status_info!" 
-            FROM meta.jobs WHERE job_id = $1
+            FROM {jobs} WHERE job_id = $1
             "#,
-        )
+            jobs = self.meta_table("jobs")
+        ))
         .bind(id)
         .fetch_optional(&self.pool)
         .await?;
@@ -39,15 +82,16 @@ impl JobHandler for Db {
     }
 
     async fn dismiss(&self, id: &str) -> anyhow::Result<Option<StatusInfo>> {
-        let status: Option<sqlx::types::Json<StatusInfo>> = sqlx::query_scalar(
+        let status: Option<sqlx::types::Json<StatusInfo>> = sqlx::query_scalar(&format!(
             r#"
-            UPDATE meta.jobs
+            UPDATE {jobs}
             SET status = $2,
                 message = 'Job dismissed'
             WHERE job_id = $1 AND status <@ '["accepted", "running"]'::jsonb
             RETURNING row_to_json(jobs) as "status_info!"
             "#,
-        )
+            jobs = self.meta_table("jobs")
+        ))
         .bind(id)
         .bind(sqlx::types::Json(StatusCode::Dismissed))
         .fetch_optional(&self.pool)
@@ -57,13 +101,14 @@ impl JobHandler for Db {
     }
 
     async fn results(&self, id: &str) -> anyhow::Result<Option<Results>> {
-        let results: Option<sqlx::types::Json<Results>> = sqlx::query_scalar(
+        let results: Option<sqlx::types::Json<Results>> = sqlx::query_scalar(&format!(
             r#"
             SELECT results as "results!"
-            FROM meta.jobs
+            FROM {jobs}
             WHERE job_id = $1
             "#,
-        )
+            jobs = self.meta_table("jobs")
+        ))
         .bind(id)
         .fetch_optional(&self.pool)
         .await?;
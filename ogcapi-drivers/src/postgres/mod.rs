@@ -1,22 +1,336 @@
 mod collection;
+mod cql;
 mod edr;
 mod feature;
 mod job;
+mod retry;
 #[cfg(feature = "stac")]
 mod stac;
 mod style;
 mod tile;
+mod timing;
+
+use std::{borrow::Cow, collections::HashMap, str::FromStr, time::Duration};
 
 use sqlx::{
-    migrate::MigrateDatabase,
+    migrate::{MigrateDatabase, Migration},
     postgres::{PgConnectOptions, PgPool, PgPoolOptions},
-    Postgres,
+    ConnectOptions, Postgres,
 };
 use url::Url;
 
+use ogcapi_types::common::Collection;
+
+pub use log::LevelFilter;
+pub use retry::{is_transient, retry, READ_ATTEMPTS};
+pub use timing::timed;
+
+/// Default schema of the metadata tables
+pub const SCHEMA: &str = "meta";
+
+/// Default maximum number of pooled connections
+pub const MAX_CONNECTIONS: u32 = 8;
+
+/// Default time to wait for a pooled connection, after which requests fail
+/// rather than queue up behind an exhausted pool
+pub const ACQUIRE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Default name of the geometry column
+const GEOMETRY_COLUMN: &str = "geom";
+
+/// Returns the name of the geometry column of a collection
+pub(crate) fn geometry_column(collection: &Collection) -> &str {
+    collection
+        .geometry_column
+        .as_deref()
+        .unwrap_or(GEOMETRY_COLUMN)
+}
+
+/// Stored name of a property exposed under `name`, given the exposed names
+/// keyed by their stored name
+pub(crate) fn stored_name<'a>(
+    names: Option<&'a HashMap<String, String>>,
+    name: &'a str,
+) -> &'a str {
+    names
+        .into_iter()
+        .flatten()
+        .find(|(_, exposed)| *exposed == name)
+        .map_or(name, |(stored, _)| stored)
+}
+
+/// Quotes an identifier, doubling embedded quotes
+pub(crate) fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Quotes a string literal, doubling embedded quotes
+pub(crate) fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Returns the `LIMIT` and `OFFSET` clauses selecting a page of a listing with
+/// placeholders starting at `$first`, to be bound with [`page_params`]
+pub(crate) fn page_clause(first: usize) -> String {
+    format!("LIMIT ${} OFFSET ${}", first, first + 1)
+}
+
+/// Returns the limit and offset to bind to a [`page_clause`], without limit
+/// all rows are selected
+pub(crate) fn page_params<T: TryInto<i64>>(
+    limit: Option<T>,
+    offset: Option<T>,
+) -> (Option<i64>, i64) {
+    let clamp = |value: T| value.try_into().unwrap_or(i64::MAX);
+    (limit.map(clamp), offset.map(clamp).unwrap_or(0))
+}
+
+/// Returns the schema of the item tables belonging to a metadata schema
+fn items_schema(schema: &str) -> String {
+    if schema == SCHEMA {
+        "items".to_string()
+    } else {
+        format!("{schema}_items")
+    }
+}
+
+/// Options of the database driver, see [`Db::setup_with`]
+#[derive(Debug, Clone)]
+pub struct DbOptions {
+    /// Schema of the metadata tables
+    pub schema: String,
+    /// Maximum number of pooled connections
+    pub max_connections: u32,
+    /// Time to wait for a pooled connection
+    pub acquire_timeout: Duration,
+    /// Statements running longer are cancelled by postgres
+    pub statement_timeout: Option<Duration>,
+    /// Level all statements are logged at
+    pub log_statements: Option<LevelFilter>,
+    /// Number of attempts of read queries failing with transient errors
+    pub read_attempts: u32,
+}
+
+impl Default for DbOptions {
+    fn default() -> Self {
+        Self {
+            schema: SCHEMA.to_string(),
+            max_connections: MAX_CONNECTIONS,
+            acquire_timeout: ACQUIRE_TIMEOUT,
+            statement_timeout: None,
+            log_statements: None,
+            read_attempts: READ_ATTEMPTS,
+        }
+    }
+}
+
+/// Returns the options of connections to the database at `url`, applying the
+/// `statement_timeout` and `log_statements` of the driver options
+pub fn connect_options(url: &Url, options: &DbOptions) -> Result<PgConnectOptions, sqlx::Error> {
+    let mut connect = PgConnectOptions::from_str(url.as_str())?;
+    if let Some(timeout) = options.statement_timeout {
+        connect = connect.options([("statement_timeout", timeout.as_millis())]);
+    }
+    if let Some(level) = options.log_statements {
+        connect.log_statements(level);
+    }
+    Ok(connect)
+}
+
+/// Applies the embedded schema migrations
+///
+/// Migrations which have already been applied are skipped, so this can be run
+/// on every startup or separately, e.g. from an init container.
+pub async fn migrate(pool: &PgPool) -> Result<(), sqlx::Error> {
+    sqlx::migrate!().run(pool).await?;
+    Ok(())
+}
+
+/// Replaces the references to the schema `from` in the SQL of a migration by
+/// `to`, which are names qualified by it, the schema itself and its name as
+/// string literal
+///
+/// Only whole identifiers are replaced, so neither `metadata` nor `geometa.`
+/// are affected when renaming `meta`.
+fn rename_schema(sql: &str, from: &str, to: &str) -> String {
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '"' || c == '$';
+
+    let mut renamed = String::with_capacity(sql.len());
+    let mut rest = sql;
+    while let Some(i) = rest.find(from) {
+        renamed.push_str(&rest[..i]);
+        rest = &rest[i + from.len()..];
+
+        let before = renamed.chars().last();
+        let after = rest.chars().next();
+        if before.map_or(false, |c| is_ident(c) || c == '.') || after.map_or(false, is_ident) {
+            renamed.push_str(from);
+        } else if before == Some('\'') && after == Some('\'') {
+            renamed.push_str(to);
+        } else {
+            renamed.push_str(&quote_ident(to));
+        }
+    }
+    renamed.push_str(rest);
+    renamed
+}
+
+/// Applies the embedded schema migrations to a custom schema
+///
+/// The metadata tables are created in `schema` and the item tables in
+/// `{schema}_items`, the applied migrations are tracked within `schema`. This
+/// allows isolated deployments to share a database.
+pub async fn migrate_schema(pool: &PgPool, schema: &str) -> Result<(), sqlx::Error> {
+    if schema == SCHEMA {
+        return migrate(pool).await;
+    }
+
+    if schema.is_empty()
+        || !schema
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    {
+        return Err(sqlx::Error::Configuration(
+            format!("invalid schema name `{schema}`").into(),
+        ));
+    }
+
+    let items = items_schema(schema);
+
+    let mut migrator = sqlx::migrate!();
+    migrator.migrations = migrator
+        .migrations
+        .iter()
+        .map(|migration| {
+            let sql = rename_schema(&migration.sql, SCHEMA, schema);
+            let sql = rename_schema(&sql, "items", &items)
+                .replace("postgis;", "postgis SCHEMA public;")
+                // already created to track the migrations
+                .replace(
+                    &format!(r#"CREATE SCHEMA "{schema}";"#),
+                    &format!(r#"CREATE SCHEMA IF NOT EXISTS "{schema}";"#),
+                );
+            Migration::new(
+                migration.version,
+                migration.description.clone(),
+                migration.migration_type,
+                Cow::Owned(sql),
+            )
+        })
+        .collect();
+
+    // track migrations in the custom schema
+    let mut conn = pool.acquire().await?;
+    sqlx::query(&format!(r#"CREATE SCHEMA IF NOT EXISTS "{schema}""#))
+        .execute(&mut conn)
+        .await?;
+    sqlx::query(&format!(r#"SET search_path TO "{schema}", public"#))
+        .execute(&mut conn)
+        .await?;
+
+    let result = migrator.run(&mut conn).await;
+
+    sqlx::query("RESET search_path").execute(&mut conn).await?;
+
+    result?;
+    Ok(())
+}
+
 #[derive(Debug, Clone)]
 pub struct Db {
     pub pool: PgPool,
+    /// Number of attempts of read queries failing with transient errors
+    pub read_attempts: u32,
+    /// Schema of the metadata tables
+    pub schema: String,
+}
+
+impl From<PgPool> for Db {
+    fn from(pool: PgPool) -> Self {
+        Db {
+            pool,
+            read_attempts: READ_ATTEMPTS,
+            schema: SCHEMA.to_string(),
+        }
+    }
+}
+
+impl Db {
+    /// Returns the qualified name of a metadata table
+    pub(crate) fn meta_table(&self, table: &str) -> String {
+        format!(r#""{}".{table}"#, self.schema)
+    }
+
+    /// Returns the schema of the item tables
+    pub fn items_schema(&self) -> String {
+        items_schema(&self.schema)
+    }
+
+    /// Returns the quoted name of the sequence of sequential item ids
+    pub(crate) fn id_sequence(&self, collection: &str) -> String {
+        format!(
+            "{}.{}",
+            quote_ident(&self.items_schema()),
+            quote_ident(&format!("{collection}_id_seq"))
+        )
+    }
+
+    /// Returns the quoted name of the table storing the items of a collection
+    pub(crate) fn items_relation(&self, collection: &Collection) -> String {
+        match &collection.table {
+            Some(table) => table
+                .split('.')
+                .map(|part| quote_ident(part.trim_matches('"')))
+                .collect::<Vec<String>>()
+                .join("."),
+            None => format!(
+                "{}.{}",
+                quote_ident(&self.items_schema()),
+                quote_ident(&collection.id)
+            ),
+        }
+    }
+
+    /// Returns the SQL expression for the items table of a collection
+    ///
+    /// Collections backed by an existing table are mapped onto the layout of the
+    /// managed item tables.
+    pub(crate) fn items_table(&self, collection: &Collection) -> String {
+        match &collection.table {
+            Some(table) => {
+                let geom = geometry_column(collection);
+                let id = collection.id_column.as_deref().unwrap_or("id");
+                // further geometry columns are no properties
+                let geometries: String = collection
+                    .geometry_columns
+                    .iter()
+                    .flatten()
+                    .map(|column| format!(" - {}", quote_literal(column)))
+                    .collect();
+                let table = self.items_relation(collection);
+                format!(
+                    r#"(
+                        SELECT
+                            {id_column}::text AS id,
+                            {collection}::text AS collection,
+                            to_jsonb(t) - {id} - {geom_literal}{geometries} AS properties,
+                            {geom_column},
+                            '[]'::jsonb AS links,
+                            '{{}}'::jsonb AS assets,
+                            NULL::jsonb AS bbox,
+                            1::bigint AS version
+                        FROM {table} t
+                    )"#,
+                    id_column = quote_ident(id),
+                    collection = quote_literal(&collection.id),
+                    id = quote_literal(id),
+                    geom_literal = quote_literal(geom),
+                    geom_column = quote_ident(geom),
+                )
+            }
+            None => self.items_relation(collection),
+        }
+    }
 }
 
 impl Db {
@@ -25,20 +339,36 @@ impl Db {
         let pool = if let Ok(url) = std::env::var("DATABASE_URL") {
             PgPoolOptions::new()
                 .max_connections(8)
+                .acquire_timeout(ACQUIRE_TIMEOUT)
                 .connect(&url)
                 .await?
         } else {
             PgPoolOptions::new()
                 .max_connections(8)
+                .acquire_timeout(ACQUIRE_TIMEOUT)
                 .connect_with(PgConnectOptions::new())
                 .await?
         };
 
-        Ok(Db { pool })
+        Ok(Db::from(pool))
     }
 
     /// Setup database driver from url
     pub async fn setup(url: &Url) -> Result<Self, sqlx::Error> {
+        Db::setup_with_schema(url, SCHEMA).await
+    }
+
+    /// Setup database driver from url using a custom schema
+    pub async fn setup_with_schema(url: &Url, schema: &str) -> Result<Self, sqlx::Error> {
+        let options = DbOptions {
+            schema: schema.to_string(),
+            ..Default::default()
+        };
+        Db::setup_with(url, &options).await
+    }
+
+    /// Setup database driver from url with custom options
+    pub async fn setup_with(url: &Url, options: &DbOptions) -> Result<Self, sqlx::Error> {
         // Create database if not exists
         if !Postgres::database_exists(url.as_str()).await? {
             Postgres::create_database(url.as_str()).await?
@@ -46,13 +376,60 @@ impl Db {
 
         // Create pool
         let pool = PgPoolOptions::new()
-            .max_connections(8)
-            .connect(url.as_str())
+            .max_connections(options.max_connections)
+            .acquire_timeout(options.acquire_timeout)
+            .connect_with(connect_options(url, options)?)
             .await?;
 
         // Run embedded migrations
-        sqlx::migrate!().run(&pool).await?;
+        migrate_schema(&pool, &options.schema).await?;
+
+        Ok(Db::from(pool)
+            .schema(&options.schema)
+            .read_attempts(options.read_attempts))
+    }
+
+    /// Sets the schema of the metadata tables, which must already be migrated
+    pub fn schema(mut self, schema: &str) -> Self {
+        self.schema = schema.to_string();
+        self
+    }
+
+    /// Sets the number of attempts of read queries
+    pub fn read_attempts(mut self, attempts: u32) -> Self {
+        self.read_attempts = attempts.max(1);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renamed_schema() {
+        let sql = r#"
+            CREATE SCHEMA meta;
+            CREATE TABLE meta.tiles (collection text REFERENCES meta.collections(id));
+            SELECT table_name FROM information_schema.tables WHERE table_schema = 'items';
+            EXECUTE format('ALTER TABLE items.%I ADD COLUMN metadata jsonb', t.table_name);
+            SELECT geometa.items FROM "meta".items_view;
+        "#;
 
-        Ok(Db { pool })
+        let sql = rename_schema(
+            &rename_schema(sql, "meta", "tenant"),
+            "items",
+            "tenant_items",
+        );
+        assert_eq!(
+            sql,
+            r#"
+            CREATE SCHEMA "tenant";
+            CREATE TABLE "tenant".tiles (collection text REFERENCES "tenant".collections(id));
+            SELECT table_name FROM information_schema.tables WHERE table_schema = 'tenant_items';
+            EXECUTE format('ALTER TABLE "tenant_items".%I ADD COLUMN metadata jsonb', t.table_name);
+            SELECT geometa.items FROM "meta".items_view;
+        "#
+        );
     }
 }
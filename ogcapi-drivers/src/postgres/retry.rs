@@ -0,0 +1,53 @@
+use std::{future::Future, io::ErrorKind, time::Duration};
+
+/// Default number of attempts of read queries
+pub const READ_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry, doubled on every attempt
+const BACKOFF: Duration = Duration::from_millis(50);
+
+/// Runs a query, retrying it with exponential backoff on transient errors
+///
+/// Only use this for reads, a write might have been applied even though the
+/// connection failed afterwards.
+pub async fn retry<T, F, Fut>(attempts: u32, mut query: F) -> Result<T, sqlx::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, sqlx::Error>>,
+{
+    let mut backoff = BACKOFF;
+    let mut attempt = 1;
+
+    loop {
+        match super::timing::record(query()).await {
+            Err(e) if attempt < attempts && is_transient(&e) => {
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Whether an error is likely to be resolved by retrying
+///
+/// Pool timeouts are not, the pool is exhausted and retrying only adds to the
+/// queue of waiting requests.
+pub fn is_transient(error: &sqlx::Error) -> bool {
+    match error {
+        sqlx::Error::Io(e) => matches!(
+            e.kind(),
+            ErrorKind::ConnectionReset
+                | ErrorKind::ConnectionAborted
+                | ErrorKind::BrokenPipe
+                | ErrorKind::TimedOut
+                | ErrorKind::UnexpectedEof
+        ),
+        // connection exceptions and administrator shutdown
+        sqlx::Error::Database(e) => e
+            .code()
+            .map_or(false, |code| code.starts_with("08") || code == "57P01"),
+        _ => false,
+    }
+}
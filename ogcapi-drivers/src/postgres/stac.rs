@@ -1,12 +1,12 @@
 use ogcapi_types::{
-    common::{Bbox, Datetime, IntervalDatetime},
+    common::{Bbox, Collection, Datetime, IntervalDatetime},
     features::{Feature, FeatureCollection},
     stac::SearchParams,
 };
 
 use crate::StacSeach;
 
-use super::Db;
+use super::{geometry_column, page_clause, page_params, quote_ident, Db};
 
 #[async_trait::async_trait]
 impl StacSeach for Db {
@@ -14,30 +14,30 @@ impl StacSeach for Db {
         let mut tx = self.pool.begin().await?;
 
         // WITH
-        let mut collection_ids: Vec<String> = sqlx::query_scalar(
+        let mut collections: Vec<sqlx::types::Json<Collection>> = sqlx::query_scalar(&format!(
             r#"
-            SELECT id FROM meta.collections 
+            SELECT collection FROM {} 
             WHERE collection ->> 'type' = 'Collection'
             "#,
-        )
+            self.meta_table("collections")
+        ))
         .fetch_all(&mut tx)
         .await?;
 
-        if let Some(collections) = &query.collections {
-            collection_ids = collections
-                .iter()
-                .filter(|c| collection_ids.contains(c))
-                .map(|c| c.to_owned())
-                .collect();
+        if let Some(ids) = &query.collections {
+            collections.retain(|c| ids.contains(&c.id));
         }
 
-        let union_all_items = collection_ids
+        let union_all_items = collections
             .iter()
-            .map(|collection_id| {
+            .map(|c| {
                 format!(
                     r#"
-                    SELECT * FROM items."{collection_id}"
-                    "#
+                    SELECT id, collection, properties, {} AS geom, links, assets, bbox
+                    FROM {} items
+                    "#,
+                    quote_ident(geometry_column(c)),
+                    self.items_table(c)
                 )
             })
             .collect::<Vec<String>>()
@@ -115,26 +115,33 @@ impl StacSeach for Db {
             where_conditions.push(format!("id IN ('{}')", ids.join("','")))
         }
 
-        // intersects
-        if let Some(intersects) = query.intersects.as_ref() {
-            where_conditions.push(format!("geom && ST_GeomFromGeoJSON('{}')", intersects));
+        // intersects, bound as first parameter
+        let intersects = query.intersects.as_ref().map(|g| g.to_string());
+        if intersects.is_some() {
+            where_conditions.push(
+                "ST_Intersects(geom, ST_Transform(ST_GeomFromGeoJSON($1), ST_SRID(geom)))"
+                    .to_string(),
+            );
         }
 
         let conditions = where_conditions.join(" AND ");
+        let (limit, offset) = page_params(query.limit, query.offset);
 
         // COUNT
-        let number_matched: (i64,) = sqlx::query_as(&format!(
+        let mut count = sqlx::query_as(&format!(
             r#"
             WITH items AS ({union_all_items})
             SELECT count(*) FROM items
             WHERE {conditions}
             "#,
-        ))
-        .fetch_one(&mut tx)
-        .await?;
+        ));
+        if let Some(intersects) = &intersects {
+            count = count.bind(intersects);
+        }
+        let number_matched: (i64,) = count.fetch_one(&mut tx).await?;
 
         // FETCH
-        let features: Option<sqlx::types::Json<Vec<Feature>>> = sqlx::query_scalar(&format!(
+        let mut fetch = sqlx::query_scalar(&format!(
             r#"
             WITH items AS ({union_all_items})
             SELECT array_to_json(array_agg(row_to_json(t)))
@@ -149,17 +156,16 @@ impl StacSeach for Db {
                     bbox
                 FROM items
                 WHERE {conditions}
-                LIMIT {}
-                OFFSET {}
+                {}
             ) t
             "#,
-            query
-                .limit
-                .map_or_else(|| String::from("NULL"), |l| l.to_string()),
-            query.offset.unwrap_or(0)
-        ))
-        .fetch_one(&mut tx)
-        .await?;
+            page_clause(if intersects.is_some() { 2 } else { 1 })
+        ));
+        if let Some(intersects) = &intersects {
+            fetch = fetch.bind(intersects);
+        }
+        let features: Option<sqlx::types::Json<Vec<Feature>>> =
+            fetch.bind(limit).bind(offset).fetch_one(&mut tx).await?;
 
         tx.commit().await?;
 
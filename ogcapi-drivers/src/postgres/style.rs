@@ -7,14 +7,15 @@ use super::Db;
 #[async_trait::async_trait]
 impl StyleTransactions for Db {
     async fn list_styles(&self) -> anyhow::Result<Styles> {
-        let styles: Option<sqlx::types::Json<Vec<Style>>> = sqlx::query_scalar(
+        let styles: Option<sqlx::types::Json<Vec<Style>>> = sqlx::query_scalar(&format!(
             r#"
             SELECT array_to_json(array_agg(row_to_json(t)))
             FROM (
-                SELECT id, title, links FROM meta.styles
+                SELECT id, title, links FROM {styles}
             ) t
             "#,
-        )
+            styles = self.meta_table("styles")
+        ))
         .fetch_one(&self.pool)
         .await?;
 
@@ -23,14 +24,15 @@ impl StyleTransactions for Db {
     }
 
     async fn read_style(&self, id: &str) -> anyhow::Result<Option<serde_json::Value>> {
-        let style: Option<sqlx::types::Json<Stylesheet>> = sqlx::query_scalar(
+        let style: Option<sqlx::types::Json<Stylesheet>> = sqlx::query_scalar(&format!(
             r#"
             SELECT row_to_json(t) as "stylesheet!"
             FROM (
-                SELECT id, value FROM meta.styles WHERE id = $1
+                SELECT id, value FROM {styles} WHERE id = $1
             ) t
             "#,
-        )
+            styles = self.meta_table("styles")
+        ))
         .bind(id)
         .fetch_optional(&self.pool)
         .await?;
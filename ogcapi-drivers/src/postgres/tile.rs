@@ -1,8 +1,15 @@
-use ogcapi_types::tiles::TileMatrixSet;
+use ogcapi_types::{common::Crs, tiles::TileMatrixSet};
 
-use crate::{CollectionTransactions, TileTransactions};
+use crate::{CollectionTransactions, Error, TileTransactions};
 
-use super::Db;
+use super::{geometry_column, quote_ident, quote_literal, retry, Db};
+
+/// Zoom level of a tile matrix of the `WebMercatorQuad`, which is its id
+fn zoom(matrix: &str) -> Result<i32, Error> {
+    matrix
+        .parse()
+        .map_err(|_| Error::Invalid(format!("Invalid tile matrix `{matrix}`")))
+}
 
 #[async_trait::async_trait]
 impl TileTransactions for Db {
@@ -18,27 +25,31 @@ impl TileTransactions for Db {
 
         for collection in collections.split(',') {
             if let Some(c) = self.read_collection(collection).await? {
-                let storage_srid = c.storage_crs.unwrap_or_default().as_srid();
+                let storage_srid = c.storage_crs.clone().unwrap_or_default().as_srid();
+                let geom = quote_ident(geometry_column(&c));
 
                 sql.push(format!(
                     r#"
-                    SELECT ST_AsMVT(mvtgeom, '{0}', 4096, 'geom')
+                    SELECT ST_AsMVT(mvtgeom, {0}, 4096, 'geom')
                     FROM (
                         SELECT
-                            ST_AsMVTGeom(ST_Transform(ST_Force2D(geom), 3857), ST_TileEnvelope($1, $3, $2), 4096, 64, TRUE) AS geom,
-                            '{0}' as collection,
+                            ST_AsMVTGeom(ST_Transform(ST_Force2D({2}), 3857), ST_TileEnvelope($1, $3, $2), 4096, 64, TRUE) AS geom,
+                            {0} as collection,
                             properties
-                        FROM items.{0}
-                        WHERE geom && ST_Transform(ST_TileEnvelope($1, $3, $2, margin => (64.0 / 4096)), {1})
+                        FROM {3} items
+                        WHERE {2} && ST_Transform(ST_TileEnvelope($1, $3, $2, margin => (64.0 / 4096)), {1})
                     ) AS mvtgeom
                     "#,
-                    collection,storage_srid
+                    quote_literal(&c.id),
+                    storage_srid,
+                    geom,
+                    self.items_table(&c)
                 ));
             };
         }
 
         let tiles: Vec<Vec<u8>> = sqlx::query_scalar(&sql.join(" UNION ALL "))
-            .bind(matrix.parse::<i32>().unwrap())
+            .bind(zoom(matrix)?)
             .bind(row as i32)
             .bind(col as i32)
             .fetch_all(&self.pool)
@@ -46,4 +57,162 @@ impl TileTransactions for Db {
 
         Ok(tiles.concat())
     }
+
+    async fn tile_geometries(
+        &self,
+        collections: &str,
+        _tms: &TileMatrixSet,
+        matrix: &str,
+        row: u32,
+        col: u32,
+        extent: u32,
+    ) -> anyhow::Result<Vec<geojson::Geometry>> {
+        let mut sql: Vec<String> = Vec::new();
+
+        for collection in collections.split(',') {
+            if let Some(c) = self.read_collection(collection).await? {
+                let storage_srid = c.storage_crs.clone().unwrap_or_default().as_srid();
+                let geom = quote_ident(geometry_column(&c));
+
+                sql.push(format!(
+                    r#"
+                    SELECT ST_AsMVTGeom(ST_Transform(ST_Force2D({1}), 3857), ST_TileEnvelope($1, $3, $2), $4, 64, TRUE) AS geom
+                    FROM {2} items
+                    WHERE {1} && ST_Transform(ST_TileEnvelope($1, $3, $2, margin => (64.0 / $4)), {0})
+                    "#,
+                    storage_srid,
+                    geom,
+                    self.items_table(&c)
+                ));
+            };
+        }
+
+        if sql.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let geometries: Vec<sqlx::types::Json<geojson::Geometry>> = sqlx::query_scalar(&format!(
+            "SELECT ST_AsGeoJSON(geom)::jsonb FROM ({}) t WHERE geom IS NOT NULL",
+            sql.join(" UNION ALL ")
+        ))
+        .bind(zoom(matrix)?)
+        .bind(row as i32)
+        .bind(col as i32)
+        .bind(extent as i32)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(geometries.into_iter().map(|g| g.0).collect())
+    }
+
+    async fn map_geometries(
+        &self,
+        collection: &str,
+        bbox: &[f64; 4],
+        crs: &Crs,
+    ) -> anyhow::Result<Vec<geojson::Geometry>> {
+        let c = match self.read_collection(collection).await? {
+            Some(c) => c,
+            None => return Ok(Vec::new()),
+        };
+        let storage_srid = c.storage_crs.clone().unwrap_or_default().as_srid();
+        let geom = quote_ident(geometry_column(&c));
+
+        let sql = format!(
+            r#"
+            SELECT ST_AsGeoJSON(ST_Transform(ST_Force2D({geom}), $5))::jsonb
+            FROM {table} items
+            WHERE {geom} && ST_Transform(ST_MakeEnvelope($1, $2, $3, $4, $5), {storage_srid})
+            "#,
+            table = self.items_table(&c)
+        );
+
+        let geometries: Vec<sqlx::types::Json<geojson::Geometry>> =
+            retry(self.read_attempts, || {
+                sqlx::query_scalar(&sql)
+                    .bind(bbox[0])
+                    .bind(bbox[1])
+                    .bind(bbox[2])
+                    .bind(bbox[3])
+                    .bind(crs.as_srid())
+                    .fetch_all(&self.pool)
+            })
+            .await?;
+
+        Ok(geometries.into_iter().map(|g| g.0).collect())
+    }
+
+    async fn read_cached_tile(
+        &self,
+        collection: &str,
+        tms: &TileMatrixSet,
+        matrix: &str,
+        row: u32,
+        col: u32,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        let sql = format!(
+            r#"
+            SELECT tile FROM {}
+            WHERE collection = $1 AND tms = $2 AND matrix = $3 AND "row" = $4 AND col = $5
+            "#,
+            self.meta_table("tiles")
+        );
+
+        let tile = retry(self.read_attempts, || {
+            sqlx::query_scalar(&sql)
+                .bind(collection)
+                .bind(&tms.id)
+                .bind(matrix)
+                .bind(row as i32)
+                .bind(col as i32)
+                .fetch_optional(&self.pool)
+        })
+        .await?;
+
+        Ok(tile)
+    }
+
+    async fn cache_tile(
+        &self,
+        collection: &str,
+        tms: &TileMatrixSet,
+        matrix: &str,
+        row: u32,
+        col: u32,
+        tile: &[u8],
+    ) -> anyhow::Result<()> {
+        sqlx::query(&format!(
+            r#"
+            INSERT INTO {} (collection, tms, matrix, "row", col, tile)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (collection, tms, matrix, "row", col) DO UPDATE SET tile = EXCLUDED.tile
+            "#,
+            self.meta_table("tiles")
+        ))
+        .bind(collection)
+        .bind(&tms.id)
+        .bind(matrix)
+        .bind(row as i32)
+        .bind(col as i32)
+        .bind(tile)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+impl Db {
+    /// Removes the cached tiles of a collection after its features changed
+    pub(crate) async fn invalidate_tiles(&self, collection: &str) -> anyhow::Result<()> {
+        sqlx::query(&format!(
+            "DELETE FROM {} WHERE collection = $1",
+            self.meta_table("tiles")
+        ))
+        .bind(collection)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
 }
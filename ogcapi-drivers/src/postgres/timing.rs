@@ -0,0 +1,29 @@
+use std::{
+    cell::Cell,
+    future::Future,
+    time::{Duration, Instant},
+};
+
+tokio::task_local! {
+    static QUERY_TIME: Cell<Duration>;
+}
+
+/// Runs a future, returning its output along with the time spent in read
+/// queries while it ran
+pub async fn timed<F: Future>(future: F) -> (F::Output, Duration) {
+    QUERY_TIME
+        .scope(Cell::new(Duration::ZERO), async move {
+            let output = future.await;
+            (output, QUERY_TIME.with(Cell::get))
+        })
+        .await
+}
+
+/// Awaits a query, adding its duration to the query time of the current task
+/// if it is accounted
+pub(crate) async fn record<T>(query: impl Future<Output = T>) -> T {
+    let start = Instant::now();
+    let output = query.await;
+    let _ = QUERY_TIME.try_with(|time| time.set(time.get() + start.elapsed()));
+    output
+}
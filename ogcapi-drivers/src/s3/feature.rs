@@ -2,7 +2,7 @@ use aws_sdk_s3::{error::GetObjectErrorKind, types::SdkError};
 
 use ogcapi_types::{
     common::{media_type::GEO_JSON, Crs},
-    features::{Feature, FeatureCollection, Query},
+    features::{AggregationGroup, Feature, FeatureCollection, PropertyValue, Query},
 };
 
 use crate::FeatureTransactions;
@@ -89,4 +89,27 @@ impl FeatureTransactions for S3 {
     ) -> anyhow::Result<FeatureCollection> {
         unimplemented!()
     }
+
+    async fn distinct_values(
+        &self,
+        _collection: &str,
+        _property: &str,
+        _query: &Query,
+    ) -> anyhow::Result<Vec<PropertyValue>> {
+        anyhow::bail!("Distinct values are not supported by the S3 driver")
+    }
+
+    async fn queryables(&self, _collection: &str) -> anyhow::Result<Vec<String>> {
+        anyhow::bail!("Queryables are not supported by the S3 driver")
+    }
+
+    async fn aggregate(
+        &self,
+        _collection: &str,
+        _property: &str,
+        _group_by: Option<&str>,
+        _query: &Query,
+    ) -> anyhow::Result<Vec<AggregationGroup>> {
+        anyhow::bail!("Aggregation is not supported by the S3 driver")
+    }
 }
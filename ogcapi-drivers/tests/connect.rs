@@ -0,0 +1,47 @@
+#[cfg(feature = "postgres")]
+mod postgres {
+    use std::time::Duration;
+
+    use url::Url;
+
+    use ogcapi_drivers::postgres::{Db, DbOptions, LevelFilter};
+
+    #[tokio::test]
+    async fn options() -> anyhow::Result<()> {
+        let url = Url::parse(&std::env::var("DATABASE_URL")?)?;
+
+        let db = Db::setup_with(
+            &url,
+            &DbOptions {
+                max_connections: 2,
+                statement_timeout: Some(Duration::from_secs(1)),
+                log_statements: Some(LevelFilter::Trace),
+                read_attempts: 5,
+                ..Default::default()
+            },
+        )
+        .await?;
+        assert_eq!(db.pool.options().get_max_connections(), 2);
+        assert_eq!(db.read_attempts, 5);
+
+        let timeout: String = sqlx::query_scalar("SHOW statement_timeout")
+            .fetch_one(&db.pool)
+            .await?;
+        assert_eq!(timeout, "1s");
+
+        // longer statements are cancelled
+        let result = sqlx::query("SELECT pg_sleep(2)").execute(&db.pool).await;
+        let error = result.unwrap_err();
+        let code = error.as_database_error().and_then(|e| e.code());
+        assert_eq!(code.as_deref(), Some("57014"));
+
+        // no timeout by default
+        let db = Db::setup(&url).await?;
+        let timeout: String = sqlx::query_scalar("SHOW statement_timeout")
+            .fetch_one(&db.pool)
+            .await?;
+        assert_eq!(timeout, "0");
+
+        Ok(())
+    }
+}
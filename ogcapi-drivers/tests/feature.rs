@@ -0,0 +1,110 @@
+#[cfg(feature = "postgres")]
+mod postgres {
+    use geojson::{Geometry, Value};
+    use serde_json::json;
+
+    use ogcapi_drivers::{postgres::Db, CollectionTransactions, Error, FeatureTransactions};
+    use ogcapi_types::{common::Collection, features::Feature};
+
+    async fn setup(db: &Db) -> Feature {
+        let collection = Collection {
+            id: "coordinates".to_string(),
+            ..Default::default()
+        };
+        db.create_collection(&collection).await.unwrap();
+
+        serde_json::from_value(json!({
+            "collection": "coordinates",
+            "type": "Feature",
+            "properties": {},
+            "geometry": { "type": "Point", "coordinates": [7.428959, 1.513394] }
+        }))
+        .unwrap()
+    }
+
+    #[sqlx::test]
+    async fn longitude_out_of_range(pool: sqlx::PgPool) -> () {
+        let db = Db::from(pool);
+        let mut feature = setup(&db).await;
+
+        feature.geometry = Geometry::new(Value::Point(vec![181.0, 0.0]));
+
+        let err = db.create_feature(&feature).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<Error>(),
+            Some(Error::Invalid(_))
+        ));
+    }
+
+    #[sqlx::test]
+    async fn non_finite_coordinate(pool: sqlx::PgPool) -> () {
+        let db = Db::from(pool);
+        let mut feature = setup(&db).await;
+
+        feature.geometry =
+            Geometry::new(Value::LineString(vec![vec![0.0, 0.0], vec![f64::NAN, 1.0]]));
+
+        let err = db.create_feature(&feature).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<Error>(),
+            Some(Error::Invalid(_))
+        ));
+
+        // valid coordinates are still accepted
+        feature.geometry = Geometry::new(Value::Point(vec![-180.0, 90.0]));
+        db.create_feature(&feature).await.unwrap();
+    }
+
+    #[sqlx::test]
+    async fn missing_collection(pool: sqlx::PgPool) -> () {
+        let db = Db::from(pool);
+        let mut feature = setup(&db).await;
+        feature.collection = Some("missing".to_string());
+
+        let err = db.create_feature(&feature).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<Error>(),
+            Some(Error::NotFound(_))
+        ));
+
+        feature.id = Some("1".to_string());
+        let err = db.update_feature(&feature).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<Error>(),
+            Some(Error::NotFound(_))
+        ));
+
+        let query = serde_json::from_value(json!({})).unwrap();
+        let err = db.list_items("missing", &query).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<Error>(),
+            Some(Error::NotFound(_))
+        ));
+    }
+
+    #[sqlx::test]
+    async fn datetime_index(pool: sqlx::PgPool) -> () {
+        let db = Db::from(pool);
+        let mut feature = setup(&db).await;
+
+        let indexes: Vec<String> = sqlx::query_scalar(
+            "SELECT indexname::text FROM pg_indexes WHERE schemaname = 'items' AND tablename = 'coordinates'",
+        )
+        .fetch_all(&db.pool)
+        .await
+        .unwrap();
+        assert!(indexes.contains(&"coordinates_datetime_idx".to_string()));
+
+        // malformed timestamps and timestamps without offset are indexed as
+        // unknown instead of failing the insert
+        for datetime in ["yesterday", "2023-13-01T00:00:00Z", "2023-01-01T00:00:00"] {
+            for properties in [
+                json!({ "datetime": datetime }),
+                json!({ "start_datetime": datetime, "end_datetime": "2023-01-01T00:00:00Z" }),
+            ] {
+                feature.properties = properties.as_object().cloned();
+                db.create_feature(&feature).await.unwrap();
+            }
+        }
+    }
+}
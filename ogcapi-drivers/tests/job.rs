@@ -1,11 +1,13 @@
 #[cfg(feature = "postgres")]
 mod postgres {
     use ogcapi_drivers::{postgres::Db, JobHandler};
-    use ogcapi_types::processes::{StatusCode, StatusInfo};
+    use ogcapi_types::processes::{
+        InlineOrRefData, InputValueNoObject, Results, StatusCode, StatusInfo,
+    };
 
     #[sqlx::test]
     async fn job_handling(pool: sqlx::PgPool) -> () {
-        let db = Db { pool };
+        let db = Db::from(pool);
 
         let job = StatusInfo {
             job_id: "test-job".to_string(),
@@ -25,4 +27,40 @@ mod postgres {
 
         assert_eq!(info.unwrap().status, StatusCode::Dismissed)
     }
+
+    #[sqlx::test]
+    async fn job_finishing(pool: sqlx::PgPool) -> () {
+        let db = Db::from(pool);
+
+        let mut job = StatusInfo {
+            job_id: "test-job".to_string(),
+            ..Default::default()
+        };
+        db.register(&job).await.unwrap();
+
+        // update
+        job.status = StatusCode::Running;
+        job.progress = Some(50);
+        db.update(&job).await.unwrap();
+
+        let info = db.status(&job.job_id).await.unwrap().unwrap();
+        assert_eq!(info.status, StatusCode::Running);
+        assert_eq!(info.progress, Some(50));
+
+        // finish
+        job.status = StatusCode::Successful;
+        let mut results = Results::default();
+        results.results.insert(
+            "greeting".to_string(),
+            InlineOrRefData::InputValueNoObject(InputValueNoObject::String("Hello".to_string())),
+        );
+        db.finish(&job, Some(&results)).await.unwrap();
+
+        let info = db.status(&job.job_id).await.unwrap().unwrap();
+        assert_eq!(info.status, StatusCode::Successful);
+        assert!(info.finished.is_some());
+
+        let results = db.results(&job.job_id).await.unwrap().unwrap();
+        assert!(results.results.contains_key("greeting"));
+    }
 }
@@ -0,0 +1,31 @@
+#[cfg(feature = "postgres")]
+mod postgres {
+    use ogcapi_drivers::postgres::migrate;
+
+    #[sqlx::test(migrations = false)]
+    async fn migrate_twice(pool: sqlx::PgPool) -> () {
+        migrate(&pool).await.unwrap();
+
+        let applied: i64 = sqlx::query_scalar("SELECT count(*) FROM _sqlx_migrations")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        // second run is a no-op
+        migrate(&pool).await.unwrap();
+
+        let reapplied: i64 = sqlx::query_scalar("SELECT count(*) FROM _sqlx_migrations")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        assert_eq!(applied, reapplied);
+
+        let exists: bool = sqlx::query_scalar("SELECT to_regclass('meta.collections') IS NOT NULL")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        assert!(exists);
+    }
+}
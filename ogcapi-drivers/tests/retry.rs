@@ -0,0 +1,69 @@
+#[cfg(feature = "postgres")]
+mod postgres {
+    use std::{
+        io,
+        sync::atomic::{AtomicU32, Ordering},
+    };
+
+    use ogcapi_drivers::postgres::{is_transient, retry};
+
+    #[tokio::test]
+    async fn transient_error() {
+        let calls = AtomicU32::new(0);
+
+        // flaky connection, reset on the first query
+        let result = retry(3, || async {
+            match calls.fetch_add(1, Ordering::SeqCst) {
+                0 => Err(sqlx::Error::Io(io::ErrorKind::ConnectionReset.into())),
+                _ => Ok("feature"),
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), "feature");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn bounded_attempts() {
+        let calls = AtomicU32::new(0);
+
+        let result: Result<(), _> = retry(3, || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(sqlx::Error::Io(io::ErrorKind::ConnectionReset.into()))
+        })
+        .await;
+
+        assert!(matches!(result, Err(sqlx::Error::Io(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn permanent_error() {
+        let calls = AtomicU32::new(0);
+
+        let result: Result<(), _> = retry(3, || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(sqlx::Error::RowNotFound)
+        })
+        .await;
+
+        assert!(!is_transient(result.as_ref().unwrap_err()));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn pool_timeout() {
+        let calls = AtomicU32::new(0);
+
+        // an exhausted pool is reported right away
+        let result: Result<(), _> = retry(3, || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(sqlx::Error::PoolTimedOut)
+        })
+        .await;
+
+        assert!(matches!(result, Err(sqlx::Error::PoolTimedOut)));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
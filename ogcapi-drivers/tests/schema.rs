@@ -0,0 +1,84 @@
+#[cfg(feature = "postgres")]
+mod postgres {
+    use serde_json::json;
+
+    use ogcapi_drivers::{
+        postgres::{migrate_schema, Db},
+        CollectionTransactions, FeatureTransactions,
+    };
+    use ogcapi_types::{
+        common::{Collection, Crs},
+        features::{Feature, Query},
+    };
+
+    #[sqlx::test]
+    async fn custom_schema(pool: sqlx::PgPool) -> () {
+        migrate_schema(&pool, "tenant").await.unwrap();
+        // idempotent
+        migrate_schema(&pool, "tenant").await.unwrap();
+
+        let db = Db::from(pool.clone()).schema("tenant");
+
+        // create
+        let collection = Collection {
+            id: "places".to_string(),
+            ..Default::default()
+        };
+        db.create_collection(&collection).await.unwrap();
+
+        let mut feature: Feature = serde_json::from_value(json!({
+            "collection": "places",
+            "type": "Feature",
+            "properties": { "name": "Bern" },
+            "geometry": { "type": "Point", "coordinates": [7.44, 46.95] }
+        }))
+        .unwrap();
+        let id = db.create_feature(&feature).await.unwrap();
+
+        // isolated from the default schema
+        let default = Db::from(pool.clone());
+        assert!(default.read_collection("places").await.unwrap().is_none());
+
+        let exists: bool =
+            sqlx::query_scalar(r#"SELECT to_regclass('"tenant_items"."places"') IS NOT NULL"#)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(exists);
+
+        // read
+        let read = db
+            .read_feature("places", &id, &Crs::default())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(read.properties.unwrap()["name"], "Bern");
+
+        let query: Query = serde_json::from_value(json!({})).unwrap();
+        let fc = db.list_items("places", &query).await.unwrap();
+        assert_eq!(fc.number_matched, Some(1));
+
+        // update
+        feature.id = Some(id.clone());
+        feature.properties = Some(json!({ "name": "Berne" }).as_object().unwrap().to_owned());
+        db.update_feature(&feature).await.unwrap();
+
+        let read = db
+            .read_feature("places", &id, &Crs::default())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(read.properties.unwrap()["name"], "Berne");
+
+        // delete
+        db.delete_feature("places", &id).await.unwrap();
+        assert!(db
+            .read_feature("places", &id, &Crs::default())
+            .await
+            .unwrap()
+            .is_none());
+
+        db.delete_collection("places").await.unwrap();
+        assert!(db.read_collection("places").await.unwrap().is_none());
+    }
+}
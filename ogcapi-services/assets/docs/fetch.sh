@@ -0,0 +1,9 @@
+#!/bin/sh
+# Fetches the API documentation bundles embedded with the `docs-assets` feature
+set -e
+
+cd "$(dirname "$0")"
+
+curl -fsSLO https://cdn.jsdelivr.net/npm/redoc@2.0.0/bundles/redoc.standalone.js
+curl -fsSLO https://unpkg.com/swagger-ui-dist@4.11.1/swagger-ui-bundle.js
+curl -fsSLO https://unpkg.com/swagger-ui-dist@4.11.1/swagger-ui.css
@@ -0,0 +1,80 @@
+//! Registry of the coordinate reference systems the server can honor
+//!
+//! Coordinates are transformed by PostGIS, so every CRS advertised by a
+//! collection needs a definition in its `spatial_ref_sys` table. The registry
+//! lists the ones shipped with PostGIS which are commonly used for data
+//! served through OGC APIs.
+
+use std::ops::RangeInclusive;
+
+use ogcapi_types::common::{Authority, Crs};
+
+/// Conformance class of requesting features in other CRS than the default
+pub(crate) const CONFORMANCE: &str = "http://www.opengis.net/spec/ogcapi-features-2/1.0/conf/crs";
+
+/// CRS defined by the OGC with their version
+const OGC: [(&str, &str); 2] = [("1.3", "CRS84"), ("0", "CRS84h")];
+
+/// Ranges of known EPSG codes
+const EPSG: [RangeInclusive<i32>; 24] = [
+    // ED50, WGS 84, ETRS89, NAD83 and other geographic CRS
+    4000..=4999,
+    // WGS 84 / Pseudo-Mercator and World Mercator
+    3857..=3857,
+    3395..=3395,
+    // ETRS89 / LAEA and LCC Europe
+    3034..=3035,
+    // ETRS89 / UTM
+    25828..=25838,
+    // WGS 84 / UTM north and south
+    32601..=32660,
+    32701..=32760,
+    // NAD83 / UTM
+    26901..=26923,
+    // NAD83 / Conus Albers
+    5070..=5070,
+    // CH1903 / LV03 and CH1903+ / LV95
+    21781..=21781,
+    2056..=2056,
+    // OSGB 1936 / British National Grid
+    27700..=27700,
+    // RGF93 / Lambert-93
+    2154..=2154,
+    // DHDN / 3-degree Gauss-Kruger
+    31466..=31469,
+    // Amersfoort / RD New
+    28992..=28992,
+    // ETRS89 / TM35FIN and SWEREF99 TM
+    3067..=3067,
+    3006..=3006,
+    // GDA94 / Australian Albers and NZGD2000 / New Zealand Transverse Mercator
+    3577..=3577,
+    2193..=2193,
+    // Polar stereographic
+    3413..=3413,
+    3031..=3031,
+    3976..=3976,
+    // Austria and Belgium Lambert
+    31287..=31287,
+    3812..=3812,
+];
+
+/// All CRS known to the server
+pub(crate) fn known() -> impl Iterator<Item = Crs> {
+    OGC.iter()
+        .map(|(version, code)| Crs::new(Authority::OGC, version, code))
+        .chain(EPSG.into_iter().flatten().map(Crs::from_epsg))
+}
+
+/// Whether the CRS is known to the server
+pub(crate) fn is_known(crs: &Crs) -> bool {
+    match crs.authority {
+        Authority::OGC => OGC
+            .iter()
+            .any(|(version, code)| crs.version == *version && crs.code == *code),
+        Authority::EPSG => crs
+            .code
+            .parse::<i32>()
+            .map_or(false, |code| EPSG.iter().any(|range| range.contains(&code))),
+    }
+}
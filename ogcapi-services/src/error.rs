@@ -1,12 +1,53 @@
 use axum::{
     headers::HeaderMap,
-    http::{header::CONTENT_TYPE, StatusCode},
+    http::{
+        header::{CONTENT_TYPE, RETRY_AFTER},
+        StatusCode,
+    },
     response::{IntoResponse, Response},
     Json,
 };
 
 use ogcapi_types::common::{media_type::PROBLEM_JSON, Exception};
 
+/// Seconds clients are asked to wait before retrying an overloaded service
+const RETRY_AFTER_SECONDS: u64 = 1;
+
+/// Problem of the given status
+///
+/// The `type` is `about:blank` without a base, otherwise the base joined
+/// with the kind of error, e.g. `https://example.com/errors/not-found`.
+pub(crate) fn problem(status: StatusCode, base: Option<&url::Url>) -> Exception {
+    let r#type = match base {
+        Some(base) => format!(
+            "{}/{}",
+            base.as_str().trim_end_matches('/'),
+            problem_kind(status)
+        ),
+        None => "about:blank".to_string(),
+    };
+
+    let exception = Exception::new(r#type).status(status.as_u16());
+
+    match status.canonical_reason() {
+        Some(reason) => exception.title(reason),
+        None => exception,
+    }
+}
+
+/// Kind of error of a status, its reason phrase in kebab case
+fn problem_kind(status: StatusCode) -> String {
+    match status.canonical_reason() {
+        Some(reason) => reason
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|s| !s.is_empty())
+            .map(str::to_ascii_lowercase)
+            .collect::<Vec<_>>()
+            .join("-"),
+        None => status.as_str().to_string(),
+    }
+}
+
 /// A common error type that can be used throughout the API.
 ///
 /// Can be returned in a `Result` from an API handler function.
@@ -22,7 +63,7 @@ pub enum Error {
 
     /// Return `500 Internal Server Error` on a `anyhow::Error`.
     #[error("an internal server error occurred")]
-    Anyhow(#[from] anyhow::Error),
+    Anyhow(anyhow::Error),
 
     /// Return `500 Internal Server Error` on a `url::ParseError`.
     #[error("an internal server error occurred")]
@@ -37,6 +78,26 @@ pub enum Error {
     Exception(StatusCode, String),
 }
 
+impl From<anyhow::Error> for Error {
+    fn from(e: anyhow::Error) -> Self {
+        match ogcapi_drivers::Error::classify(e) {
+            Ok(ogcapi_drivers::Error::NotFound(message)) => {
+                Error::Exception(StatusCode::NOT_FOUND, message)
+            }
+            Ok(ogcapi_drivers::Error::Conflict(message)) => {
+                Error::Exception(StatusCode::CONFLICT, message)
+            }
+            Ok(ogcapi_drivers::Error::Invalid(message)) => {
+                Error::Exception(StatusCode::BAD_REQUEST, message)
+            }
+            Ok(ogcapi_drivers::Error::Unavailable(message)) => {
+                Error::Exception(StatusCode::SERVICE_UNAVAILABLE, message)
+            }
+            Err(e) => Error::Anyhow(e),
+        }
+    }
+}
+
 impl Error {
     fn status_code(&self) -> StatusCode {
         match self {
@@ -78,10 +139,13 @@ impl IntoResponse for Error {
             }
         };
 
-        let exception = Exception::new(status.as_u16()).detail(message);
+        let exception = problem(status, None).detail(message);
 
         let mut headers = HeaderMap::new();
         headers.insert(CONTENT_TYPE, PROBLEM_JSON.parse().unwrap());
+        if status == StatusCode::SERVICE_UNAVAILABLE {
+            headers.insert(RETRY_AFTER, RETRY_AFTER_SECONDS.into());
+        }
 
         (status, headers, Json(exception)).into_response()
     }
@@ -1,23 +1,34 @@
 use anyhow::Context;
 use axum::{
-    extract::{FromRequestParts, Host, OriginalUri},
-    http::{request::Parts, StatusCode},
+    body::HttpBody,
+    extract::{FromRequest, FromRequestParts, OriginalUri},
+    http::{
+        header::{ACCEPT_LANGUAGE, FORWARDED, HOST},
+        request::Parts,
+        Request, StatusCode,
+    },
+    BoxError, Json,
 };
+use serde::de::DeserializeOwned;
+use serde_json::Value;
 use url::Url;
 
-use crate::Error;
+use crate::{AppState, Error};
 
 /// Extractor for the remote URL
+///
+/// Scheme and host are taken from the `Forwarded` or `X-Forwarded-Proto` and
+/// `X-Forwarded-Host` headers if forwarded headers are trusted.
 pub(crate) struct RemoteUrl(pub Url);
 
 #[axum::async_trait]
-impl<S> FromRequestParts<S> for RemoteUrl
-where
-    S: Send + Sync,
-{
+impl FromRequestParts<AppState> for RemoteUrl {
     type Rejection = Error;
 
-    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
         let uri = OriginalUri::from_request_parts(parts, state)
             .await
             .expect("Infalllible, hence this should never fail");
@@ -25,23 +36,65 @@ where
         let url = if uri.0.scheme().is_some() {
             uri.0.to_string()
         } else {
-            let host = Host::from_request_parts(parts, state)
-                .await
-                .context("Unabe to extract host")?;
+            let trusted = state.settings.trust_forwarded_headers;
 
-            let proto = parts
-                .headers
-                .get("X-Forwarded-Proto")
-                .and_then(|f| f.to_str().ok())
-                .unwrap_or("http");
+            let host = if trusted {
+                forwarded(parts, "host", "X-Forwarded-Host")
+            } else {
+                None
+            }
+            .or_else(|| {
+                parts
+                    .headers
+                    .get(HOST)
+                    .and_then(|host| host.to_str().ok())
+                    .map(ToOwned::to_owned)
+            })
+            .or_else(|| parts.uri.authority().map(ToString::to_string))
+            .context("Unabe to extract host")?;
 
-            format!("{}://{}{}", proto, host.0, uri.0)
+            let proto = if trusted {
+                forwarded(parts, "proto", "X-Forwarded-Proto")
+            } else {
+                None
+            };
+
+            format!("{}://{}{}", proto.as_deref().unwrap_or("http"), host, uri.0)
         };
 
         Ok(RemoteUrl(Url::parse(&url)?))
     }
 }
 
+/// Value set by the proxy in front of the service, the last one of the
+/// `Forwarded` header or else of the `X-Forwarded-*` one
+///
+/// Preceding values are appended by further proxies or sent by the client
+/// and can't be trusted.
+fn forwarded(parts: &Parts, key: &str, header: &str) -> Option<String> {
+    let last = |name: &str| {
+        parts
+            .headers
+            .get_all(name)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .last()
+    };
+
+    last(FORWARDED.as_str())
+        .and_then(|element| {
+            element.split(';').find_map(|pair| {
+                let (k, v) = pair.trim().split_once('=')?;
+                k.eq_ignore_ascii_case(key)
+                    .then(|| v.trim_matches('"').to_string())
+            })
+        })
+        .or_else(|| last(header).map(ToOwned::to_owned))
+}
+
 /// Extractor that deserializes query strings into some type `T` with [`serde_qs`]
 pub(crate) struct Qs<T>(pub(crate) T);
 
@@ -61,3 +114,84 @@ where
         }
     }
 }
+
+/// Extractor that deserializes JSON request bodies into some type `T`,
+/// reporting the path of invalid values
+pub(crate) struct JsonBody<T>(pub(crate) T);
+
+#[axum::async_trait]
+impl<S, B, T> FromRequest<S, B> for JsonBody<T>
+where
+    T: DeserializeOwned,
+    B: HttpBody + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<BoxError>,
+    S: Send + Sync,
+{
+    type Rejection = Error;
+
+    async fn from_request(request: Request<B>, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<Value>::from_request(request, state)
+            .await
+            .map_err(|rejection| Error::Exception(rejection.status(), rejection.body_text()))?;
+
+        from_json(value).map(JsonBody)
+    }
+}
+
+/// Deserializes a JSON value, failing with `400 Bad Request` naming the path
+/// of the invalid value, e.g. `geometry.coordinates[0]`
+pub(crate) fn from_json<T: DeserializeOwned>(value: Value) -> Result<T, Error> {
+    serde_path_to_error::deserialize(value).map_err(|e| {
+        let message = match e.path().to_string().as_str() {
+            "." => e.inner().to_string(),
+            path => format!("Invalid `{path}`: {}", e.inner()),
+        };
+        Error::Exception(StatusCode::BAD_REQUEST, message)
+    })
+}
+
+/// Extractor for the languages accepted by the client, ordered by preference
+pub(crate) struct AcceptLanguage(pub(crate) Vec<String>);
+
+#[axum::async_trait]
+impl<S> FromRequestParts<S> for AcceptLanguage
+where
+    S: Send + Sync,
+{
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        let mut languages: Vec<(String, f32)> = parts
+            .headers
+            .get_all(ACCEPT_LANGUAGE)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .filter_map(|range| {
+                let mut parts = range.split(';');
+                let tag = parts.next()?.trim().to_lowercase();
+                let q = parts
+                    .find_map(|p| p.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.parse().ok())?;
+                (!tag.is_empty() && q > 0.0).then_some((tag, q))
+            })
+            .collect();
+
+        languages.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        // an explicit `lang` parameter, as used by alternate language links,
+        // takes precedence
+        let lang = parts.uri.query().and_then(|query| {
+            url::form_urlencoded::parse(query.as_bytes())
+                .find(|(key, _)| key == "lang")
+                .map(|(_, value)| value.to_lowercase())
+        });
+
+        Ok(AcceptLanguage(
+            lang.into_iter()
+                .chain(languages.into_iter().map(|(tag, _)| tag))
+                .collect(),
+        ))
+    }
+}
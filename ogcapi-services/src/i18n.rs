@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+
+use url::Url;
+
+use ogcapi_types::common::{
+    link_rel::{ATERNATE, SELF},
+    media_type::JSON,
+    Collection, LandingPage, Link, Links, Translation,
+};
+
+use crate::extractors::AcceptLanguage;
+
+/// Resources with translatable metadata
+pub(crate) trait Translate {
+    /// Applies the best matching translation and returns its language
+    fn translate(&mut self, accept: &AcceptLanguage, default: &str) -> String;
+
+    /// Languages translations are available in
+    fn languages(&self) -> Vec<String>;
+}
+
+impl Translate for Collection {
+    fn languages(&self) -> Vec<String> {
+        languages(&self.translations)
+    }
+
+    fn translate(&mut self, accept: &AcceptLanguage, default: &str) -> String {
+        translate(
+            &mut self.title,
+            &mut self.description,
+            self.translations.take(),
+            accept,
+            default,
+        )
+    }
+}
+
+impl Translate for LandingPage {
+    fn languages(&self) -> Vec<String> {
+        languages(&self.translations)
+    }
+
+    fn translate(&mut self, accept: &AcceptLanguage, default: &str) -> String {
+        translate(
+            &mut self.title,
+            &mut self.description,
+            self.translations.take(),
+            accept,
+            default,
+        )
+    }
+}
+
+fn languages(translations: &Option<HashMap<String, Translation>>) -> Vec<String> {
+    let mut languages: Vec<String> = translations
+        .iter()
+        .flatten()
+        .map(|(l, _)| l.to_owned())
+        .collect();
+    languages.sort();
+    languages
+}
+
+/// Sets the language of the self link of a translated resource and links its
+/// representations in the other available languages
+pub(crate) fn link_languages(
+    links: &mut Links,
+    url: &Url,
+    language: &str,
+    default: &str,
+    languages: Vec<String>,
+) {
+    if let Some(link) = links.iter_mut().find(|l| l.rel == SELF) {
+        link.hreflang = Some(language.to_owned());
+    }
+
+    links.retain(|l| !(l.rel == ATERNATE && l.hreflang.is_some()));
+
+    let others = std::iter::once(default.to_owned())
+        .chain(languages.into_iter().filter(|l| l != default))
+        .filter(|l| !l.eq_ignore_ascii_case(language));
+
+    for other in others {
+        let mut href = url.clone();
+        let pairs: Vec<(String, String)> = url
+            .query_pairs()
+            .filter(|(key, _)| key != "lang")
+            .map(|(key, value)| (key.into_owned(), value.into_owned()))
+            .collect();
+        href.query_pairs_mut()
+            .clear()
+            .extend_pairs(pairs)
+            .append_pair("lang", &other);
+
+        links.push(Link::new(href, ATERNATE).mediatype(JSON).hreflang(other));
+    }
+}
+
+fn translate(
+    title: &mut Option<String>,
+    description: &mut Option<String>,
+    translations: Option<HashMap<String, Translation>>,
+    accept: &AcceptLanguage,
+    default: &str,
+) -> String {
+    let translations = translations.unwrap_or_default();
+
+    for range in accept.0.iter() {
+        if range == "*" || matches(range, default) {
+            break;
+        }
+
+        if let Some((language, translation)) = translations
+            .iter()
+            .find(|(language, _)| matches(range, language))
+        {
+            if translation.title.is_some() {
+                *title = translation.title.to_owned();
+            }
+            if translation.description.is_some() {
+                *description = translation.description.to_owned();
+            }
+            return language.to_owned();
+        }
+    }
+
+    default.to_owned()
+}
+
+/// Whether a language range matches a language tag, ignoring subtags
+fn matches(range: &str, tag: &str) -> bool {
+    let primary = |s: &str| s.split('-').next().unwrap_or_default().to_lowercase();
+    range.eq_ignore_ascii_case(tag) || primary(range) == primary(tag)
+}
@@ -0,0 +1,108 @@
+//! JSON-FG encoding of feature collections
+//!
+//! JSON-FG extends GeoJSON with members for the coordinate reference system,
+//! temporal extent and geometries in other CRS than WGS 84. Documents stay
+//! valid GeoJSON, so they can also be served as a profile of it.
+//!
+//! The stored geometry is mapped into `place`, or into `geometry` if it is in
+//! WGS 84 as required by GeoJSON. The `datetime` property, or the
+//! `start_datetime` and `end_datetime` properties, are mapped into `time`.
+
+use serde_json::{json, Value};
+
+use ogcapi_types::{
+    common::{
+        media_type::{GEO_JSON, JSON_FG, JSON_FG_PROFILE},
+        Crs,
+    },
+    features::{Feature, FeatureCollection},
+};
+
+/// Conformance class of the JSON-FG core requirements
+pub(crate) const CORE: &str = "http://www.opengis.net/spec/json-fg-1/0.1/conf/core";
+
+/// Returns the content type of the response if the `Accept` header asks for
+/// JSON-FG, either by its media type or as profile of GeoJSON
+pub(crate) fn negotiate(accept: &str) -> Option<String> {
+    accept.split(',').find_map(|range| {
+        let mut parts = range.split(';').map(str::trim);
+        match parts.next()? {
+            JSON_FG => Some(JSON_FG.to_string()),
+            GEO_JSON
+                if parts.any(|parameter| {
+                    parameter
+                        .strip_prefix("profile=")
+                        .map_or(false, |profiles| {
+                            profiles
+                                .trim_matches('"')
+                                .split(' ')
+                                .any(|profile| profile == JSON_FG_PROFILE)
+                        })
+                }) =>
+            {
+                Some(format!(r#"{GEO_JSON}; profile="{JSON_FG_PROFILE}""#))
+            }
+            _ => None,
+        }
+    })
+}
+
+/// Encodes a feature collection as JSON-FG
+pub(crate) fn encode(fc: &FeatureCollection, feature_type: &str, crs: &Crs) -> Value {
+    let mut value = serde_json::to_value(fc).unwrap();
+
+    value["conformsTo"] = json!([CORE]);
+    value["features"] = fc
+        .features
+        .iter()
+        .map(|feature| encode_feature(feature, feature_type, crs))
+        .collect();
+
+    value
+}
+
+fn encode_feature(feature: &Feature, feature_type: &str, crs: &Crs) -> Value {
+    let mut value = serde_json::to_value(feature).unwrap();
+
+    value["featureType"] = json!(feature_type);
+    value["coordRefSys"] = json!(crs.to_string());
+
+    if is_wgs84(crs) {
+        value["place"] = Value::Null;
+    } else {
+        value["place"] = value["geometry"].take();
+    }
+
+    value["time"] = feature
+        .properties
+        .as_ref()
+        .map_or(Value::Null, |properties| {
+            match (
+                properties.get("datetime").and_then(Value::as_str),
+                properties.get("start_datetime").and_then(Value::as_str),
+                properties.get("end_datetime").and_then(Value::as_str),
+            ) {
+                (Some(datetime), _, _) => instant(datetime),
+                (None, None, None) => Value::Null,
+                (None, start, end) => {
+                    json!({ "interval": [start.unwrap_or(".."), end.unwrap_or("..")] })
+                }
+            }
+        });
+
+    value
+}
+
+/// Whether coordinates are in WGS 84 longitude, latitude (and height) order
+fn is_wgs84(crs: &Crs) -> bool {
+    matches!(crs.to_string().rsplit('/').next(), Some("CRS84" | "CRS84h"))
+}
+
+/// Encodes a timestamp or date as temporal instant
+fn instant(datetime: &str) -> Value {
+    if datetime.contains('T') {
+        json!({ "timestamp": datetime })
+    } else {
+        json!({ "date": datetime })
+    }
+}
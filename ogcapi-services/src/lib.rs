@@ -1,19 +1,38 @@
 mod config;
+mod crs;
 mod error;
 mod extractors;
+mod i18n;
+#[cfg(feature = "features")]
+mod jsonfg;
+mod middleware;
 mod openapi;
+mod pagination;
 #[cfg(feature = "processes")]
 mod processor;
+#[cfg(feature = "tiles")]
+mod render;
+pub mod routers;
 mod routes;
 mod service;
 mod state;
 pub mod telemetry;
+#[cfg(feature = "features")]
+mod topojson;
+mod vertices;
+#[cfg(feature = "features")]
+mod well_known;
+#[cfg(feature = "processes")]
+mod worker;
 
-pub use config::Config;
+pub use config::{
+    ApiKey, Config, ItemCount, LandingPageLink, PaginationStyle, Permission, Settings,
+    TrailingSlash,
+};
 pub use error::Error;
 pub use openapi::OpenAPI;
-pub use service::Service;
-pub use state::AppState;
+pub use service::{app, Service};
+pub use state::{AppState, Drivers};
 
 #[cfg(feature = "processes")]
 pub use processor::{Greeter, Processor};
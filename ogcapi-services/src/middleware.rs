@@ -0,0 +1,422 @@
+use std::{
+    io::Read,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use axum::{
+    body::{self, Body, Full, HttpBody},
+    extract::State,
+    http::{
+        header::{CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE, LOCATION},
+        Method, Request, StatusCode, Uri,
+    },
+    middleware::{from_fn_with_state, Next},
+    response::{IntoResponse, Response},
+    Router,
+};
+use subtle::ConstantTimeEq;
+
+use ogcapi_drivers::postgres::timed;
+use ogcapi_types::common::{
+    media_type::{GEO_JSON, JSON, PROBLEM_JSON},
+    Access, Collection, Exception,
+};
+
+use crate::{config::TrailingSlash, error::problem, AppState, Error, Permission, Settings};
+
+/// Normalizes request paths before routing
+///
+/// Trailing slashes are handled according to the configured strategy and
+/// collection ids are optionally lowercased.
+pub(crate) async fn normalize_path<B>(
+    State(settings): State<Arc<Settings>>,
+    mut request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let path = request.uri().path();
+
+    let mut normalized = path.to_string();
+
+    let trailing_slash = path.len() > 1 && path.ends_with('/');
+    if trailing_slash && settings.trailing_slash != TrailingSlash::Strict {
+        normalized = format!("/{}", path.trim_matches('/'));
+    }
+
+    if settings.lowercase_collection_ids {
+        if let Some(rest) = normalized.strip_prefix("/collections/") {
+            let (id, rest) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+            normalized = format!("/collections/{}{}", id.to_lowercase(), rest);
+        }
+    }
+
+    if normalized == path {
+        return next.run(request).await;
+    }
+
+    let path_and_query = match request.uri().query() {
+        Some(query) => format!("{}?{}", normalized, query),
+        None => normalized,
+    };
+
+    if trailing_slash && settings.trailing_slash == TrailingSlash::Redirect {
+        return (StatusCode::PERMANENT_REDIRECT, [(LOCATION, path_and_query)]).into_response();
+    }
+
+    let mut parts = request.uri().clone().into_parts();
+    parts.path_and_query = Some(path_and_query.parse().expect("valid path"));
+    *request.uri_mut() = Uri::from_parts(parts).expect("valid uri");
+
+    next.run(request).await
+}
+
+/// Permission of an API key, compared in constant time against all configured
+/// keys to not leak matching prefixes through the response time
+fn find_api_key(settings: &Settings, key: &str) -> Option<Permission> {
+    settings.api_keys.iter().fold(None, |found, api_key| {
+        if bool::from(api_key.key.as_bytes().ct_eq(key.as_bytes())) {
+            Some(api_key.permission)
+        } else {
+            found
+        }
+    })
+}
+
+/// Checks the `X-API-Key` header against the configured API keys
+///
+/// Safe methods require at least `read` permission, all others `write`.
+///
+/// The resolved [`Principal`] is added to the request extensions. Anonymous
+/// reads of a collection and its items are passed on, the access policy of the
+/// collection is enforced by the route.
+pub(crate) async fn api_key<B>(
+    State(settings): State<Arc<Settings>>,
+    mut request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    if settings.api_keys.is_empty() {
+        request
+            .extensions_mut()
+            .insert(Principal(Some(Permission::Write)));
+        return next.run(request).await;
+    }
+
+    let key = request
+        .headers()
+        .get("X-API-Key")
+        .and_then(|v| v.to_str().ok());
+
+    let permission = match key {
+        Some(key) => match find_api_key(&settings, key) {
+            Some(permission) => Some(permission),
+            None => {
+                return Error::Exception(StatusCode::UNAUTHORIZED, "Invalid API key".to_string())
+                    .into_response()
+            }
+        },
+        None => None,
+    };
+
+    request.extensions_mut().insert(Principal(permission));
+
+    let required = match *request.method() {
+        Method::GET | Method::HEAD | Method::OPTIONS => Permission::Read,
+        _ => Permission::Write,
+    };
+
+    let permission = match permission {
+        Some(permission) => permission,
+        None if required == Permission::Read && is_collection_scoped(request.uri().path()) => {
+            return next.run(request).await
+        }
+        None => {
+            return Error::Exception(StatusCode::UNAUTHORIZED, "Missing API key".to_string())
+                .into_response()
+        }
+    };
+
+    if permission < required {
+        return Error::Exception(
+            StatusCode::FORBIDDEN,
+            format!("API key lacks `{:?}` permission", required).to_lowercase(),
+        )
+        .into_response();
+    }
+
+    next.run(request).await
+}
+
+/// Whether a path addresses a collection or its items, which enforce the
+/// access policy of the collection
+fn is_collection_scoped(path: &str) -> bool {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    matches!(
+        segments.as_slice(),
+        ["collections", _] | ["collections", _, "items" | "properties", ..]
+    )
+}
+
+/// Permission of the client making a request, `None` if anonymous
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Principal(pub(crate) Option<Permission>);
+
+impl Principal {
+    /// Checks whether the principal may read the collection
+    pub(crate) fn authorize_read(&self, collection: &Collection) -> Result<(), Error> {
+        if self.0.is_some() || collection.access == Some(Access::Public) {
+            Ok(())
+        } else {
+            Err(Error::Exception(
+                StatusCode::FORBIDDEN,
+                format!("Collection `{}` is not public", collection.id),
+            ))
+        }
+    }
+}
+
+/// Rejects request bodies which are not JSON before they are deserialized
+///
+/// Applies to `POST`, `PUT` and `PATCH` requests and accepts
+/// `application/json` and `application/geo+json`.
+pub(crate) async fn json_content_type<B>(request: Request<B>, next: Next<B>) -> Response {
+    if !matches!(
+        *request.method(),
+        Method::POST | Method::PUT | Method::PATCH
+    ) {
+        return next.run(request).await;
+    }
+
+    let media_type = request
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(';').next())
+        .map(|v| v.trim().to_ascii_lowercase());
+
+    match media_type.as_deref() {
+        Some(JSON) | Some(GEO_JSON) => next.run(request).await,
+        Some(media_type) => Error::Exception(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            format!(
+                "Unsupported media type `{}`, expected `{}` or `{}`",
+                media_type, JSON, GEO_JSON
+            ),
+        )
+        .into_response(),
+        None => Error::Exception(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            format!(
+                "Missing `Content-Type`, expected `{}` or `{}`",
+                JSON, GEO_JSON
+            ),
+        )
+        .into_response(),
+    }
+}
+
+/// Responds with `504 Gateway Timeout` if the handler does not finish in time
+///
+/// The handler and its pending queries are dropped, postgres cancels the
+/// statements by the `statement_timeout` of the connections.
+pub(crate) async fn timeout<B>(
+    State(duration): State<Duration>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    match tokio::time::timeout(duration, next.run(request)).await {
+        Ok(response) => response,
+        Err(_) => Error::Exception(
+            StatusCode::GATEWAY_TIMEOUT,
+            format!("Request timed out after {} ms", duration.as_millis()),
+        )
+        .into_response(),
+    }
+}
+
+/// Applies the [`timeout`] in milliseconds to the routes of a router
+pub(crate) fn route_timeout(router: Router<AppState>, millis: Option<u64>) -> Router<AppState> {
+    match millis {
+        Some(millis) => {
+            router.route_layer(from_fn_with_state(Duration::from_millis(millis), timeout))
+        }
+        None => router,
+    }
+}
+
+/// Reserializes JSON responses with lexicographically sorted object keys
+pub(crate) async fn sort_json_keys<B>(request: Request<B>, next: Next<B>) -> Response {
+    let response = next.run(request).await;
+
+    let is_json = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(';').next())
+        .map_or(false, |v| v == JSON || v.ends_with("+json"));
+
+    if !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+
+    let bytes = match hyper::body::to_bytes(body).await {
+        Ok(bytes) => bytes,
+        Err(e) => return Error::Anyhow(e.into()).into_response(),
+    };
+
+    // `serde_json::Map` is ordered by key
+    let bytes = match serde_json::from_slice::<serde_json::Value>(&bytes) {
+        Ok(value) => serde_json::to_vec(&value).expect("serialize value"),
+        Err(_) => bytes.to_vec(),
+    };
+
+    parts.headers.remove(CONTENT_LENGTH);
+
+    Response::from_parts(parts, body::boxed(Full::from(bytes)))
+}
+
+/// Types problem responses below the configured base
+///
+/// Only problems typed `about:blank` are changed, specific types are kept.
+pub(crate) async fn problem_type<B>(
+    State(base): State<url::Url>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let response = next.run(request).await;
+
+    let is_problem = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map_or(false, |v| v == PROBLEM_JSON);
+
+    if !is_problem {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+
+    let bytes = match hyper::body::to_bytes(body).await {
+        Ok(bytes) => bytes,
+        Err(e) => return Error::Anyhow(e.into()).into_response(),
+    };
+
+    let bytes = match serde_json::from_slice::<Exception>(&bytes) {
+        Ok(mut exception) if exception.r#type == "about:blank" => {
+            exception.r#type = problem(parts.status, Some(&base)).r#type;
+            serde_json::to_vec(&exception).expect("serialize exception")
+        }
+        _ => bytes.to_vec(),
+    };
+
+    parts.headers.remove(CONTENT_LENGTH);
+
+    Response::from_parts(parts, body::boxed(Full::from(bytes)))
+}
+
+/// Decompresses `gzip` and `deflate` encoded request bodies
+///
+/// Other encodings than `identity` are rejected, as are bodies exceeding
+/// `limit` bytes before or after decoding.
+pub(crate) async fn decompress(
+    State(limit): State<usize>,
+    request: Request<Body>,
+    next: Next<Body>,
+) -> Response {
+    let encodings: Vec<String> = request
+        .headers()
+        .get_all(CONTENT_ENCODING)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .map(|v| v.trim().to_ascii_lowercase())
+        .filter(|v| !v.is_empty() && v != "identity")
+        .collect();
+
+    if encodings.is_empty() {
+        return next.run(request).await;
+    }
+
+    if let Some(encoding) = encodings
+        .iter()
+        .find(|e| !["gzip", "deflate"].contains(&e.as_str()))
+    {
+        return Error::Exception(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            format!("Unsupported content encoding `{}`", encoding),
+        )
+        .into_response();
+    }
+
+    let (mut parts, mut body) = request.into_parts();
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        match chunk {
+            Ok(chunk) if bytes.len() + chunk.len() <= limit => bytes.extend_from_slice(&chunk),
+            Ok(_) => return payload_too_large(limit),
+            Err(e) => return Error::Anyhow(e.into()).into_response(),
+        }
+    }
+
+    // encodings are listed in the order they were applied
+    for encoding in encodings.iter().rev() {
+        // read one byte more than allowed to detect oversized bodies
+        let max = limit as u64 + 1;
+        let mut decoded = Vec::new();
+        let result = match encoding.as_str() {
+            "gzip" => flate2::read::GzDecoder::new(&bytes[..])
+                .take(max)
+                .read_to_end(&mut decoded),
+            _ => flate2::read::ZlibDecoder::new(&bytes[..])
+                .take(max)
+                .read_to_end(&mut decoded),
+        };
+        if let Err(e) = result {
+            return Error::Exception(
+                StatusCode::BAD_REQUEST,
+                format!("Unable to decode `{}` body: {}", encoding, e),
+            )
+            .into_response();
+        }
+        if decoded.len() > limit {
+            return payload_too_large(limit);
+        }
+        bytes = decoded;
+    }
+
+    parts.headers.remove(CONTENT_ENCODING);
+    parts.headers.remove(CONTENT_LENGTH);
+
+    next.run(Request::from_parts(parts, Body::from(bytes)))
+        .await
+}
+
+fn payload_too_large(limit: usize) -> Response {
+    Error::Exception(
+        StatusCode::PAYLOAD_TOO_LARGE,
+        format!("Request body exceeds {} bytes", limit),
+    )
+    .into_response()
+}
+
+/// Reports the time spent in database reads and in total handling the request
+/// in a `Server-Timing` header
+pub(crate) async fn server_timing<B>(request: Request<B>, next: Next<B>) -> Response {
+    let start = Instant::now();
+    let (mut response, db) = timed(next.run(request)).await;
+    let total = start.elapsed();
+
+    let value = format!(
+        "db;dur={:.3};desc=\"Database reads\", total;dur={:.3}",
+        db.as_secs_f64() * 1000.0,
+        total.as_secs_f64() * 1000.0
+    );
+    response
+        .headers_mut()
+        .insert("Server-Timing", value.parse().unwrap());
+
+    response
+}
@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+
+use axum::http::StatusCode;
+use url::Url;
+
+use ogcapi_types::common::{
+    link_rel::{NEXT, PREV},
+    Link,
+};
+
+use crate::{config::PaginationStyle, Error, Result};
+
+/// Query parameter of the page token
+const TOKEN: &str = "token";
+
+/// Resolves the offset of the requested page
+///
+/// With token based pagination the `token` parameter is taken from the
+/// additional parameters, so it is not mistaken for a property filter.
+pub(crate) fn resolve(
+    style: PaginationStyle,
+    offset: &mut Option<usize>,
+    parameters: &mut HashMap<String, String>,
+) -> Result<()> {
+    let token = parameters.remove(TOKEN);
+
+    if let (PaginationStyle::Token, Some(token)) = (style, token) {
+        *offset = Some(decode(&token)?);
+    }
+
+    Ok(())
+}
+
+/// Page of a listing, shared by all paginated routes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Page {
+    pub(crate) offset: usize,
+    pub(crate) limit: usize,
+}
+
+impl Page {
+    /// Page of the requested `offset` and `limit`, the limit falls back to the
+    /// default and is clamped to the maximum
+    pub(crate) fn new(
+        offset: Option<usize>,
+        limit: Option<usize>,
+        default_limit: usize,
+        max_limit: usize,
+    ) -> Self {
+        Page {
+            offset: offset.unwrap_or(0),
+            limit: limit.unwrap_or(default_limit).min(max_limit),
+        }
+    }
+
+    /// Offset of the previous page, clamped to the first item, if any
+    pub(crate) fn prev(&self) -> Option<usize> {
+        if self.offset > 0 {
+            Some(self.offset.saturating_sub(self.limit))
+        } else {
+            None
+        }
+    }
+
+    /// Offset of the next page, only if items are matched beyond this page,
+    /// i.e. `offset + limit < number_matched`
+    pub(crate) fn next(&self, number_matched: Option<u64>) -> Option<usize> {
+        let next = self.offset.checked_add(self.limit)?;
+        match number_matched {
+            Some(number_matched) if (next as u64) < number_matched => Some(next),
+            _ => None,
+        }
+    }
+
+    /// Links to the previous and next page of the listing at `url`
+    pub(crate) fn links(
+        &self,
+        style: PaginationStyle,
+        url: &Url,
+        number_matched: Option<u64>,
+        media_type: &str,
+    ) -> Vec<Link> {
+        let mut links = Vec::new();
+
+        if let Some(offset) = self.prev() {
+            let href = page(style, url, offset, self.limit);
+            links.push(Link::new(href, PREV).mediatype(media_type));
+        }
+
+        if let Some(offset) = self.next(number_matched) {
+            let href = page(style, url, offset, self.limit);
+            links.push(Link::new(href, NEXT).mediatype(media_type));
+        }
+
+        links
+    }
+}
+
+/// Url of the page at `offset`, keeping all other query parameters
+fn page(style: PaginationStyle, url: &Url, offset: usize, limit: usize) -> Url {
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(k, _)| !matches!(k.as_ref(), "offset" | "limit" | TOKEN))
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+
+    let mut url = url.to_owned();
+    {
+        let mut query = url.query_pairs_mut();
+        query.clear().extend_pairs(pairs);
+        query.append_pair("limit", &limit.to_string());
+        match style {
+            PaginationStyle::Offset => query.append_pair("offset", &offset.to_string()),
+            PaginationStyle::Token => query.append_pair(TOKEN, &encode(offset)),
+        };
+    }
+
+    url
+}
+
+fn encode(offset: usize) -> String {
+    format!("{:016x}", offset)
+}
+
+fn decode(token: &str) -> Result<usize> {
+    usize::from_str_radix(token, 16).map_err(|_| {
+        Error::Exception(
+            StatusCode::BAD_REQUEST,
+            format!("Invalid page token `{}`", token),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_and_clamping() {
+        assert_eq!(
+            Page::new(None, None, 10, 100),
+            Page {
+                offset: 0,
+                limit: 10
+            }
+        );
+        assert_eq!(
+            Page::new(Some(5), Some(1000), 10, 100),
+            Page {
+                offset: 5,
+                limit: 100
+            }
+        );
+    }
+
+    #[test]
+    fn prev() {
+        let page = |offset| Page { offset, limit: 10 };
+
+        assert_eq!(page(0).prev(), None);
+        assert_eq!(page(1).prev(), Some(0));
+        assert_eq!(page(9).prev(), Some(0));
+        assert_eq!(page(10).prev(), Some(0));
+        assert_eq!(page(11).prev(), Some(1));
+        assert_eq!(page(25).prev(), Some(15));
+    }
+
+    #[test]
+    fn next() {
+        let page = Page {
+            offset: 10,
+            limit: 10,
+        };
+
+        assert_eq!(page.next(None), None);
+        assert_eq!(page.next(Some(15)), None);
+        // offset + limit equals the count on the last page
+        assert_eq!(page.next(Some(20)), None);
+        assert_eq!(page.next(Some(21)), Some(20));
+
+        let page = Page {
+            offset: 10,
+            limit: 9,
+        };
+        // one item below the count
+        assert_eq!(page.next(Some(20)), Some(19));
+        assert_eq!(
+            Page {
+                offset: usize::MAX,
+                limit: 1
+            }
+            .next(Some(u64::MAX)),
+            None
+        );
+    }
+
+    #[test]
+    fn links_keep_other_parameters() {
+        let url = Url::parse("http://localhost/items?bbox=0,0,1,1&offset=10&limit=10").unwrap();
+        let page = Page {
+            offset: 10,
+            limit: 10,
+        };
+
+        let links = page.links(PaginationStyle::Offset, &url, Some(30), "application/json");
+
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[0].rel, PREV);
+        assert_eq!(
+            links[0].href,
+            "http://localhost/items?bbox=0%2C0%2C1%2C1&limit=10&offset=0"
+        );
+        assert_eq!(links[1].rel, NEXT);
+        assert!(links[1].href.ends_with("limit=10&offset=20"));
+    }
+
+    #[test]
+    fn token_round_trip() {
+        assert_eq!(decode(&encode(42)).unwrap(), 42);
+        assert!(decode("not a token").is_err());
+    }
+}
@@ -1,12 +1,14 @@
+use std::time::Duration;
+
 use axum::response::{IntoResponse, Response};
 use dyn_clone::DynClone;
 use schemars::{schema_for, JsonSchema};
 use serde::Deserialize;
 use url::Url;
 
-use ogcapi_types::processes::{Execute, Process};
+use ogcapi_types::processes::{Execute, InlineOrRefData, InputValueNoObject, Process, Results};
 
-use crate::{AppState, Result};
+use crate::{AppState, Error, Result};
 
 #[axum::async_trait]
 /// Trait for defining and executing a [Process]
@@ -17,8 +19,28 @@ pub trait Processor: Send + Sync + DynClone {
     /// Returns the Process description
     fn process(&self) -> Process;
 
+    /// Returns the maximum number of simultaneous executions, further ones
+    /// are queued (unlimited by default)
+    fn concurrency(&self) -> Option<usize> {
+        None
+    }
+
+    /// Returns the time after which a running job is cancelled (no limit by
+    /// default)
+    fn timeout(&self) -> Option<Duration> {
+        None
+    }
+
     /// Executes the Process and returns a response
     async fn execute(&self, execute: Execute, state: &AppState, url: &Url) -> Result<Response>;
+
+    /// Runs the Process as asynchronous job and returns its results
+    async fn run(&self, _execute: Execute, _state: &AppState) -> Result<Results> {
+        Err(Error::Exception(
+            axum::http::StatusCode::NOT_IMPLEMENTED,
+            format!("Process `{}` does not support async execution", self.id()),
+        ))
+    }
 }
 
 dyn_clone::clone_trait_object!(Processor);
@@ -41,6 +63,20 @@ struct GreeterInputs {
     name: String,
 }
 
+impl GreeterInputs {
+    /// Reads the inputs of an execution, rejecting invalid ones
+    fn from_execute(execute: Execute) -> Result<Self> {
+        serde_json::to_value(execute.inputs)
+            .and_then(serde_json::from_value)
+            .map_err(|e| {
+                Error::Exception(
+                    axum::http::StatusCode::BAD_REQUEST,
+                    format!("Invalid inputs of process `greet`: {e}"),
+                )
+            })
+    }
+}
+
 /// Outputs for the `greet` process
 #[derive(JsonSchema)]
 struct GreeterOutputs(String);
@@ -60,8 +96,22 @@ impl Processor for Greeter {
     }
 
     async fn execute(&self, execute: Execute, _state: &AppState, _url: &Url) -> Result<Response> {
-        let value = serde_json::to_value(execute.inputs).unwrap();
-        let inputs: GreeterInputs = serde_json::from_value(value).unwrap();
+        let inputs = GreeterInputs::from_execute(execute)?;
         Ok(format!("Hello, {}!\n", inputs.name).into_response())
     }
+
+    async fn run(&self, execute: Execute, _state: &AppState) -> Result<Results> {
+        let inputs = GreeterInputs::from_execute(execute)?;
+
+        let mut results = Results::default();
+        results.results.insert(
+            "greeting".to_string(),
+            InlineOrRefData::InputValueNoObject(InputValueNoObject::String(format!(
+                "Hello, {}!",
+                inputs.name
+            ))),
+        );
+
+        Ok(results)
+    }
 }
@@ -0,0 +1,204 @@
+//! Server side rendering of features into PNG images
+
+use anyhow::Context;
+use geojson::{Geometry, PolygonType, Position, Value};
+use tiny_skia::{FillRule, Paint, Path, PathBuilder, Pixmap, Stroke, Transform};
+
+const STROKE_WIDTH: f32 = 1.5;
+const POINT_RADIUS: f32 = 3.0;
+
+/// Colors features are drawn with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Style {
+    /// Fill of polygons and points
+    pub(crate) fill: [u8; 4],
+    /// Outline of polygons, points and lines
+    pub(crate) stroke: [u8; 4],
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Style {
+            fill: [51, 136, 255, 102],
+            stroke: [51, 136, 255, 255],
+        }
+    }
+}
+
+impl Style {
+    /// Takes the colors of the first `fill` and `line` layers of a Mapbox
+    /// style, hex colors are supported and others fall back to the default
+    pub(crate) fn from_mapbox(style: &serde_json::Value) -> Style {
+        let mut result = Style::default();
+
+        let layers = style["layers"].as_array().cloned().unwrap_or_default();
+        let paint = |kind: &str| {
+            layers
+                .iter()
+                .find(|layer| layer["type"] == kind)
+                .map(|layer| layer["paint"].to_owned())
+        };
+
+        if let Some(paint) = paint("fill") {
+            if let Some([r, g, b, _]) = paint["fill-color"].as_str().and_then(hex) {
+                let opacity = paint["fill-opacity"].as_f64().unwrap_or(1.0);
+                result.fill = [r, g, b, (opacity.clamp(0.0, 1.0) * 255.0) as u8];
+            }
+            if let Some(color) = paint["fill-outline-color"].as_str().and_then(hex) {
+                result.stroke = color;
+            }
+        }
+
+        if let Some(color) =
+            paint("line").and_then(|paint| paint["line-color"].as_str().and_then(hex))
+        {
+            result.stroke = color;
+        }
+
+        result
+    }
+}
+
+/// Parses `#rgb` and `#rrggbb` colors
+fn hex(color: &str) -> Option<[u8; 4]> {
+    let digits = color.strip_prefix('#')?;
+    let len = match digits.len() {
+        3 => 1,
+        6 => 2,
+        _ => return None,
+    };
+    let channel = |i: usize| {
+        let value = u8::from_str_radix(digits.get(i * len..(i + 1) * len)?, 16).ok()?;
+        Some(if len == 1 { value * 17 } else { value })
+    };
+    Some([channel(0)?, channel(1)?, channel(2)?, 255])
+}
+
+/// Maps the coordinates of geometries to pixels of an image
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Viewport {
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    /// Coordinates of the top left corner
+    pub(crate) origin: (f64, f64),
+    /// Pixels per coordinate unit, negative for axes pointing up
+    pub(crate) scale: (f64, f64),
+}
+
+impl Viewport {
+    fn pixel(&self, position: &Position) -> (f32, f32) {
+        (
+            ((position[0] - self.origin.0) * self.scale.0) as f32,
+            ((position[1] - self.origin.1) * self.scale.1) as f32,
+        )
+    }
+}
+
+/// Renders the geometries into a PNG, areas without features are transparent
+pub(crate) fn png(
+    geometries: &[Geometry],
+    viewport: &Viewport,
+    style: &Style,
+) -> anyhow::Result<Vec<u8>> {
+    let mut pixmap =
+        Pixmap::new(viewport.width, viewport.height).context("image size must not be zero")?;
+
+    let mut canvas = Canvas {
+        pixmap: &mut pixmap,
+        viewport,
+        style,
+    };
+    for geometry in geometries {
+        canvas.draw(&geometry.value);
+    }
+
+    Ok(pixmap.encode_png()?)
+}
+
+struct Canvas<'a> {
+    pixmap: &'a mut Pixmap,
+    viewport: &'a Viewport,
+    style: &'a Style,
+}
+
+impl Canvas<'_> {
+    fn draw(&mut self, value: &Value) {
+        match value {
+            Value::Point(position) => self.point(position),
+            Value::MultiPoint(positions) => positions.iter().for_each(|p| self.point(p)),
+            Value::LineString(line) => self.stroke(path(&[line], false, self.viewport)),
+            Value::MultiLineString(lines) => lines
+                .iter()
+                .for_each(|line| self.stroke(path(&[line], false, self.viewport))),
+            Value::Polygon(polygon) => self.polygon(polygon),
+            Value::MultiPolygon(polygons) => polygons.iter().for_each(|p| self.polygon(p)),
+            Value::GeometryCollection(geometries) => {
+                geometries.iter().for_each(|g| self.draw(&g.value))
+            }
+        }
+    }
+
+    fn point(&mut self, position: &Position) {
+        let (x, y) = self.viewport.pixel(position);
+        if let Some(circle) = PathBuilder::from_circle(x, y, POINT_RADIUS) {
+            self.fill(&circle, FillRule::Winding);
+            self.stroke(Some(circle));
+        }
+    }
+
+    fn polygon(&mut self, polygon: &PolygonType) {
+        let rings: Vec<&Vec<Position>> = polygon.iter().collect();
+        if let Some(path) = path(&rings, true, self.viewport) {
+            self.fill(&path, FillRule::EvenOdd);
+            self.stroke(Some(path));
+        }
+    }
+
+    fn fill(&mut self, path: &Path, rule: FillRule) {
+        self.pixmap.fill_path(
+            path,
+            &paint(self.style.fill),
+            rule,
+            Transform::identity(),
+            None,
+        );
+    }
+
+    fn stroke(&mut self, path: Option<Path>) {
+        if let Some(path) = path {
+            let stroke = Stroke {
+                width: STROKE_WIDTH,
+                ..Default::default()
+            };
+            self.pixmap.stroke_path(
+                &path,
+                &paint(self.style.stroke),
+                &stroke,
+                Transform::identity(),
+                None,
+            );
+        }
+    }
+}
+
+fn path(rings: &[&Vec<Position>], close: bool, viewport: &Viewport) -> Option<Path> {
+    let mut builder = PathBuilder::new();
+    for ring in rings {
+        let mut pixels = ring.iter().map(|position| viewport.pixel(position));
+        if let Some((x, y)) = pixels.next() {
+            builder.move_to(x, y);
+            pixels.for_each(|(x, y)| builder.line_to(x, y));
+            if close {
+                builder.close();
+            }
+        }
+    }
+    builder.finish()
+}
+
+fn paint(rgba: [u8; 4]) -> Paint<'static> {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(rgba[0], rgba[1], rgba[2], rgba[3]);
+    paint.anti_alias = true;
+    paint
+}
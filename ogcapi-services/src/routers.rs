@@ -0,0 +1,56 @@
+//! Routers of the individual APIs
+//!
+//! Unlike the full [`app`](crate::app), these come without the common
+//! middleware stack, but still authenticate requests. This allows to compose
+//! services of some APIs only, or to test routes with mocked drivers.
+
+use axum::Router;
+
+use crate::{routes, service::authenticate, AppState};
+
+/// Router of the collections metadata
+pub fn collections(state: &AppState) -> Router<AppState> {
+    authenticate(routes::collections::router(state), state)
+}
+
+/// Router of the features API
+#[cfg(feature = "features")]
+pub fn features(state: &AppState) -> Router<AppState> {
+    authenticate(routes::features::router(state), state)
+}
+
+/// Router of the records API
+#[cfg(feature = "records")]
+pub fn records(state: &AppState) -> Router<AppState> {
+    authenticate(routes::records::router(state), state)
+}
+
+/// Router of the environmental data retrieval API
+#[cfg(feature = "edr")]
+pub fn edr(state: &AppState) -> Router<AppState> {
+    authenticate(routes::edr::router(state), state)
+}
+
+/// Router of the styles API
+#[cfg(feature = "styles")]
+pub fn styles(state: &AppState) -> Router<AppState> {
+    authenticate(routes::styles::router(state), state)
+}
+
+/// Router of the tiles API
+#[cfg(feature = "tiles")]
+pub fn tiles(state: &AppState) -> Router<AppState> {
+    authenticate(routes::tiles::router(state), state)
+}
+
+/// Router of the maps API
+#[cfg(feature = "maps")]
+pub fn maps(state: &AppState) -> Router<AppState> {
+    authenticate(routes::maps::router(state), state)
+}
+
+/// Router of the processes API
+#[cfg(feature = "processes")]
+pub fn processes(state: &AppState) -> Router<AppState> {
+    authenticate(routes::processes::router(state), state)
+}
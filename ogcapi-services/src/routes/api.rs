@@ -1,19 +1,120 @@
+use anyhow::Context;
 use axum::{extract::State, headers::HeaderMap, http::header::CONTENT_TYPE, response::Html, Json};
-use openapiv3::OpenAPI;
+use serde_json::Value;
 
-use ogcapi_types::common::media_type::OPEN_API_JSON;
+use ogcapi_types::common::media_type::{OPEN_API_JSON, OPEN_API_YAML};
 
-use crate::{AppState, Result};
+use crate::{AppState, Result, Settings};
 
-pub(crate) async fn api(State(state): State<AppState>) -> (HeaderMap, Json<OpenAPI>) {
+pub(crate) async fn api(State(state): State<AppState>) -> (HeaderMap, Json<Value>) {
     let mut headers = HeaderMap::new();
     headers.insert(CONTENT_TYPE, OPEN_API_JSON.parse().unwrap());
 
-    (headers, Json(state.openapi.0))
+    (headers, Json(openapi(&state)))
 }
 
-pub(crate) async fn redoc() -> Result<Html<String>> {
-    Ok(Html(
+/// The same document as YAML, for tooling that prefers it
+pub(crate) async fn api_yaml(State(state): State<AppState>) -> Result<(HeaderMap, String)> {
+    let mut headers = HeaderMap::new();
+    headers.insert(CONTENT_TYPE, OPEN_API_YAML.parse().unwrap());
+
+    let yaml = serde_yaml::to_string(&openapi(&state))
+        .context("Unable to serialize the OpenAPI definition")?;
+
+    Ok((headers, yaml))
+}
+
+/// OpenAPI definition of the service adapted to its settings
+fn openapi(state: &AppState) -> Value {
+    let mut openapi = serde_json::to_value(&state.openapi.0).unwrap();
+    advertise_limits(&mut openapi, &state.settings);
+    openapi
+}
+
+/// Sets the default and maximum of the `limit` parameter to the configured
+/// page sizes
+fn advertise_limits(openapi: &mut Value, settings: &Settings) {
+    if let Some(limit) = openapi.pointer_mut("/components/parameters/limit") {
+        limit["schema"]["default"] = settings.default_limit.into();
+        limit["schema"]["maximum"] = settings.max_limit.into();
+
+        if let Some(description) = limit["description"].as_str() {
+            let description = format!(
+                "{}Minimum = 1. Maximum = {}. Default = {}.",
+                description.split("Minimum = ").next().unwrap_or_default(),
+                settings.max_limit,
+                settings.default_limit
+            );
+            limit["description"] = description.into();
+        }
+    }
+}
+
+/// Path the files of the API documentation are served at if self-hosted
+pub(crate) const ASSETS: &str = "/assets/docs";
+
+/// Files of the `swagger-ui-dist` and `redoc` bundles embedded in the binary,
+/// fetched into `assets/docs` with `assets/docs/fetch.sh` before building
+#[cfg(feature = "docs-assets")]
+const EMBEDDED_ASSETS: [(&str, &str, &[u8]); 3] = [
+    (
+        "redoc.standalone.js",
+        "application/javascript",
+        include_bytes!("../../assets/docs/redoc.standalone.js"),
+    ),
+    (
+        "swagger-ui-bundle.js",
+        "application/javascript",
+        include_bytes!("../../assets/docs/swagger-ui-bundle.js"),
+    ),
+    (
+        "swagger-ui.css",
+        "text/css",
+        include_bytes!("../../assets/docs/swagger-ui.css"),
+    ),
+];
+
+/// Whether the files of the API documentation are served by the service
+/// itself, from the configured directory or embedded in the binary
+fn self_hosted(settings: &Settings) -> bool {
+    settings.api_docs_assets.is_some() || cfg!(feature = "docs-assets")
+}
+
+/// Base URL of the files of a bundle, relative to the documentation page
+fn assets(settings: &Settings, cdn: &str) -> String {
+    if self_hosted(settings) {
+        ASSETS.trim_start_matches('/').to_string()
+    } else {
+        cdn.to_string()
+    }
+}
+
+/// Embedded file of the API documentation
+#[cfg(feature = "docs-assets")]
+pub(crate) async fn asset(
+    axum::extract::Path(file): axum::extract::Path<String>,
+) -> Result<impl axum::response::IntoResponse> {
+    let (_, content_type, bytes) = EMBEDDED_ASSETS
+        .iter()
+        .find(|(name, ..)| *name == file)
+        .ok_or(crate::Error::NotFound)?;
+
+    Ok(([(CONTENT_TYPE, *content_type)], *bytes))
+}
+
+pub(crate) async fn redoc(State(state): State<AppState>) -> Result<Html<String>> {
+    let assets = assets(
+        &state.settings,
+        "https://cdn.jsdelivr.net/npm/redoc@next/bundles",
+    );
+    // fall back to the system fonts without internet access
+    let fonts = if self_hosted(&state.settings) {
+        ""
+    } else {
+        r#"<link href="https://fonts.googleapis.com/css?family=Montserrat:300,400,700|Roboto:300,400,700" rel="stylesheet">"#
+    };
+
+    Ok(Html(format!(
         r#"
         <!DOCTYPE html>
         <html>
@@ -22,25 +123,27 @@ pub(crate) async fn redoc() -> Result<Html<String>> {
             <!-- needed for adaptive design -->
             <meta charset="utf-8"/>
             <meta name="viewport" content="width=device-width, initial-scale=1">
-            <link href="https://fonts.googleapis.com/css?family=Montserrat:300,400,700|Roboto:300,400,700" rel="stylesheet">
+            {fonts}
             <style>
-                body {
+                body {{
                     margin: 0;
                     padding: 0;
-                }
+                }}
             </style>
         </head>
         <body>
             <redoc spec-url="api"></redoc>
-            <script src="https://cdn.jsdelivr.net/npm/redoc@next/bundles/redoc.standalone.js"></script>
+            <script src="{assets}/redoc.standalone.js"></script>
         </body>
         </html>
-        "#.to_string(),
-    ))
+        "#
+    )))
 }
 
-pub(crate) async fn swagger() -> Result<Html<String>> {
-    Ok(Html(
+pub(crate) async fn swagger(State(state): State<AppState>) -> Result<Html<String>> {
+    let assets = assets(&state.settings, "https://unpkg.com/swagger-ui-dist@4.11.1");
+
+    Ok(Html(format!(
         r#"
         <!DOCTYPE html>
         <html lang="en">
@@ -49,21 +152,21 @@ pub(crate) async fn swagger() -> Result<Html<String>> {
             <meta name="viewport" content="width=device-width, initial-scale=1" />
             <meta name="description" content="SwaggerIU" />
             <title>SwaggerUI</title>
-            <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@4.11.1/swagger-ui.css" />
+            <link rel="stylesheet" href="{assets}/swagger-ui.css" />
         </head>
         <body>
             <div id="swagger-ui"></div>
-            <script src="https://unpkg.com/swagger-ui-dist@4.11.1/swagger-ui-bundle.js" crossorigin></script>
+            <script src="{assets}/swagger-ui-bundle.js" crossorigin></script>
             <script>
-            window.onload = () => {
-                window.ui = SwaggerUIBundle({
+            window.onload = () => {{
+                window.ui = SwaggerUIBundle({{
                 url: 'api',
                 dom_id: '#swagger-ui',
-                });
-            };
+                }});
+            }};
             </script>
         </body>
         </html>
-        "#.to_string(),
-    ))
+        "#
+    )))
 }
@@ -0,0 +1,70 @@
+//! Non-standard introspection of the capabilities of the server
+//!
+//! Reports which parts of the API are compiled in and what the drivers can do,
+//! so operators and clients can detect them without probing the endpoints.
+
+use axum::{http::StatusCode, Extension, Json};
+use serde::Serialize;
+
+use crate::{crs, middleware::Principal, Error, Permission, Result};
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Capabilities {
+    /// Enabled APIs besides the common core
+    apis: Vec<&'static str>,
+    /// Whether collections and features can be created, updated and deleted
+    transactions: bool,
+    /// Whether tiles are served
+    tiles: bool,
+    /// Spatial query parameters evaluated by the driver
+    spatial_queries: Vec<&'static str>,
+    /// Coordinate reference systems the driver can transform to
+    crs: Vec<String>,
+}
+
+pub(crate) async fn capabilities(
+    Extension(principal): Extension<Principal>,
+) -> Result<Json<Capabilities>> {
+    if principal.0 != Some(Permission::Write) {
+        return Err(Error::Exception(
+            StatusCode::FORBIDDEN,
+            "Capabilities require `write` permission".to_string(),
+        ));
+    }
+
+    let apis = [
+        ("features", cfg!(feature = "features")),
+        ("records", cfg!(feature = "records")),
+        ("edr", cfg!(feature = "edr")),
+        ("processes", cfg!(feature = "processes")),
+        ("styles", cfg!(feature = "styles")),
+        ("tiles", cfg!(feature = "tiles")),
+        ("maps", cfg!(feature = "maps")),
+        ("stac", cfg!(feature = "stac")),
+    ];
+
+    let spatial_queries = [
+        ("bbox", true),
+        ("center,radius", cfg!(feature = "features")),
+        ("filter:s_intersects", cfg!(feature = "features")),
+        ("intersects", cfg!(feature = "stac")),
+        ("coords", cfg!(feature = "edr")),
+    ];
+
+    Ok(Json(Capabilities {
+        apis: enabled(&apis),
+        transactions: cfg!(feature = "features"),
+        tiles: cfg!(feature = "tiles"),
+        spatial_queries: enabled(&spatial_queries),
+        crs: crs::known().map(|crs| crs.to_string()).collect(),
+    }))
+}
+
+fn enabled(flags: &[(&'static str, bool)]) -> Vec<&'static str> {
+    flags
+        .iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(name, _)| *name)
+        .collect()
+}
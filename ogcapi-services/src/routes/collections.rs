@@ -1,8 +1,12 @@
 use axum::{
     extract::{Path, State},
     headers::HeaderMap,
-    http::{header::LOCATION, StatusCode},
-    Json,
+    http::{
+        header::{CONTENT_LANGUAGE, LOCATION},
+        StatusCode,
+    },
+    middleware::{from_fn, from_fn_with_state},
+    Extension, Json,
     {routing::get, Router},
 };
 
@@ -11,10 +15,15 @@ use ogcapi_types::common::{
     media_type::{GEO_JSON, JSON},
     Collection, Collections, Crs, Link, Linked, Query,
 };
+use ogcapi_types::{features::FEATURE, records::RECORD};
 
 use crate::{
-    extractors::{Qs, RemoteUrl},
-    AppState, Error, Result,
+    crs,
+    extractors::{AcceptLanguage, JsonBody, Qs, RemoteUrl},
+    i18n::{self, Translate},
+    middleware::{self, Principal},
+    routes::{method_not_allowed, options},
+    AppState, Error, Result, Settings,
 };
 
 const CONFORMANCE: [&str; 3] = [
@@ -27,8 +36,14 @@ const CONFORMANCE: [&str; 3] = [
 async fn create(
     State(state): State<AppState>,
     RemoteUrl(url): RemoteUrl,
-    Json(collection): Json<Collection>,
+    JsonBody(mut collection): JsonBody<Collection>,
 ) -> Result<(StatusCode, HeaderMap)> {
+    validate_crs(&collection)?;
+    validate_item_type(&mut collection)?;
+    validate_table(&collection, &state.settings)?;
+    // counted on read
+    collection.item_count = None;
+
     if state
         .drivers
         .collections
@@ -56,21 +71,124 @@ async fn create(
     Ok((StatusCode::CREATED, headers))
 }
 
+/// Rejects collections advertising a CRS which is not in the registry
+fn validate_crs(collection: &Collection) -> Result<()> {
+    if let Some(unknown) = collection
+        .crs
+        .iter()
+        .chain(collection.storage_crs.as_ref())
+        .find(|c| !crs::is_known(c))
+    {
+        return Err(Error::Exception(
+            StatusCode::BAD_REQUEST,
+            format!("Unknown CRS `{unknown}`"),
+        ));
+    }
+    Ok(())
+}
+
+/// Rejects a CRS which is not supported by a collection, collections without
+/// a list of CRS support the ones of the service
+pub(crate) fn is_supported_crs(
+    collection: &Collection,
+    crs: &Crs,
+    settings: &Settings,
+) -> Result<()> {
+    if collection.crs.contains(crs) || (collection.crs.is_empty() && settings.crs.contains(crs)) {
+        Ok(())
+    } else {
+        Err(Error::Exception(
+            StatusCode::BAD_REQUEST,
+            format!("Unsupported CRS `{}`", crs),
+        ))
+    }
+}
+
+/// Rejects backing tables which are not configured by the operator
+fn validate_table(collection: &Collection, settings: &Settings) -> Result<()> {
+    let normalize = |table: &str| {
+        table
+            .split('.')
+            .map(|part| part.trim_matches('"'))
+            .collect::<Vec<&str>>()
+            .join(".")
+    };
+    if let Some(table) = collection.table.as_deref() {
+        let table = normalize(table);
+        if !settings.tables.iter().any(|t| normalize(t) == table) {
+            return Err(Error::Exception(
+                StatusCode::FORBIDDEN,
+                format!("Table `{table}` is not configured to back collections"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Removes the storage details of a collection from responses
+fn hide_storage(collection: &mut Collection) {
+    collection.table = None;
+    collection.id_column = None;
+    collection.property_names = None;
+}
+
+/// Rejects item types other than `feature` and `record`, which are
+/// normalized to lowercase
+fn validate_item_type(collection: &mut Collection) -> Result<()> {
+    if let Some(item_type) = collection.item_type.as_mut() {
+        item_type.make_ascii_lowercase();
+        if item_type != FEATURE && item_type != RECORD {
+            return Err(Error::Exception(
+                StatusCode::BAD_REQUEST,
+                format!("Unknown item type `{item_type}`, expected `{FEATURE}` or `{RECORD}`"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Inlines the CRS offered by the server for collections without a list of
+/// their own, the default storage CRS and the default item type
+fn advertise_defaults(collection: &mut Collection, settings: &Settings) {
+    if collection.crs.is_empty() {
+        collection.crs = settings.crs.clone();
+    }
+    collection.storage_crs.get_or_insert_with(Crs::default);
+    collection
+        .item_type
+        .get_or_insert_with(|| FEATURE.to_string());
+}
+
 /// Get collection metadata
 async fn read(
     State(state): State<AppState>,
     Path(collection_id): Path<String>,
     RemoteUrl(url): RemoteUrl,
-) -> Result<Json<Collection>> {
+    Extension(principal): Extension<Principal>,
+    accept_language: AcceptLanguage,
+) -> Result<(HeaderMap, Json<Collection>)> {
     let mut collection = state
         .drivers
         .collections
         .read_collection(&collection_id)
         .await?
         .ok_or(Error::NotFound)?;
+    principal.authorize_read(&collection)?;
+    advertise_defaults(&mut collection, &state.settings);
+
+    #[cfg(feature = "features")]
+    if let Some(item_count) = state.settings.item_count {
+        collection.item_count = state
+            .drivers
+            .features
+            .count_items(&collection, item_count == crate::ItemCount::Exact)
+            .await?;
+    }
+
+    hide_storage(&mut collection);
 
     collection.links.insert_or_update(&[
-        Link::new(&url, SELF),
+        Link::new(&url, SELF).mediatype(JSON),
         Link::new(url.join("..")?, ROOT).mediatype(JSON),
     ]);
 
@@ -92,16 +210,36 @@ async fn read(
 
     collection.links.resolve_relative_links();
 
-    Ok(Json(collection))
+    let languages = collection.languages();
+    let language = collection.translate(&accept_language, &state.settings.default_language);
+    i18n::link_languages(
+        &mut collection.links,
+        &url,
+        &language,
+        &state.settings.default_language,
+        languages,
+    );
+
+    let mut headers = HeaderMap::new();
+    if let Ok(language) = language.parse() {
+        headers.insert(CONTENT_LANGUAGE, language);
+    }
+
+    Ok((headers, Json(collection)))
 }
 
 /// Update collection metadata
 async fn update(
     State(state): State<AppState>,
     Path(collection_id): Path<String>,
-    Json(mut collection): Json<Collection>,
+    JsonBody(mut collection): JsonBody<Collection>,
 ) -> Result<StatusCode> {
     collection.id = collection_id;
+    validate_crs(&collection)?;
+    validate_item_type(&mut collection)?;
+    validate_table(&collection, &state.settings)?;
+    // counted on read
+    collection.item_count = None;
 
     state
         .drivers
@@ -130,10 +268,25 @@ async fn collections(
     Qs(query): Qs<Query>,
     RemoteUrl(url): RemoteUrl,
     State(state): State<AppState>,
+    accept_language: AcceptLanguage,
 ) -> Result<Json<Collections>> {
     let mut collections = state.drivers.collections.list_collections(&query).await?;
 
     for collection in collections.collections.iter_mut() {
+        collection.translate(&accept_language, &state.settings.default_language);
+        advertise_defaults(collection, &state.settings);
+
+        #[cfg(feature = "features")]
+        if let Some(item_count) = state.settings.item_count {
+            collection.item_count = state
+                .drivers
+                .features
+                .count_items(collection, item_count == crate::ItemCount::Exact)
+                .await?;
+        }
+
+        hide_storage(collection);
+
         collection.links.insert_or_update(&[
             Link::new(url.join(&format!("collections/{}", collection.id))?, SELF).mediatype(JSON),
             Link::new(url.join(".")?, ROOT).mediatype(JSON),
@@ -152,25 +305,38 @@ async fn collections(
         Link::new(url.join(".")?, ROOT).mediatype(JSON),
     ];
 
-    collections.crs = vec![Crs::default(), Crs::from_epsg(3857)];
+    collections.crs = state.settings.crs.clone();
 
     Ok(Json(collections))
 }
 
 pub(crate) fn router(state: &AppState) -> Router<AppState> {
     let mut root = state.root.write().unwrap();
-    root.links.push(
-        Link::new("collections", DATA)
-            .title("Metadata about the resource collections")
-            .mediatype(JSON),
-    );
+    root.links.insert_or_update(&[Link::new("collections", DATA)
+        .title("Metadata about the resource collections")
+        .mediatype(JSON)]);
 
     state.conformance.write().unwrap().extend(&CONFORMANCE);
 
     Router::new()
-        .route("/collections", get(collections).post(create))
+        .route(
+            "/collections",
+            get(collections)
+                .post(create)
+                .options(|| options("GET, HEAD, POST, OPTIONS"))
+                .fallback(|| method_not_allowed("GET, HEAD, POST, OPTIONS")),
+        )
         .route(
             "/collections/:collection_id",
-            get(read).put(update).delete(remove),
+            get(read)
+                .put(update)
+                .delete(remove)
+                .options(|| options("GET, HEAD, PUT, DELETE, OPTIONS"))
+                .fallback(|| method_not_allowed("GET, HEAD, PUT, DELETE, OPTIONS")),
         )
+        .route_layer(from_fn(middleware::json_content_type))
+        .route_layer(from_fn_with_state(
+            state.settings.max_body_size,
+            middleware::decompress,
+        ))
 }
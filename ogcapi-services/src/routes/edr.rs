@@ -1,20 +1,28 @@
 use axum::{
     extract::{Path, State},
     headers::HeaderMap,
-    http::header::CONTENT_TYPE,
+    http::{
+        header::{CONTENT_TYPE, LOCATION},
+        StatusCode,
+    },
+    middleware::{from_fn, from_fn_with_state},
     routing::get,
     Json, Router,
 };
 
 use ogcapi_types::{
-    common::{link_rel::SELF, media_type::GEO_JSON, Link},
-    edr::{Query, QueryType},
+    common::{
+        link_rel::{COLLECTION, SELF},
+        media_type::{GEO_JSON, JSON},
+        Link, Linked,
+    },
+    edr::{Instance, Instances, Query, QueryType},
     features::FeatureCollection,
 };
 
 use crate::{
-    extractors::{Qs, RemoteUrl},
-    AppState, Result,
+    extractors::{JsonBody, Qs, RemoteUrl},
+    middleware, vertices, AppState, Error, Result,
 };
 
 const CONFORMANCE: [&str; 8] = [
@@ -34,18 +42,66 @@ async fn query(
     Qs(query): Qs<Query>,
     RemoteUrl(url): RemoteUrl,
     State(state): State<AppState>,
+) -> Result<(HeaderMap, Json<FeatureCollection>)> {
+    run_query(&state, &collection_id, None, &query_type, &query, &url).await
+}
+
+/// Query scoped to an instance of the collection
+async fn instance_query(
+    Path((collection_id, instance_id, query_type)): Path<(String, String, QueryType)>,
+    Qs(query): Qs<Query>,
+    RemoteUrl(url): RemoteUrl,
+    State(state): State<AppState>,
+) -> Result<(HeaderMap, Json<FeatureCollection>)> {
+    state
+        .drivers
+        .edr
+        .read_instance(&collection_id, &instance_id)
+        .await?
+        .ok_or(Error::NotFound)?;
+
+    run_query(
+        &state,
+        &collection_id,
+        Some(&instance_id),
+        &query_type,
+        &query,
+        &url,
+    )
+    .await
+}
+
+async fn run_query(
+    state: &AppState,
+    collection_id: &str,
+    instance_id: Option<&str>,
+    query_type: &QueryType,
+    query: &Query,
+    url: &url::Url,
 ) -> Result<(HeaderMap, Json<FeatureCollection>)> {
     tracing::debug!("{:#?}", query);
 
+    vertices::check(
+        "coords",
+        vertices::count_wkt(&query.coords),
+        state.settings.max_filter_vertices,
+    )?;
+
     let mut fc = state
         .drivers
         .edr
-        .query(&collection_id, &query_type, &query)
+        .query(collection_id, instance_id, query_type, query)
         .await?;
 
+    // items are addressed through the collection
+    let collection_url = match instance_id {
+        Some(_) => url.join("../../")?,
+        None => url.clone(),
+    };
+
     for feature in fc.features.iter_mut() {
         feature.links = vec![Link::new(
-            url.join(&format!(
+            collection_url.join(&format!(
                 "items/{}",
                 feature.id.as_ref().expect("Feature should have id")
             ))?,
@@ -61,15 +117,118 @@ async fn query(
     Ok((headers, Json(fc)))
 }
 
-// async fn instances() {}
+async fn instances(
+    Path(collection_id): Path<String>,
+    RemoteUrl(url): RemoteUrl,
+    State(state): State<AppState>,
+) -> Result<Json<Instances>> {
+    state
+        .drivers
+        .collections
+        .read_collection(&collection_id)
+        .await?
+        .ok_or(Error::NotFound)?;
 
-// async fn instance() {}
+    let mut instances = state.drivers.edr.list_instances(&collection_id).await?;
+
+    for instance in instances.iter_mut() {
+        instance.links.insert_or_update(&[Link::new(
+            url.join(&format!("instances/{}", instance.id))?,
+            SELF,
+        )
+        .mediatype(JSON)]);
+    }
+
+    Ok(Json(Instances {
+        links: vec![
+            Link::new(&url, SELF).mediatype(JSON),
+            Link::new(url.join(".")?, COLLECTION).mediatype(JSON),
+        ],
+        instances,
+    }))
+}
+
+async fn create_instance(
+    Path(collection_id): Path<String>,
+    RemoteUrl(url): RemoteUrl,
+    State(state): State<AppState>,
+    JsonBody(instance): JsonBody<Instance>,
+) -> Result<(StatusCode, HeaderMap)> {
+    state
+        .drivers
+        .collections
+        .read_collection(&collection_id)
+        .await?
+        .ok_or(Error::NotFound)?;
+
+    if state
+        .drivers
+        .edr
+        .read_instance(&collection_id, &instance.id)
+        .await?
+        .is_some()
+    {
+        return Err(Error::Exception(
+            StatusCode::CONFLICT,
+            format!("Instance with id `{}` already exists.", instance.id),
+        ));
+    }
+
+    let id = state
+        .drivers
+        .edr
+        .create_instance(&collection_id, &instance)
+        .await?;
+
+    let location = url.join(&format!("instances/{id}"))?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(LOCATION, location.as_str().parse().unwrap());
+
+    Ok((StatusCode::CREATED, headers))
+}
+
+async fn instance(
+    Path((collection_id, instance_id)): Path<(String, String)>,
+    RemoteUrl(url): RemoteUrl,
+    State(state): State<AppState>,
+) -> Result<Json<Instance>> {
+    let mut instance = state
+        .drivers
+        .edr
+        .read_instance(&collection_id, &instance_id)
+        .await?
+        .ok_or(Error::NotFound)?;
+
+    instance.links.insert_or_update(&[
+        Link::new(&url, SELF).mediatype(JSON),
+        Link::new(url.join("..")?, COLLECTION).mediatype(JSON),
+    ]);
+
+    Ok(Json(instance))
+}
 
 pub(crate) fn router(state: &AppState) -> Router<AppState> {
     state.conformance.write().unwrap().extend(&CONFORMANCE);
 
-    Router::new().route("/collections/:collection_id/:query_type", get(query))
-    // .route("/collections/:collection_id/instances", get(instances))
-    // .route("/collections/:collection_id/instances/:instance_id", get(instance))
-    // .route("/collections/:collection_id/instances/:instance_id/:query_type", get(instance))
+    Router::new()
+        .route("/collections/:collection_id/:query_type", get(query))
+        .route(
+            "/collections/:collection_id/instances",
+            get(instances)
+                .post(create_instance)
+                .route_layer(from_fn(middleware::json_content_type))
+                .route_layer(from_fn_with_state(
+                    state.settings.max_body_size,
+                    middleware::decompress,
+                )),
+        )
+        .route(
+            "/collections/:collection_id/instances/:instance_id",
+            get(instance),
+        )
+        .route(
+            "/collections/:collection_id/instances/:instance_id/:query_type",
+            get(instance_query),
+        )
 }
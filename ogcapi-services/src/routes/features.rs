@@ -1,66 +1,169 @@
+use std::time::SystemTime;
+
 use anyhow::Context;
 use axum::{
     extract::{Path, State},
+    headers::{HeaderMapExt, IfModifiedSince, LastModified},
     http::{
-        header::{CONTENT_TYPE, LOCATION},
+        header::{ACCEPT, CONTENT_TYPE, LOCATION},
         HeaderMap, StatusCode,
     },
+    middleware::{from_fn, from_fn_with_state},
+    response::{IntoResponse, Response},
     routing::get,
-    Json, Router,
+    Extension, Json, Router,
 };
+use serde::Deserialize;
+use serde_json::Value;
 
 use ogcapi_types::{
     common::{
-        link_rel::{COLLECTION, NEXT, PREV, ROOT, SELF},
-        media_type::{GEO_JSON, JSON},
-        Collection, Crs, Link, Linked,
+        link_rel::{ATERNATE, COLLECTION, ITEM, ROOT, SELF},
+        media_type::{GEO_JSON, JSON, JSON_FG},
+        Collection, Crs, IdStrategy, Link, Linked,
     },
-    features::{Feature, FeatureCollection, Query},
+    features::{Aggregation, Feature, FeatureCollection, GeometryFormat, PropertyValues, Query},
 };
 
 use crate::{
-    extractors::{Qs, RemoteUrl},
-    AppState, Error, Result,
+    crs,
+    extractors::{from_json, JsonBody, Qs, RemoteUrl},
+    jsonfg,
+    middleware::{self, Principal},
+    pagination::{self, Page},
+    routes::{collections::is_supported_crs, method_not_allowed, options},
+    topojson, vertices, well_known, AppState, Error, Result, Settings,
 };
 
-const CONFORMANCE: [&str; 4] = [
+/// Maximum number of distinct values returned for a property
+const MAX_VALUES: usize = 1000;
+
+const CONFORMANCE: [&str; 5] = [
     "http://www.opengis.net/spec/ogcapi-features-1/1.0/conf/core",
     "http://www.opengis.net/spec/ogcapi-features-1/1.0/conf/oas30",
     "http://www.opengis.net/spec/ogcapi-features-1/1.0/conf/geojson",
-    "http://www.opengis.net/spec/ogcapi-features-2/1.0/conf/crs",
+    crs::CONFORMANCE,
+    jsonfg::CORE,
 ];
 
 async fn create(
     State(state): State<AppState>,
     RemoteUrl(url): RemoteUrl,
     Path(collection_id): Path<String>,
-    Json(mut feature): Json<Feature>,
-) -> Result<(StatusCode, HeaderMap)> {
+    request_headers: HeaderMap,
+    JsonBody(body): JsonBody<Value>,
+) -> Result<Response> {
+    let collection = state
+        .drivers
+        .collections
+        .read_collection(&collection_id)
+        .await?
+        .ok_or(Error::NotFound)?;
+    is_writable(&collection)?;
+
+    if body["type"] == "FeatureCollection" {
+        let fc: FeatureCollection = from_json(body)?;
+        return create_many(&state, &url, &collection, fc.features).await;
+    }
+
+    let mut feature: Feature = from_json(body)?;
+    is_valid_id(&collection, &feature)?;
+
     feature.collection = Some(collection_id);
 
     let id = state.drivers.features.create_feature(&feature).await?;
 
-    let location = url.join(&format!("items/{}", id))?;
+    let location = item_url(&url, &id)?;
 
     let mut headers = HeaderMap::new();
     headers.insert(LOCATION, location.as_str().parse().unwrap());
 
-    Ok((StatusCode::CREATED, headers))
+    let representation = request_headers
+        .get_all("Prefer")
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .any(|v| v.split(',').any(|p| p.trim() == "return=representation"));
+
+    if !representation {
+        return Ok((StatusCode::CREATED, headers).into_response());
+    }
+
+    let mut feature = state
+        .drivers
+        .features
+        .read_feature(&collection.id, &id, &Crs::default())
+        .await?
+        .ok_or(Error::NotFound)?;
+
+    feature.links.insert_or_update(&[
+        Link::new(&location, SELF).mediatype(GEO_JSON),
+        Link::new(location.join("../../..")?, ROOT).mediatype(JSON),
+        Link::new(
+            location.join(&format!("../../{}", collection.id))?,
+            COLLECTION,
+        )
+        .mediatype(JSON),
+    ]);
+    feature.links.resolve_relative_links();
+
+    headers.insert(
+        "Preference-Applied",
+        "return=representation".parse().unwrap(),
+    );
+    headers.insert(CONTENT_TYPE, GEO_JSON.parse().unwrap());
+
+    Ok((StatusCode::CREATED, headers, Json(feature)).into_response())
+}
+
+/// Creates all features of a `FeatureCollection` and responds with links to
+/// the created items
+async fn create_many(
+    state: &AppState,
+    url: &url::Url,
+    collection: &Collection,
+    mut features: Vec<Feature>,
+) -> Result<Response> {
+    if features.len() > state.settings.max_batch_features {
+        return Err(Error::Exception(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "feature collection must not contain more than {} features, split it into several requests",
+                state.settings.max_batch_features
+            ),
+        ));
+    }
+
+    for feature in features.iter_mut() {
+        is_valid_id(collection, feature)?;
+        feature.collection = Some(collection.id.to_owned());
+    }
+
+    let ids = state.drivers.features.create_features(&features).await?;
+
+    let links = ids
+        .iter()
+        .map(|id| Ok(Link::new(item_url(url, id)?, ITEM).mediatype(GEO_JSON)))
+        .collect::<Result<Vec<Link>>>()?;
+
+    Ok((StatusCode::CREATED, Json(links)).into_response())
 }
 
 async fn read(
     State(state): State<AppState>,
     RemoteUrl(url): RemoteUrl,
     Path((collection_id, id)): Path<(String, String)>,
+    Extension(principal): Extension<Principal>,
     Qs(query): Qs<Query>,
-) -> Result<(HeaderMap, Json<Feature>)> {
+    request_headers: HeaderMap,
+) -> Result<Response> {
     let collection = state
         .drivers
         .collections
         .read_collection(&collection_id)
         .await?
         .ok_or(Error::NotFound)?;
-    is_supported_crs(&collection, &query.crs).await?;
+    principal.authorize_read(&collection)?;
+    is_supported_crs(&collection, &query.crs, &state.settings)?;
 
     let mut feature = state
         .drivers
@@ -69,6 +172,20 @@ async fn read(
         .await?
         .ok_or(Error::NotFound)?;
 
+    let last_modified = last_modified(&feature);
+    if let (Some(since), Some(modified)) = (
+        request_headers.typed_get::<IfModifiedSince>(),
+        last_modified,
+    ) {
+        if !since.is_modified(modified) {
+            return Ok(StatusCode::NOT_MODIFIED.into_response());
+        }
+    }
+
+    if let Some(format) = query.geometry_format {
+        encode_geometry(&mut feature, format);
+    }
+
     feature.links.insert_or_update(&[
         Link::new(&url, SELF).mediatype(GEO_JSON),
         Link::new(url.join("../../..")?, ROOT).mediatype(JSON),
@@ -86,15 +203,37 @@ async fn read(
             .context("Unable to parse `Content-Crs` header value")?,
     );
     headers.insert(CONTENT_TYPE, GEO_JSON.parse().unwrap());
+    if let Some(modified) = last_modified {
+        headers.typed_insert(LastModified::from(modified));
+    }
+
+    Ok((headers, Json(feature)).into_response())
+}
 
-    Ok((headers, Json(feature)))
+/// Time of the last modification of a feature, from its `updated` or
+/// `lastModified` property
+fn last_modified(feature: &Feature) -> Option<SystemTime> {
+    let properties = feature.properties.as_ref()?;
+    ["updated", "lastModified"]
+        .iter()
+        .filter_map(|key| properties.get(*key)?.as_str())
+        .find_map(|value| chrono::DateTime::parse_from_rfc3339(value).ok())
+        .map(SystemTime::from)
 }
 
 async fn update(
     State(state): State<AppState>,
     Path((collection_id, id)): Path<(String, String)>,
-    Json(mut feature): Json<Feature>,
+    JsonBody(mut feature): JsonBody<Feature>,
 ) -> Result<StatusCode> {
+    let collection = state
+        .drivers
+        .collections
+        .read_collection(&collection_id)
+        .await?
+        .ok_or(Error::NotFound)?;
+    is_writable(&collection)?;
+
     feature.id = Some(id);
     feature.collection = Some(collection_id);
 
@@ -107,6 +246,14 @@ async fn remove(
     State(state): State<AppState>,
     Path((collection_id, id)): Path<(String, String)>,
 ) -> Result<StatusCode> {
+    let collection = state
+        .drivers
+        .collections
+        .read_collection(&collection_id)
+        .await?
+        .ok_or(Error::NotFound)?;
+    is_writable(&collection)?;
+
     state
         .drivers
         .features
@@ -118,28 +265,121 @@ async fn remove(
 
 async fn items(
     State(state): State<AppState>,
-    RemoteUrl(mut url): RemoteUrl,
+    RemoteUrl(url): RemoteUrl,
     Path(collection_id): Path<String>,
+    Extension(principal): Extension<Principal>,
     Qs(mut query): Qs<Query>,
-) -> Result<(HeaderMap, Json<FeatureCollection>)> {
+    request_headers: HeaderMap,
+) -> Result<Response> {
     tracing::debug!("{:#?}", query);
 
-    // Limit
-    if let Some(limit) = query.limit {
-        if limit > 10000 {
-            query.limit = Some(10000);
+    let json_fg = request_headers
+        .get(ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .and_then(jsonfg::negotiate);
+
+    let format = match query.f.as_deref() {
+        None => match json_fg {
+            Some(content_type) => Format::JsonFg(content_type),
+            None => Format::GeoJson,
+        },
+        Some("json" | "geojson") => Format::GeoJson,
+        Some("topojson") => Format::TopoJson,
+        Some("jsonfg") => Format::JsonFg(JSON_FG.to_string()),
+        Some(f) => {
+            return Err(Error::Exception(
+                StatusCode::BAD_REQUEST,
+                format!("Unsupported format `{}`", f),
+            ))
         }
-    } else {
-        query.limit = Some(100);
+    };
+
+    pagination::resolve(
+        state.settings.pagination,
+        &mut query.offset,
+        &mut query.additional_parameters,
+    )?;
+
+    // Offset, negative values are already rejected on deserialization
+    if query
+        .offset
+        .map_or(false, |offset| offset > i64::MAX as usize)
+    {
+        return Err(Error::Exception(
+            StatusCode::BAD_REQUEST,
+            format!("query parameter `offset` must not exceed {}", i64::MAX),
+        ));
     }
 
+    // Radius
+    match (query.center.as_deref(), query.radius) {
+        (None, None) => {}
+        (Some([lon, lat]), Some(radius))
+            if (-180.0..=180.0).contains(lon)
+                && (-90.0..=90.0).contains(lat)
+                && radius.is_finite()
+                && radius >= 0.0 => {}
+        _ => {
+            return Err(Error::Exception(
+                StatusCode::BAD_REQUEST,
+                "query parameters `center` and `radius` require a longitude, latitude and a positive distance in meters".to_string(),
+            ))
+        }
+    }
+
+    // Cluster distance
+    if query
+        .cluster
+        .map_or(false, |distance| !distance.is_finite() || distance < 0.0)
+    {
+        return Err(Error::Exception(
+            StatusCode::BAD_REQUEST,
+            "query parameter `cluster` must be a finite, non-negative distance".to_string(),
+        ));
+    }
+
+    // Filter CRS
+    if let Some(filter_crs) = query.filter_crs.as_ref() {
+        if !crs::is_known(filter_crs) {
+            return Err(Error::Exception(
+                StatusCode::BAD_REQUEST,
+                format!("Unknown `filter-crs` {}", filter_crs),
+            ));
+        }
+    }
+
+    // Bbox area
+    if let (Some(bbox), Some(max_area)) = (query.bbox.as_ref(), state.settings.max_bbox_area) {
+        if bbox.area(&query.bbox_crs) > max_area {
+            return Err(Error::Exception(
+                StatusCode::BAD_REQUEST,
+                format!(
+                    "query parameter `bbox` must not cover more than {} square units of `bbox-crs`, request a smaller area or page through the items with `limit` and `offset` instead",
+                    max_area
+                ),
+            ));
+        }
+    }
+
+    // Limit
+    let page = Page::new(
+        query.offset,
+        query.limit,
+        state.settings.default_limit,
+        state.settings.max_limit,
+    );
+    query.limit = Some(page.limit);
+
     let collection = state
         .drivers
         .collections
         .read_collection(&collection_id)
         .await?
         .ok_or(Error::NotFound)?;
-    is_supported_crs(&collection, &query.crs).await?;
+    principal.authorize_read(&collection)?;
+    is_supported_crs(&collection, &query.crs, &state.settings)?;
+    is_supported_bbox_crs(&collection, &query.bbox_crs, &state.settings)?;
+    check_filter_vertices(&query, &state.settings)?;
 
     // TODO: validate additional parameters
 
@@ -151,61 +391,278 @@ async fn items(
 
     fc.links.insert_or_update(&[
         Link::new(&url, SELF).mediatype(GEO_JSON),
+        Link::new(with_format(&url, "jsonfg"), ATERNATE)
+            .mediatype(JSON_FG)
+            .title("This document as JSON-FG"),
         Link::new(url.join("../..")?, ROOT).mediatype(JSON),
         Link::new(url.join(".")?, COLLECTION).mediatype(JSON),
     ]);
 
     // pagination
-    if let Some(limit) = query.limit {
-        if query.offset.is_none() {
-            query.offset = Some(0);
+    fc.links.insert_or_update(&page.links(
+        state.settings.pagination,
+        &url,
+        fc.number_matched,
+        GEO_JSON,
+    ));
+
+    let skip_geometry = query.skip_geometry.unwrap_or_default();
+
+    for feature in fc.features.iter_mut() {
+        if let Some(format) = query.geometry_format.filter(|_| !skip_geometry) {
+            encode_geometry(feature, format);
         }
 
-        if let Some(offset) = query.offset {
-            if offset != 0 && offset >= limit {
-                query.offset = Some(offset - limit);
-                url.set_query(serde_qs::to_string(&query).ok().as_deref());
-                let previous = Link::new(&url, PREV).mediatype(GEO_JSON);
-                fc.links.insert_or_update(&[previous]);
+        feature.links.insert_or_update(&[
+            Link::new(item_url(&url, feature.id.as_ref().unwrap())?, SELF).mediatype(GEO_JSON),
+            Link::new(url.join("../..")?, ROOT).mediatype(JSON),
+            Link::new(url.join(&format!("../{}", collection.id))?, COLLECTION).mediatype(JSON),
+        ])
+    }
+
+    let mut headers = HeaderMap::new();
+    if !skip_geometry {
+        headers.insert("Content-Crs", query.crs.to_string().parse().unwrap());
+    }
+
+    match format {
+        Format::GeoJson if skip_geometry => {
+            headers.insert(CONTENT_TYPE, GEO_JSON.parse().unwrap());
+            let mut document = serde_json::to_value(&fc).unwrap();
+            strip_geometries(&mut document);
+            Ok((headers, Json(document)).into_response())
+        }
+        Format::GeoJson => {
+            headers.insert(CONTENT_TYPE, GEO_JSON.parse().unwrap());
+            Ok((headers, Json(fc)).into_response())
+        }
+        Format::TopoJson => {
+            headers.insert(CONTENT_TYPE, JSON.parse().unwrap());
+            let topology = topojson::encode(&collection.id, &fc.features);
+            Ok((headers, Json(topology)).into_response())
+        }
+        Format::JsonFg(content_type) => {
+            headers.insert(CONTENT_TYPE, content_type.parse().unwrap());
+            let mut document = jsonfg::encode(&fc, &collection.id, &query.crs);
+            if skip_geometry {
+                strip_geometries(&mut document);
             }
+            Ok((headers, Json(document)).into_response())
+        }
+    }
+}
 
-            if let Some(number_matched) = fc.number_matched {
-                if number_matched > (offset + limit) as u64 {
-                    query.offset = Some(offset + limit);
-                    url.set_query(serde_qs::to_string(&query).ok().as_deref());
-                    let next = Link::new(&url, NEXT).mediatype(GEO_JSON);
-                    fc.links.insert_or_update(&[next]);
-                }
+/// Sets the geometries of the features of an encoded collection to `null`
+fn strip_geometries(document: &mut Value) {
+    if let Some(features) = document["features"].as_array_mut() {
+        for feature in features {
+            feature["geometry"] = Value::Null;
+            if feature.get("place").is_some() {
+                feature["place"] = Value::Null;
             }
         }
     }
+}
 
-    for feature in fc.features.iter_mut() {
-        feature.links.insert_or_update(&[
+/// Returns the url with the `f` query parameter set to `format`
+fn with_format(url: &url::Url, format: &str) -> url::Url {
+    let mut url = url.clone();
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(key, _)| key != "f")
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    url.query_pairs_mut()
+        .clear()
+        .extend_pairs(pairs)
+        .append_pair("f", format);
+    url
+}
+
+/// Adds the geometry in a well-known encoding to the feature properties
+fn encode_geometry(feature: &mut Feature, format: GeometryFormat) {
+    let (key, value) = match format {
+        GeometryFormat::Wkt => ("wkt", well_known::to_wkt(&feature.geometry)),
+        GeometryFormat::Wkb => ("wkb", well_known::to_wkb_hex(&feature.geometry)),
+    };
+    feature.append_properties(serde_json::Map::from_iter([(
+        key.to_string(),
+        Value::String(value),
+    )]));
+}
+
+/// Encodings of item listings
+enum Format {
+    GeoJson,
+    TopoJson,
+    /// JSON-FG, served with the negotiated content type
+    JsonFg(String),
+}
+
+async fn values(
+    State(state): State<AppState>,
+    RemoteUrl(url): RemoteUrl,
+    Path((collection_id, property)): Path<(String, String)>,
+    Extension(principal): Extension<Principal>,
+    Qs(mut query): Qs<Query>,
+) -> Result<Json<PropertyValues>> {
+    query.limit = Some(query.limit.unwrap_or(MAX_VALUES).min(MAX_VALUES));
+
+    let collection = state
+        .drivers
+        .collections
+        .read_collection(&collection_id)
+        .await?
+        .ok_or(Error::NotFound)?;
+    principal.authorize_read(&collection)?;
+    is_supported_bbox_crs(&collection, &query.bbox_crs, &state.settings)?;
+    check_filter_vertices(&query, &state.settings)?;
+
+    let values = state
+        .drivers
+        .features
+        .distinct_values(&collection_id, &property, &query)
+        .await?;
+
+    Ok(Json(PropertyValues {
+        property,
+        values,
+        links: vec![
+            Link::new(&url, SELF).mediatype(JSON),
             Link::new(
-                url.join(&format!("items/{}", feature.id.as_ref().unwrap()))?,
-                SELF,
+                url.join(&format!("../../../{}", collection_id))?,
+                COLLECTION,
             )
-            .mediatype(GEO_JSON),
-            Link::new(url.join("../..")?, ROOT).mediatype(JSON),
-            Link::new(url.join(&format!("../{}", collection.id))?, COLLECTION).mediatype(JSON),
-        ])
+            .mediatype(JSON),
+        ],
+    }))
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+struct AggregationQuery {
+    group_by: Option<String>,
+}
+
+async fn aggregate(
+    State(state): State<AppState>,
+    RemoteUrl(url): RemoteUrl,
+    Path((collection_id, property)): Path<(String, String)>,
+    Extension(principal): Extension<Principal>,
+    Qs(aggregation): Qs<AggregationQuery>,
+    Qs(mut query): Qs<Query>,
+) -> Result<Json<Aggregation>> {
+    query.additional_parameters.remove("group-by");
+
+    let collection = state
+        .drivers
+        .collections
+        .read_collection(&collection_id)
+        .await?
+        .ok_or(Error::NotFound)?;
+    principal.authorize_read(&collection)?;
+    is_supported_bbox_crs(&collection, &query.bbox_crs, &state.settings)?;
+    check_filter_vertices(&query, &state.settings)?;
+
+    let queryables = state.drivers.features.queryables(&collection_id).await?;
+    for name in std::iter::once(&property).chain(aggregation.group_by.as_ref()) {
+        if !queryables.contains(name) {
+            return Err(Error::Exception(
+                StatusCode::BAD_REQUEST,
+                format!("Unknown property `{}`", name),
+            ));
+        }
     }
 
-    let mut headers = HeaderMap::new();
-    headers.insert("Content-Crs", query.crs.to_string().parse().unwrap());
-    headers.insert(CONTENT_TYPE, GEO_JSON.parse().unwrap());
+    let groups = state
+        .drivers
+        .features
+        .aggregate(
+            &collection_id,
+            &property,
+            aggregation.group_by.as_deref(),
+            &query,
+        )
+        .await?;
 
-    Ok((headers, Json(fc)))
+    Ok(Json(Aggregation {
+        property,
+        group_by: aggregation.group_by,
+        groups,
+        links: vec![
+            Link::new(&url, SELF).mediatype(JSON),
+            Link::new(
+                url.join(&format!("../../../{}", collection_id))?,
+                COLLECTION,
+            )
+            .mediatype(JSON),
+        ],
+    }))
+}
+
+/// Rejects filters with more than the allowed number of vertices
+fn check_filter_vertices(query: &Query, settings: &Settings) -> Result<()> {
+    match query.filter.as_deref() {
+        Some(filter) => vertices::check(
+            "filter",
+            vertices::count_filter(filter, &query.filter_lang.clone().unwrap_or_default()),
+            settings.max_filter_vertices,
+        ),
+        None => Ok(()),
+    }
 }
 
-async fn is_supported_crs(collection: &Collection, crs: &Crs) -> Result<(), Error> {
-    if collection.crs.contains(crs) {
+/// Checks the `bbox-crs`, which defaults to CRS84 and is always supported,
+/// against the CRS of the collection
+fn is_supported_bbox_crs(collection: &Collection, crs: &Crs, settings: &Settings) -> Result<()> {
+    if *crs == Crs::default()
+        || collection.crs.contains(crs)
+        || (collection.crs.is_empty() && settings.crs.contains(crs))
+    {
         Ok(())
     } else {
         Err(Error::Exception(
             StatusCode::BAD_REQUEST,
-            format!("Unsuported CRS `{}`", crs),
+            format!("Unsupported `bbox-crs` {}", crs),
+        ))
+    }
+}
+
+fn is_valid_id(collection: &Collection, feature: &Feature) -> Result<(), Error> {
+    match (collection.id_strategy, &feature.id) {
+        (Some(IdStrategy::Uuid | IdStrategy::Sequential), Some(id)) => Err(Error::Exception(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Collection `{}` does not accept client supplied id `{}`",
+                collection.id, id
+            ),
+        )),
+        (Some(IdStrategy::Client), None) => Err(Error::Exception(
+            StatusCode::BAD_REQUEST,
+            format!("Collection `{}` requires a feature id", collection.id),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Resolves the url of an item relative to the `items` url, percent-encoding
+/// reserved characters such as `/` in the feature id
+fn item_url(url: &url::Url, id: &str) -> Result<url::Url> {
+    let mut location = url.join("items")?;
+    location
+        .path_segments_mut()
+        .expect("http url can be a base")
+        .push(id);
+    Ok(location)
+}
+
+fn is_writable(collection: &Collection) -> Result<(), Error> {
+    if collection.table.is_none() {
+        Ok(())
+    } else {
+        Err(Error::Exception(
+            StatusCode::METHOD_NOT_ALLOWED,
+            format!("Collection `{}` is read-only", collection.id),
         ))
     }
 }
@@ -213,10 +670,35 @@ async fn is_supported_crs(collection: &Collection, crs: &Crs) -> Result<(), Erro
 pub(crate) fn router(state: &AppState) -> Router<AppState> {
     state.conformance.write().unwrap().extend(&CONFORMANCE);
 
-    Router::new()
-        .route("/collections/:collection_id/items", get(items).post(create))
+    let router = Router::new()
+        .route(
+            "/collections/:collection_id/items",
+            get(items)
+                .post(create)
+                .options(|| options("GET, HEAD, POST, OPTIONS"))
+                .fallback(|| method_not_allowed("GET, HEAD, POST, OPTIONS")),
+        )
         .route(
             "/collections/:collection_id/items/:id",
-            get(read).put(update).delete(remove),
+            get(read)
+                .put(update)
+                .delete(remove)
+                .options(|| options("GET, HEAD, PUT, DELETE, OPTIONS"))
+                .fallback(|| method_not_allowed("GET, HEAD, PUT, DELETE, OPTIONS")),
         )
+        .route(
+            "/collections/:collection_id/properties/:property/values",
+            get(values),
+        )
+        .route(
+            "/collections/:collection_id/properties/:property/aggregate",
+            get(aggregate),
+        )
+        .route_layer(from_fn(middleware::json_content_type))
+        .route_layer(from_fn_with_state(
+            state.settings.max_body_size,
+            middleware::decompress,
+        ));
+
+    middleware::route_timeout(router, state.settings.features_timeout)
 }
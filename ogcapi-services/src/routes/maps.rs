@@ -0,0 +1,134 @@
+use axum::{
+    extract::{Path, State},
+    http::{header::CONTENT_TYPE, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Extension, Router,
+};
+use serde::Deserialize;
+
+use ogcapi_types::common::{media_type::PNG, Bbox, Crs};
+
+use crate::{
+    extractors::Qs,
+    middleware::{self, Principal},
+    render::{self, Style, Viewport},
+    routes::collections::is_supported_crs,
+    AppState, Error, Result,
+};
+
+const CONFORMANCE: [&str; 2] = [
+    "http://www.opengis.net/spec/ogcapi-maps-1/1.0/conf/core",
+    "http://www.opengis.net/spec/ogcapi-maps-1/1.0/conf/png",
+];
+
+/// Default size of maps in pixels
+const SIZE: u32 = 512;
+/// Maximum width and height of maps in pixels
+const MAX_SIZE: u32 = 4096;
+
+#[derive(Deserialize, Debug)]
+struct MapQuery {
+    /// Area to render as `minx,miny,maxx,maxy` in the `crs`
+    bbox: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+    crs: Option<String>,
+    /// Identifier of a style to render the features with
+    style: Option<String>,
+}
+
+/// Renders the features of a collection within a bbox into a PNG
+///
+/// The bbox keeps its aspect ratio, it is centered and padded to the
+/// requested size. Areas without features are transparent.
+async fn map(
+    State(state): State<AppState>,
+    Path(collection_id): Path<String>,
+    Extension(principal): Extension<Principal>,
+    Qs(query): Qs<MapQuery>,
+) -> Result<Response> {
+    let collection = state
+        .drivers
+        .collections
+        .read_collection(&collection_id)
+        .await?
+        .ok_or(Error::NotFound)?;
+    principal.authorize_read(&collection)?;
+
+    let bad_request = |message: String| Error::Exception(StatusCode::BAD_REQUEST, message);
+
+    let crs = match query.crs {
+        Some(crs) => crs.parse::<Crs>().map_err(bad_request)?,
+        None => Crs::default(),
+    };
+    is_supported_crs(&collection, &crs, &state.settings)?;
+
+    let bbox = match query.bbox.as_deref().map(str::parse::<Bbox>) {
+        Some(Ok(Bbox::Bbox2D(bbox))) => bbox,
+        Some(Ok(Bbox::Bbox3D(bbox))) => [bbox[0], bbox[1], bbox[3], bbox[4]],
+        Some(Err(e)) => return Err(bad_request(e.to_string())),
+        None => return Err(bad_request("Missing query parameter `bbox`".to_string())),
+    };
+    let (dx, dy) = (bbox[2] - bbox[0], bbox[3] - bbox[1]);
+    if !(dx > 0.0 && dy > 0.0) {
+        return Err(bad_request(
+            "Query parameter `bbox` must not be empty".to_string(),
+        ));
+    }
+
+    let width = query.width.unwrap_or(SIZE);
+    let height = query.height.unwrap_or(SIZE);
+    if !(1..=MAX_SIZE).contains(&width) || !(1..=MAX_SIZE).contains(&height) {
+        return Err(bad_request(format!(
+            "Query parameters `width` and `height` must be between 1 and {}",
+            MAX_SIZE
+        )));
+    }
+
+    let style = match query.style {
+        #[cfg(feature = "styles")]
+        Some(id) => {
+            let style = state
+                .drivers
+                .styles
+                .read_style(&id)
+                .await?
+                .ok_or_else(|| bad_request(format!("Unknown style `{}`", id)))?;
+            Style::from_mapbox(&style)
+        }
+        #[cfg(not(feature = "styles"))]
+        Some(_) => return Err(bad_request("Styles are not supported".to_string())),
+        None => Style::default(),
+    };
+
+    let geometries = state
+        .drivers
+        .tiles
+        .map_geometries(&collection.id, &bbox, &crs)
+        .await?;
+
+    // uniform scale, padding the shorter side of the bbox
+    let scale = (width as f64 / dx).min(height as f64 / dy);
+    let viewport = Viewport {
+        width,
+        height,
+        origin: (
+            bbox[0] - (width as f64 / scale - dx) / 2.0,
+            bbox[3] + (height as f64 / scale - dy) / 2.0,
+        ),
+        scale: (scale, -scale),
+    };
+
+    let png = render::png(&geometries, &viewport, &style)?;
+
+    Ok(([(CONTENT_TYPE, PNG)], png).into_response())
+}
+
+pub(crate) fn router(state: &AppState) -> Router<AppState> {
+    state.conformance.write().unwrap().extend(&CONFORMANCE);
+
+    let router = Router::new().route("/collections/:collection_id/map", get(map));
+
+    middleware::route_timeout(router, state.settings.tiles_timeout)
+}
@@ -1,11 +1,16 @@
 pub(crate) mod api;
+pub(crate) mod capabilities;
 pub(crate) mod collections;
 #[cfg(feature = "edr")]
 pub(crate) mod edr;
 #[cfg(feature = "features")]
 pub(crate) mod features;
+#[cfg(feature = "maps")]
+pub(crate) mod maps;
 #[cfg(feature = "processes")]
 pub(crate) mod processes;
+#[cfg(feature = "records")]
+pub(crate) mod records;
 #[cfg(feature = "stac")]
 pub(crate) mod stac;
 #[cfg(feature = "styles")]
@@ -13,24 +18,40 @@ pub(crate) mod styles;
 #[cfg(feature = "tiles")]
 pub(crate) mod tiles;
 
-use axum::{extract::State, Json};
+use axum::{
+    extract::State,
+    http::{
+        header::{ALLOW, CONTENT_LANGUAGE},
+        HeaderMap, StatusCode,
+    },
+    response::IntoResponse,
+    Json,
+};
 
 #[cfg(feature = "stac")]
 use ogcapi_types::common::link_rel::SEARCH;
 use ogcapi_types::common::{
     link_rel::{CONFORMANCE, ROOT, SELF, SERVICE_DESC, SERVICE_DOC},
-    media_type::{HTML, JSON, OPEN_API_JSON},
+    media_type::{self, HTML, JSON, OPEN_API_JSON},
     Conformance, LandingPage, Link, Linked,
 };
 
-use crate::{extractors::RemoteUrl, AppState, Result};
+use crate::{
+    extractors::{AcceptLanguage, RemoteUrl},
+    i18n::{self, Translate},
+    AppState, Error, Result,
+};
 
 pub(crate) async fn root(
     RemoteUrl(url): RemoteUrl,
     State(state): State<AppState>,
-) -> Result<Json<LandingPage>> {
+    accept_language: AcceptLanguage,
+) -> Result<(HeaderMap, Json<LandingPage>)> {
     let mut root = state.root.read().unwrap().to_owned();
 
+    let languages = root.languages();
+    let language = root.translate(&accept_language, &state.settings.default_language);
+
     root.links.insert_or_update(&[
         Link::new(format!("{}/", url.as_str().trim_end_matches('/')), SELF).mediatype(JSON),
         Link::new(".", ROOT).mediatype(JSON),
@@ -40,9 +61,6 @@ pub(crate) async fn root(
         Link::new("swagger", SERVICE_DOC)
             .title("The Open API definition (Swagger UI)")
             .mediatype(HTML),
-        // Link::new("redoc", SERVICE_DOC)
-        //     .title("The Open API definition (Redoc")
-        //     .mediatype(HTML),
         Link::new("conformance", CONFORMANCE)
             .title("Conformance classes implemented by this API")
             .mediatype(JSON),
@@ -51,14 +69,72 @@ pub(crate) async fn root(
             .title("URI for the STAC API - Item Search endpoint")
             .mediatype(JSON),
     ]);
+    // a second `service-doc`, which `insert_or_update` would replace the
+    // first one with
+    if !root.links.iter().any(|l| l.href == "redoc") {
+        root.links.push(
+            Link::new("redoc", SERVICE_DOC)
+                .title("The Open API definition (ReDoc)")
+                .mediatype(HTML),
+        );
+    }
+    root.links
+        .extend(state.settings.landing_page_links.iter().map(|link| {
+            let mut l = Link::new(&link.href, &link.rel);
+            l.r#type = media_type::from_extension(link.href.path()).map(ToOwned::to_owned);
+            l
+        }));
     root.links.resolve_relative_links();
+    i18n::link_languages(
+        &mut root.links,
+        &url,
+        &language,
+        &state.settings.default_language,
+        languages,
+    );
 
     #[cfg(feature = "stac")]
-    let root = root.conforms_to(&state.conformance.read().unwrap().conforms_to[..]);
+    let root = root.conforms_to(&advertised_conformance(&state).conforms_to[..]);
 
-    Ok(Json(root))
+    let mut headers = HeaderMap::new();
+    if let Ok(language) = language.parse() {
+        headers.insert(CONTENT_LANGUAGE, language);
+    }
+
+    Ok((headers, Json(root)))
 }
 
 pub(crate) async fn conformance(State(state): State<AppState>) -> Json<Conformance> {
-    Json(state.conformance.read().unwrap().to_owned())
+    Json(advertised_conformance(&state))
+}
+
+/// Implemented conformance classes without the ones disabled in the settings
+fn advertised_conformance(state: &AppState) -> Conformance {
+    let mut conformance = state.conformance.read().unwrap().to_owned();
+    conformance
+        .conforms_to
+        .retain(|class| !state.settings.disabled_conformance_classes.contains(class));
+    // reprojection is only offered with more than the default CRS
+    if state.settings.crs.len() < 2 {
+        conformance
+            .conforms_to
+            .retain(|class| class != crate::crs::CONFORMANCE);
+    }
+    conformance
+}
+
+/// Responds to `OPTIONS` requests with the methods allowed on a resource
+pub(crate) async fn options(methods: &'static str) -> impl IntoResponse {
+    (StatusCode::NO_CONTENT, [(ALLOW, methods)])
+}
+
+/// Responds to unsupported methods on a known resource with the allowed ones
+pub(crate) async fn method_not_allowed(methods: &'static str) -> impl IntoResponse {
+    (
+        [(ALLOW, methods)],
+        Error::Exception(
+            StatusCode::METHOD_NOT_ALLOWED,
+            format!("Method not allowed, expected one of `{}`", methods),
+        ),
+    )
 }
@@ -1,73 +1,82 @@
+use std::io::SeekFrom;
+
 use axum::{
+    body::StreamBody,
     extract::{Path, Query, State},
-    http::StatusCode,
-    response::{IntoResponse, Response},
+    http::{
+        header::{ACCEPT_RANGES, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, LOCATION, RANGE},
+        HeaderMap, HeaderValue, StatusCode,
+    },
+    middleware::{from_fn, from_fn_with_state},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
     routing::{get, post},
     Json, Router,
 };
-use url::Position;
+use tokio::{
+    io::{AsyncReadExt, AsyncSeekExt},
+    sync::{broadcast::error::RecvError, mpsc},
+};
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_util::io::ReaderStream;
+use url::{Position, Url};
 
 use ogcapi_types::{
     common::{
-        link_rel::{NEXT, PREV, PROCESSES, SELF},
+        link_rel::{PROCESSES, SELF, STATUS},
         media_type::JSON,
         Link,
     },
-    processes::{Execute, Process, ProcessList, ProcessQuery, ProcessSummary},
+    processes::{Execute, InlineOrRefData, Process, ProcessList, ProcessQuery, ProcessSummary},
 };
 
-use crate::{extractors::RemoteUrl, AppState, Error, Result};
+use crate::{
+    extractors::{JsonBody, RemoteUrl},
+    middleware,
+    pagination::Page,
+    routes::{method_not_allowed, options},
+    worker, AppState, Error, PaginationStyle, Result,
+};
 
-const CONFORMANCE: [&str; 4] = [
+const CONFORMANCE: [&str; 5] = [
     "http://www.opengis.net/spec/ogcapi-processes-1/1.0/conf/core",
     "http://www.opengis.net/spec/ogcapi-processes-1/1.0/conf/ogc-process-description",
     "http://www.opengis.net/spec/ogcapi-processes-1/1.0/conf/json",
     // "http://www.opengis.net/spec/ogcapi-processes-1/1.0/conf/html",
     // "http://www.opengis.net/spec/ogcapi-processes-1/1.0/conf/oas30",
     // "http://www.opengis.net/spec/ogcapi-processes-1/1.0/conf/job-list",
-    // "http://www.opengis.net/spec/ogcapi-processes-1/1.0/conf/callback",
+    "http://www.opengis.net/spec/ogcapi-processes-1/1.0/conf/callback",
     "http://www.opengis.net/spec/ogcapi-processes-1/1.0/conf/dismiss",
 ];
 
 async fn processes(
     State(state): State<AppState>,
-    RemoteUrl(mut url): RemoteUrl,
-    Query(mut query): Query<ProcessQuery>,
+    RemoteUrl(url): RemoteUrl,
+    Query(query): Query<ProcessQuery>,
 ) -> Result<Json<ProcessList>> {
-    let limit = query
-        .limit
-        .unwrap_or_else(|| state.processors.read().unwrap().len());
-    let offset = query.offset.unwrap_or(0);
-
-    let mut summaries: Vec<ProcessSummary> = state
-        .processors
-        .read()
-        .unwrap()
-        .clone()
+    let processors = state.processors.read().unwrap().clone();
+    let number_matched = processors.len();
+
+    let page = Page::new(query.offset, query.limit, number_matched, usize::MAX);
+
+    let mut summaries: Vec<ProcessSummary> = processors
         .into_iter()
-        .skip(offset)
-        .take(limit)
+        .skip(page.offset)
+        .take(page.limit)
         .map(|(_id, p)| p.process().summary)
         .collect();
 
     let mut links = vec![Link::new(&url, SELF).mediatype(JSON)];
 
     if query.limit.is_some() {
-        if offset != 0 && offset >= limit {
-            query.offset = Some(offset - limit);
-            let query_string = serde_qs::to_string(&query)?;
-            url.set_query(Some(&query_string));
-            let previous = Link::new(&url, PREV).mediatype(JSON);
-            links.push(previous);
-        }
-
-        if summaries.len() == limit {
-            query.offset = Some(offset + limit);
-            let query_string = serde_qs::to_string(&query)?;
-            url.set_query(Some(&query_string));
-            let next = Link::new(&url, NEXT).mediatype(JSON);
-            links.push(next);
-        }
+        links.extend(page.links(
+            PaginationStyle::Offset,
+            &url,
+            Some(number_matched as u64),
+            JSON,
+        ));
     }
 
     summaries.iter_mut().for_each(|p| {
@@ -110,12 +119,34 @@ async fn execution(
     State(state): State<AppState>,
     RemoteUrl(url): RemoteUrl,
     Path(id): Path<String>,
-    Json(execute): Json<Execute>,
+    headers: HeaderMap,
+    JsonBody(execute): JsonBody<Execute>,
 ) -> Result<Response> {
     let processors = state.processors.read().unwrap().clone();
     let processor = processors.get(&id);
     match processor {
-        Some(processor) => processor.execute(execute, &state, &url).await,
+        Some(processor) => {
+            let respond_async = headers
+                .get_all("Prefer")
+                .iter()
+                .filter_map(|v| v.to_str().ok())
+                .any(|v| v.split(',').any(|p| p.trim() == "respond-async"));
+
+            if respond_async || execute.subscriber.is_some() {
+                let mut status = worker::spawn(&state, processor.clone(), execute).await?;
+
+                let location = url.join(&format!("../../jobs/{}", status.job_id))?;
+                status.links = vec![Link::new(&location, STATUS).mediatype(JSON)];
+
+                let mut headers = HeaderMap::new();
+                headers.insert(LOCATION, location.as_str().parse().unwrap());
+
+                Ok((StatusCode::CREATED, headers, Json(status)).into_response())
+            } else {
+                let _permit = worker::permit(&state, &id).await;
+                processor.execute(execute, &state, &url).await
+            }
+        }
         None => Err(Error::Exception(
             StatusCode::NOT_FOUND,
             format!("No process with id `{}`", id),
@@ -147,10 +178,46 @@ async fn status(
     }
 }
 
+async fn events(State(state): State<AppState>, Path(id): Path<String>) -> Result<Response> {
+    // subscribe before reading the status to not miss any update
+    let mut updates = state.job_events.subscribe();
+
+    let status = state.drivers.jobs.status(&id).await?.ok_or_else(|| {
+        Error::Exception(StatusCode::NOT_FOUND, format!("No job with id `{}`", id))
+    })?;
+
+    let (tx, rx) = mpsc::channel(16);
+
+    tokio::spawn(async move {
+        let mut status = status;
+        loop {
+            let event = Event::default().event("status").json_data(&status);
+            if tx.send(event).await.is_err() || status.status.is_final() {
+                break;
+            }
+
+            status = loop {
+                match updates.recv().await {
+                    Ok(update) if update.job_id == id => break update,
+                    Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return,
+                }
+            };
+        }
+    });
+
+    Ok(Sse::new(ReceiverStream::new(rx))
+        .keep_alive(KeepAlive::default())
+        .into_response())
+}
+
 async fn delete(State(state): State<AppState>, Path(id): Path<String>) -> Result<Response> {
     let status = state.drivers.jobs.dismiss(&id).await?;
 
-    // TODO: cancel execution
+    // running executions are cancelled by the worker on this event
+    if let Some(info) = &status {
+        state.job_events.send(info.clone()).ok();
+    }
 
     match status {
         Some(info) => Ok(Json(info).into_response()),
@@ -161,13 +228,37 @@ async fn delete(State(state): State<AppState>, Path(id): Path<String>) -> Result
     }
 }
 
-async fn results(State(state): State<AppState>, Path(id): Path<String>) -> Result<Response> {
+async fn results(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response> {
     let results = state.drivers.jobs.results(&id).await?;
 
     // TODO: check if job is finished
 
     match results {
-        Some(results) => Ok(Json(results).into_response()),
+        Some(results) => {
+            // a single output stored in a file is served as is
+            if let [InlineOrRefData::Link(link)] = &results.results.values().collect::<Vec<_>>()[..]
+            {
+                if let Some(path) = Url::parse(&link.href)
+                    .ok()
+                    .filter(|url| url.scheme() == "file")
+                    .and_then(|url| url.to_file_path().ok())
+                {
+                    let dir = state.settings.results_dir.as_deref();
+                    return match results_file(dir, &path).await {
+                        Some(path) => file(&path, link.r#type.as_deref(), &headers).await,
+                        None => Err(Error::Exception(
+                            StatusCode::NOT_FOUND,
+                            format!("No results of job `{}`", id),
+                        )),
+                    };
+                }
+            }
+            Ok(Json(results).into_response())
+        }
         None => Err(Error::Exception(
             StatusCode::NOT_FOUND,
             format!("No job with id `{}`", id),
@@ -175,6 +266,96 @@ async fn results(State(state): State<AppState>, Path(id): Path<String>) -> Resul
     }
 }
 
+/// Resolves a result file, `None` unless it exists within the results
+/// directory `dir`
+async fn results_file(
+    dir: Option<&std::path::Path>,
+    path: &std::path::Path,
+) -> Option<std::path::PathBuf> {
+    let dir = tokio::fs::canonicalize(dir?).await.ok()?;
+    let path = tokio::fs::canonicalize(path).await.ok()?;
+    path.starts_with(&dir).then_some(path)
+}
+
+/// Streams a file, or the byte range of it requested by the `Range` header
+async fn file(
+    path: &std::path::Path,
+    media_type: Option<&str>,
+    headers: &HeaderMap,
+) -> Result<Response> {
+    let mut file = tokio::fs::File::open(path)
+        .await
+        .map_err(anyhow::Error::from)?;
+    let size = file.metadata().await.map_err(anyhow::Error::from)?.len();
+
+    let range = headers
+        .get(RANGE)
+        .and_then(|v| v.to_str().ok())
+        // invalid and multiple ranges are ignored, the full file is served
+        .and_then(|v| byte_range(v, size));
+
+    let mut headers = HeaderMap::new();
+    headers.insert(ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+    headers.insert(
+        CONTENT_TYPE,
+        media_type
+            .and_then(|t| t.parse().ok())
+            .unwrap_or(HeaderValue::from_static("application/octet-stream")),
+    );
+
+    let (status, start, length) = match range {
+        None => (StatusCode::OK, 0, size),
+        Some(Some((start, end))) => {
+            headers.insert(
+                CONTENT_RANGE,
+                format!("bytes {start}-{end}/{size}").parse().unwrap(),
+            );
+            (StatusCode::PARTIAL_CONTENT, start, end - start + 1)
+        }
+        Some(None) => {
+            headers.insert(CONTENT_RANGE, format!("bytes */{size}").parse().unwrap());
+            return Ok((StatusCode::RANGE_NOT_SATISFIABLE, headers).into_response());
+        }
+    };
+    headers.insert(CONTENT_LENGTH, length.into());
+
+    file.seek(SeekFrom::Start(start))
+        .await
+        .map_err(anyhow::Error::from)?;
+    let body = StreamBody::new(ReaderStream::new(file.take(length)));
+
+    Ok((status, headers, body).into_response())
+}
+
+/// Parses a single `bytes` range into inclusive offsets within a file of
+/// `size` bytes
+///
+/// Returns `None` if the range is invalid or not a single range and
+/// `Some(None)` if it is not satisfiable.
+fn byte_range(range: &str, size: u64) -> Option<Option<(u64, u64)>> {
+    let (start, end) = range.trim().strip_prefix("bytes=")?.split_once('-')?;
+
+    let parse = |value: &str| match value.trim() {
+        "" => Some(None),
+        value => value.parse::<u64>().ok().map(Some),
+    };
+    let (start, end) = match (parse(start)?, parse(end)?) {
+        (None, None) => return None,
+        (Some(start), Some(end)) if start > end => return None,
+        (None, Some(suffix)) => (size.saturating_sub(suffix), size.checked_sub(1)),
+        (Some(start), end) => (
+            start,
+            size.checked_sub(1)
+                .map(|last| end.map_or(last, |end| end.min(last))),
+        ),
+    };
+
+    Some(
+        end.filter(|end| start <= *end && start < size)
+            .map(|end| (start, end)),
+    )
+}
+
 pub(crate) fn router(state: &AppState) -> Router<AppState> {
     let mut root = state.root.write().unwrap();
     root.links.append(&mut vec![
@@ -191,8 +372,25 @@ pub(crate) fn router(state: &AppState) -> Router<AppState> {
     Router::new()
         .route("/processes", get(processes))
         .route("/processes/:id", get(process))
-        .route("/processes/:id/execution", post(execution))
+        .route(
+            "/processes/:id/execution",
+            post(execution)
+                .options(|| options("POST, OPTIONS"))
+                .fallback(|| method_not_allowed("POST, OPTIONS")),
+        )
         .route("/jobs", get(jobs))
-        .route("/jobs/:id", get(status).delete(delete))
+        .route(
+            "/jobs/:id",
+            get(status)
+                .delete(delete)
+                .options(|| options("GET, HEAD, DELETE, OPTIONS"))
+                .fallback(|| method_not_allowed("GET, HEAD, DELETE, OPTIONS")),
+        )
         .route("/jobs/:id/results", get(results))
+        .route("/jobs/:id/events", get(events))
+        .route_layer(from_fn(middleware::json_content_type))
+        .route_layer(from_fn_with_state(
+            state.settings.max_body_size,
+            middleware::decompress,
+        ))
 }
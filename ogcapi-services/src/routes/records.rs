@@ -0,0 +1,18 @@
+use axum::Router;
+
+use crate::AppState;
+
+const CONFORMANCE: [&str; 4] = [
+    "http://www.opengis.net/spec/ogcapi-records-1/1.0/conf/record-core",
+    "http://www.opengis.net/spec/ogcapi-records-1/1.0/conf/record-collection",
+    "http://www.opengis.net/spec/ogcapi-records-1/1.0/conf/record-api",
+    "http://www.opengis.net/spec/ogcapi-records-1/1.0/conf/searchable-catalog",
+];
+
+/// Records are served as features of collections with item type `record`,
+/// this router only advertises the conformance classes.
+pub(crate) fn router(state: &AppState) -> Router<AppState> {
+    state.conformance.write().unwrap().extend(&CONFORMANCE);
+
+    Router::new()
+}
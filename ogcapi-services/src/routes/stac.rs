@@ -17,8 +17,9 @@ use ogcapi_types::{
 use url::Url;
 
 use crate::{
-    extractors::{Qs, RemoteUrl},
-    AppState, Error, Result,
+    extractors::{JsonBody, Qs, RemoteUrl},
+    pagination::Page,
+    vertices, AppState, Error, Result,
 };
 
 pub(crate) async fn search_get(
@@ -32,7 +33,7 @@ pub(crate) async fn search_get(
 pub(crate) async fn search_post(
     State(state): State<AppState>,
     RemoteUrl(url): RemoteUrl,
-    Json(params): Json<SearchBody>,
+    JsonBody(params): JsonBody<SearchBody>,
 ) -> Result<(HeaderMap, Json<FeatureCollection>)> {
     search(params.into(), url, state).await
 }
@@ -79,6 +80,15 @@ pub(crate) async fn search(
         }
     }
 
+    // Intersects
+    if let Some(intersects) = params.intersects.as_ref() {
+        vertices::check(
+            "intersects",
+            vertices::count(intersects),
+            state.settings.max_filter_vertices,
+        )?;
+    }
+
     let mut fc = state.db.search(&params).await?;
 
     fc.links.insert_or_update(&[
@@ -88,26 +98,23 @@ pub(crate) async fn search(
 
     // pagination
     if let Some(limit) = params.limit {
-        if params.offset.is_none() {
-            params.offset = Some(0);
-        }
+        let page = Page {
+            offset: params.offset.unwrap_or(0) as usize,
+            limit: limit as usize,
+        };
 
-        if let Some(offset) = params.offset {
-            if offset != 0 && offset >= limit {
-                params.offset = Some(offset - limit);
-                url.set_query(serde_qs::to_string(&params).ok().as_deref());
-                let previous = Link::new(&url, PREV).mediatype(GEO_JSON);
-                fc.links.insert_or_update(&[previous]);
-            }
+        if let Some(offset) = page.prev() {
+            params.offset = Some(offset as u64);
+            url.set_query(serde_qs::to_string(&params).ok().as_deref());
+            let previous = Link::new(&url, PREV).mediatype(GEO_JSON);
+            fc.links.insert_or_update(&[previous]);
+        }
 
-            if let Some(number_matched) = fc.number_matched {
-                if number_matched > offset + limit {
-                    params.offset = Some(offset + limit);
-                    url.set_query(serde_qs::to_string(&params).ok().as_deref());
-                    let next = Link::new(&url, NEXT).mediatype(GEO_JSON);
-                    fc.links.insert_or_update(&[next]);
-                }
-            }
+        if let Some(offset) = page.next(fc.number_matched) {
+            params.offset = Some(offset as u64);
+            url.set_query(serde_qs::to_string(&params).ok().as_deref());
+            let next = Link::new(&url, NEXT).mediatype(GEO_JSON);
+            fc.links.insert_or_update(&[next]);
         }
     }
 
@@ -2,7 +2,11 @@ use std::collections::HashMap;
 
 use axum::{
     extract::{Path, State},
-    http::StatusCode,
+    http::{
+        header::{ACCEPT, CONTENT_TYPE},
+        HeaderMap, StatusCode,
+    },
+    response::{IntoResponse, Response},
     routing::get,
     Json, Router,
 };
@@ -12,7 +16,7 @@ use serde::Deserialize;
 use ogcapi_types::{
     common::{
         link_rel::{TILESETS_VECTOR, TILING_SCHEME},
-        media_type::JSON,
+        media_type::{JSON, MVT, PNG},
         Link,
     },
     tiles::{Query, TileMatrix, TileMatrixSet, TileMatrixSetItem, TileMatrixSets, TileSets},
@@ -20,10 +24,12 @@ use ogcapi_types::{
 
 use crate::{
     extractors::{Qs, RemoteUrl},
+    middleware,
+    render::{self, Style, Viewport},
     AppState, Error, Result,
 };
 
-const CONFORMANCE: [&str; 7] = [
+const CONFORMANCE: [&str; 8] = [
     "http://www.opengis.net/spec/ogcapi-tiles-1/1.0/conf/core",
     "http://www.opengis.net/spec/ogcapi-tiles-1/1.0/conf/tileset",
     "http://www.opengis.net/spec/ogcapi-tiles-1/1.0/conf/tilesets-list",
@@ -31,7 +37,7 @@ const CONFORMANCE: [&str; 7] = [
     "http://www.opengis.net/spec/ogcapi-tiles-1/1.0/conf/dataset-tilesets",
     "http://www.opengis.net/spec/ogcapi-tiles-1/1.0/conf/geodata-selection",
     // "http://www.opengis.net/spec/ogcapi-tiles-1/1.0/conf/jpeg",
-    // "http://www.opengis.net/spec/ogcapi-tiles-1/1.0/conf/png",
+    "http://www.opengis.net/spec/ogcapi-tiles-1/1.0/conf/png",
     "http://www.opengis.net/spec/ogcapi-tiles-1/1.0/conf/mvt",
     // "http://www.opengis.net/spec/ogcapi-tiles-1/1.0/conf/geojson",
     // "http://www.opengis.net/spec/ogcapi-tiles-1/1.0/conf/tiff",
@@ -40,6 +46,11 @@ const CONFORMANCE: [&str; 7] = [
 
 const WEB_MERCARTOR_QUAD: &[u8; 8005] = include_bytes!("../../assets/tms/WebMercartorQuad.json");
 
+/// Extent of the tile coordinates rendered into PNG tiles
+const EXTENT: u32 = 4096;
+/// Size of PNG tiles in pixels
+const TILE_SIZE: u32 = 256;
+
 static TMS: OnceCell<HashMap<String, TileMatrixSet>> = OnceCell::new();
 static TM: OnceCell<HashMap<String, HashMap<String, TileMatrix>>> = OnceCell::new();
 
@@ -55,8 +66,42 @@ pub struct TileParams {
     /// the MatrixWidth-1 for the selected TileMatrix.
     row: u32,
     /// Column index of the tile on the selected TileMatrix. It cannot exceed
-    /// the MatrixHeight-1 for the selected TileMatrix.
-    col: u32,
+    /// the MatrixHeight-1 for the selected TileMatrix. May be suffixed with
+    /// the extension of the tile format, `.mvt` or `.png`.
+    col: String,
+}
+
+/// Encoding of a tile
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TileFormat {
+    Mvt,
+    Png,
+}
+
+impl TileFormat {
+    /// Selects the format by path extension, falling back to the `Accept`
+    /// header and MVT
+    fn negotiate(extension: Option<&str>, headers: &HeaderMap) -> Result<Self> {
+        match extension {
+            Some("mvt" | "pbf") => Ok(TileFormat::Mvt),
+            Some("png") => Ok(TileFormat::Png),
+            Some(extension) => Err(Error::Exception(
+                StatusCode::NOT_FOUND,
+                format!("Unsupported tile format `{}`", extension),
+            )),
+            None => {
+                let accept = headers
+                    .get(ACCEPT)
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or_default();
+                if accept.contains(PNG) && !accept.contains(MVT) {
+                    Ok(TileFormat::Png)
+                } else {
+                    Ok(TileFormat::Mvt)
+                }
+            }
+        }
+    }
 }
 
 async fn tile_matrix_sets(RemoteUrl(url): RemoteUrl) -> Result<Json<TileMatrixSets>> {
@@ -71,7 +116,8 @@ async fn tile_matrix_sets(RemoteUrl(url): RemoteUrl) -> Result<Json<TileMatrixSe
             links: vec![Link::new(
                 url.join(&format!("tileMatrixSets/{}", &tms.id))?,
                 TILING_SCHEME,
-            )],
+            )
+            .mediatype(JSON)],
             ..Default::default()
         };
 
@@ -104,25 +150,91 @@ async fn tile(
     Path(params): Path<TileParams>,
     Qs(query): Qs<Query>,
     State(state): State<AppState>,
-) -> Result<Vec<u8>> {
+    headers: HeaderMap,
+) -> Result<Response> {
     let tms = TMS
         .get()
         .and_then(|tms| tms.get(&params.tms_id))
-        .expect("Get tms from TMS");
+        .ok_or_else(|| {
+            Error::Exception(
+                StatusCode::NOT_FOUND,
+                format!("Unknown tile matrix set `{}`", params.tms_id),
+            )
+        })?;
+    let matrix = TM
+        .get()
+        .and_then(|tm| tm.get(&params.tms_id))
+        .and_then(|tm| tm.get(&params.matrix))
+        .ok_or_else(|| {
+            Error::Exception(
+                StatusCode::NOT_FOUND,
+                format!("Unknown tile matrix `{}`", params.matrix),
+            )
+        })?;
+
+    let (col, extension) = match params.col.split_once('.') {
+        Some((col, extension)) => (col, Some(extension)),
+        None => (params.col.as_str(), None),
+    };
+    let col: u32 = col.parse().map_err(|_| {
+        Error::Exception(
+            StatusCode::BAD_REQUEST,
+            format!("Invalid tile column `{}`", col),
+        )
+    })?;
+    if u64::from(params.row) >= matrix.matrix_height.get()
+        || u64::from(col) >= matrix.matrix_width.get()
+    {
+        return Err(Error::Exception(
+            StatusCode::NOT_FOUND,
+            format!(
+                "Tile {}/{} is outside of tile matrix `{}`",
+                params.row, col, params.matrix
+            ),
+        ));
+    }
+    let format = TileFormat::negotiate(extension, &headers)?;
+
+    let collections = params.collection_id.or(query.collections).unwrap();
+
+    if format == TileFormat::Png {
+        let geometries = state
+            .drivers
+            .tiles
+            .tile_geometries(&collections, tms, &params.matrix, params.row, col, EXTENT)
+            .await?;
+
+        let scale = TILE_SIZE as f64 / EXTENT as f64;
+        let viewport = Viewport {
+            width: TILE_SIZE,
+            height: TILE_SIZE,
+            origin: (0.0, 0.0),
+            scale: (scale, scale),
+        };
+        let png = render::png(&geometries, &viewport, &Style::default())?;
+
+        return Ok(([(CONTENT_TYPE, PNG)], png).into_response());
+    }
+
+    // seeded tiles are only cached per collection
+    if !collections.contains(',') {
+        if let Some(tile) = state
+            .drivers
+            .tiles
+            .read_cached_tile(&collections, tms, &params.matrix, params.row, col)
+            .await?
+        {
+            return Ok(([(CONTENT_TYPE, MVT)], tile).into_response());
+        }
+    }
 
     let tiles = state
         .drivers
         .tiles
-        .tile(
-            &params.collection_id.or(query.collections).unwrap(),
-            tms,
-            &params.matrix,
-            params.row,
-            params.col,
-        )
+        .tile(&collections, tms, &params.matrix, params.row, col)
         .await?;
 
-    Ok(tiles)
+    Ok(([(CONTENT_TYPE, MVT)], tiles).into_response())
 }
 
 pub(crate) fn router(state: &AppState) -> Router<AppState> {
@@ -153,7 +265,7 @@ pub(crate) fn router(state: &AppState) -> Router<AppState> {
     TMS.set(tms_map).expect("set `TMS` once cell content");
     TM.set(tm).expect("set `TM` once cell content");
 
-    Router::new()
+    let router = Router::new()
         .route("/tileMatrixSets", get(tile_matrix_sets))
         .route("/tileMatrixSets/:tms_id", get(tile_matrix_set))
         .route("/tiles", get(tiles))
@@ -163,5 +275,7 @@ pub(crate) fn router(state: &AppState) -> Router<AppState> {
         .route(
             "/collections/:collection_id/tiles/:tms_id/:matrix/:row/:col",
             get(tile),
-        )
+        );
+
+    middleware::route_timeout(router, state.settings.tiles_timeout)
 }
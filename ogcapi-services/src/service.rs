@@ -1,32 +1,39 @@
 use std::{
     any::Any,
+    future::Future,
+    io,
     net::{SocketAddr, TcpListener},
+    time::Duration,
 };
 
 use axum::{
     body::Body,
+    extract::DefaultBodyLimit,
     http::{
         header::{AUTHORIZATION, CONTENT_TYPE, COOKIE, PROXY_AUTHORIZATION, SET_COOKIE},
-        Response, StatusCode,
+        HeaderName, Response, StatusCode,
     },
+    middleware::from_fn_with_state,
     response::IntoResponse,
-    routing::get,
+    routing::{get, get_service},
     Router,
 };
-use tower::ServiceBuilder;
+use tokio::time::MissedTickBehavior;
+use tower::{make::Shared, Layer, ServiceBuilder};
 use tower_http::{
     catch_panic::CatchPanicLayer,
     compression::CompressionLayer,
     cors::CorsLayer,
     request_id::MakeRequestUuid,
     sensitive_headers::SetSensitiveRequestHeadersLayer,
+    services::ServeDir,
     trace::{DefaultMakeSpan, TraceLayer},
     ServiceBuilderExt,
 };
 
-use ogcapi_types::common::Exception;
+use ogcapi_types::common::{media_type::PROBLEM_JSON, Query};
 
-use crate::{routes, AppState, Config, ConfigParser, Error};
+use crate::{error::problem, middleware, routes, AppState, Config, Error};
 
 /// OGC API Services
 pub struct Service {
@@ -38,7 +45,7 @@ pub struct Service {
 impl Service {
     pub async fn new() -> Self {
         // config
-        let config = Config::parse();
+        let config = Config::load();
 
         // state
         let state = AppState::new_from(&config).await;
@@ -47,56 +54,7 @@ impl Service {
     }
 
     pub async fn new_with(config: &Config, state: AppState) -> Self {
-        // router
-        let router = Router::new()
-            .route("/", get(routes::root))
-            .route("/api", get(routes::api::api))
-            .route("/redoc", get(routes::api::redoc))
-            .route("/swagger", get(routes::api::swagger))
-            .route("/conformance", get(routes::conformance));
-
-        let router = router.merge(routes::collections::router(&state));
-
-        #[cfg(feature = "stac")]
-        let router = router.route(
-            "/search",
-            get(routes::stac::search_get).post(routes::stac::search_post),
-        );
-
-        #[cfg(feature = "features")]
-        let router = router.merge(routes::features::router(&state));
-
-        #[cfg(feature = "edr")]
-        let router = router.merge(routes::edr::router(&state));
-
-        #[cfg(feature = "styles")]
-        let router = router.merge(routes::styles::router(&state));
-
-        #[cfg(feature = "tiles")]
-        let router = router.merge(routes::tiles::router(&state));
-
-        #[cfg(feature = "processes")]
-        let router = router.merge(routes::processes::router(&state));
-
-        // add a fallback service for handling routes to unknown paths
-        let router = router.fallback(handler_404);
-
-        // middleware stack
-        let router = router.layer(
-            ServiceBuilder::new()
-                .set_x_request_id(MakeRequestUuid)
-                .layer(SetSensitiveRequestHeadersLayer::new([
-                    AUTHORIZATION,
-                    PROXY_AUTHORIZATION,
-                    COOKIE,
-                    SET_COOKIE,
-                ]))
-                .layer(TraceLayer::new_for_http().make_span_with(DefaultMakeSpan::new()))
-                .layer(CompressionLayer::new())
-                .layer(CorsLayer::permissive())
-                .layer(CatchPanicLayer::custom(handle_panic))
-                .propagate_x_request_id(),
-        );
+        let router = app(&state);
 
         let listener =
             TcpListener::bind((config.host.as_str(), config.port)).expect("create listener");
@@ -110,9 +68,22 @@ impl Service {
 
     /// Serve application
     pub async fn serve(self) {
+        // keep the collection extents fresh in the background
+        let refresh = self.state.settings.extent_refresh_interval.map(|interval| {
+            tokio::spawn(refresh_extents(
+                self.state.clone(),
+                Duration::from_secs(interval),
+                shutdown_signal(),
+            ))
+        });
+
         // add state
+        let settings = self.state.settings.clone();
         let router = self.router.with_state(self.state);
 
+        // normalize paths before routing
+        let app = from_fn_with_state(settings, middleware::normalize_path).layer(router);
+
         // serve
         tracing::info!(
             "listening on http://{}",
@@ -121,10 +92,15 @@ impl Service {
 
         let builder = axum::Server::from_tcp(self.listener).unwrap();
         builder
-            .serve(router.into_make_service())
+            .serve(Shared::new(app))
             .with_graceful_shutdown(shutdown_signal())
             .await
-            .unwrap()
+            .unwrap();
+
+        // let a running refresh complete
+        if let Some(refresh) = refresh {
+            refresh.await.ok();
+        }
     }
 
     // helper function to get randomized port
@@ -134,13 +110,137 @@ impl Service {
     }
 }
 
+/// Router of all enabled APIs with the middleware stack, the state still
+/// needs to be provided with [`Router::with_state`]
+pub fn app(state: &AppState) -> Router<AppState> {
+    let router = Router::new()
+        .route("/", get(routes::root))
+        .route("/api", get(routes::api::api))
+        .route("/api.yaml", get(routes::api::api_yaml))
+        .route("/redoc", get(routes::api::redoc))
+        .route("/swagger", get(routes::api::swagger))
+        .route("/conformance", get(routes::conformance))
+        .route("/capabilities", get(routes::capabilities::capabilities));
+
+    // self-hosted files of the API documentation, the configured directory
+    // takes precedence over the embedded ones
+    let router = match &state.settings.api_docs_assets {
+        Some(dir) => router.nest_service(
+            routes::api::ASSETS,
+            get_service(ServeDir::new(dir))
+                .handle_error(|e: io::Error| async move { Error::Anyhow(e.into()) }),
+        ),
+        #[cfg(feature = "docs-assets")]
+        None => router.route(
+            &format!("{}/:file", routes::api::ASSETS),
+            get(routes::api::asset),
+        ),
+        #[cfg(not(feature = "docs-assets"))]
+        None => router,
+    };
+
+    let router = router.merge(routes::collections::router(state));
+
+    #[cfg(feature = "stac")]
+    let router = router.route(
+        "/search",
+        get(routes::stac::search_get)
+            .post(routes::stac::search_post)
+            .route_layer(axum::middleware::from_fn(middleware::json_content_type))
+            .route_layer(from_fn_with_state(
+                state.settings.max_body_size,
+                middleware::decompress,
+            )),
+    );
+
+    #[cfg(feature = "features")]
+    let router = router.merge(routes::features::router(state));
+
+    #[cfg(feature = "records")]
+    let router = router.merge(routes::records::router(state));
+
+    #[cfg(feature = "edr")]
+    let router = router.merge(routes::edr::router(state));
+
+    #[cfg(feature = "styles")]
+    let router = router.merge(routes::styles::router(state));
+
+    #[cfg(feature = "tiles")]
+    let router = router.merge(routes::tiles::router(state));
+
+    #[cfg(feature = "maps")]
+    let router = router.merge(routes::maps::router(state));
+
+    #[cfg(feature = "processes")]
+    let router = router.merge(routes::processes::router(state));
+
+    // add a fallback service for handling routes to unknown paths
+    let router = router.fallback(handler_404);
+
+    // size of the bodies read by extractors
+    let router = router.layer(DefaultBodyLimit::max(state.settings.max_body_size));
+
+    // stable key order, only added if enabled as it buffers responses
+    let router = if state.settings.sort_json_keys {
+        router.layer(axum::middleware::from_fn(middleware::sort_json_keys))
+    } else {
+        router
+    };
+
+    // timing, measured within the handling task
+    let router = if state.settings.server_timing {
+        router.layer(axum::middleware::from_fn(middleware::server_timing))
+    } else {
+        router
+    };
+
+    // authentication
+    let router = authenticate(router, state);
+
+    // problem types, covering authentication errors as well
+    let router = match &state.settings.problem_type_base {
+        Some(base) => router.layer(from_fn_with_state(base.clone(), middleware::problem_type)),
+        None => router,
+    };
+
+    let problem_type_base = state.settings.problem_type_base.clone();
+
+    // middleware stack
+    router.layer(
+        ServiceBuilder::new()
+            .set_x_request_id(MakeRequestUuid)
+            .layer(SetSensitiveRequestHeadersLayer::new([
+                AUTHORIZATION,
+                PROXY_AUTHORIZATION,
+                COOKIE,
+                SET_COOKIE,
+                HeaderName::from_static("x-api-key"),
+            ]))
+            .layer(TraceLayer::new_for_http().make_span_with(DefaultMakeSpan::new()))
+            .layer(CompressionLayer::new())
+            .layer(CorsLayer::permissive())
+            .layer(CatchPanicLayer::custom(move |err| {
+                handle_panic(err, problem_type_base.as_ref())
+            }))
+            .propagate_x_request_id(),
+    )
+}
+
+/// Adds the authentication by API key
+pub(crate) fn authenticate(router: Router<AppState>, state: &AppState) -> Router<AppState> {
+    router.layer(from_fn_with_state(
+        state.settings.clone(),
+        middleware::api_key,
+    ))
+}
+
 /// Custom 404 handler
 async fn handler_404() -> impl IntoResponse {
     Error::NotFound
 }
 
 /// Custom panic handler
-fn handle_panic(err: Box<dyn Any + Send + 'static>) -> Response<Body> {
+fn handle_panic(err: Box<dyn Any + Send + 'static>, base: Option<&url::Url>) -> Response<Body> {
     let details = if let Some(s) = err.downcast_ref::<String>() {
         s.clone()
     } else if let Some(s) = err.downcast_ref::<&str>() {
@@ -149,18 +249,60 @@ fn handle_panic(err: Box<dyn Any + Send + 'static>) -> Response<Body> {
         "Unknown panic message".to_string()
     };
 
-    let body =
-        Exception::new_from_status(StatusCode::INTERNAL_SERVER_ERROR.as_u16()).detail(details);
+    let body = problem(StatusCode::INTERNAL_SERVER_ERROR, base).detail(details);
 
     let body = serde_json::to_string(&body).unwrap();
 
     Response::builder()
         .status(StatusCode::INTERNAL_SERVER_ERROR)
-        .header(CONTENT_TYPE, "application/json")
+        .header(CONTENT_TYPE, PROBLEM_JSON)
         .body(Body::from(body))
         .unwrap()
 }
 
+/// Periodically recomputes the extents of all collections until shutdown
+async fn refresh_extents(state: AppState, period: Duration, shutdown: impl Future<Output = ()>) {
+    let query = Query {
+        bbox: None,
+        bbox_crs: None,
+        datetime: None,
+        limit: None,
+        offset: None,
+        f: None,
+    };
+
+    let mut interval = tokio::time::interval(period);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    tokio::pin!(shutdown);
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            _ = &mut shutdown => return,
+        }
+
+        let collections = match state.drivers.collections.list_collections(&query).await {
+            Ok(collections) => collections.collections,
+            Err(e) => {
+                tracing::warn!("Unable to list collections for the extent refresh: {}", e);
+                continue;
+            }
+        };
+
+        for collection in collections {
+            if let Err(e) = state
+                .drivers
+                .collections
+                .refresh_extent(&collection.id)
+                .await
+            {
+                tracing::warn!("Unable to refresh the extent of `{}`: {}", collection.id, e);
+            }
+        }
+    }
+}
+
 /// Handle shutdown signals
 async fn shutdown_signal() {
     let ctrl_c = async {
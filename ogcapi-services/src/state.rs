@@ -11,12 +11,15 @@ use ogcapi_drivers::StyleTransactions;
 #[cfg(feature = "tiles")]
 use ogcapi_drivers::TileTransactions;
 
-use ogcapi_drivers::{postgres::Db, CollectionTransactions};
+use ogcapi_drivers::{
+    postgres::{Db, DbOptions},
+    CollectionTransactions,
+};
 use ogcapi_types::common::{Conformance, LandingPage};
 
 #[cfg(feature = "processes")]
 use crate::Processor;
-use crate::{openapi::OPENAPI, Config, ConfigParser, OpenAPI};
+use crate::{openapi::OPENAPI, Config, OpenAPI, Settings};
 
 /// Application state
 #[derive(Clone)]
@@ -24,12 +27,19 @@ pub struct AppState {
     pub root: Arc<RwLock<LandingPage>>,
     pub conformance: Arc<RwLock<Conformance>>,
     pub openapi: OpenAPI,
+    pub settings: Arc<Settings>,
     pub drivers: Arc<Drivers>,
     pub db: Db,
     #[cfg(feature = "stac")]
     pub s3: ogcapi_drivers::s3::S3,
     #[cfg(feature = "processes")]
     pub processors: Arc<RwLock<std::collections::HashMap<String, Box<dyn Processor>>>>,
+    /// Execution slots of processes with limited concurrency
+    #[cfg(feature = "processes")]
+    pub process_limits: Arc<RwLock<std::collections::HashMap<String, Arc<tokio::sync::Semaphore>>>>,
+    /// Status updates of running jobs
+    #[cfg(feature = "processes")]
+    pub job_events: tokio::sync::broadcast::Sender<ogcapi_types::processes::StatusInfo>,
 }
 
 // TODO: Introduce service trait
@@ -47,9 +57,27 @@ pub struct Drivers {
     pub tiles: Box<dyn TileTransactions>,
 }
 
+impl From<Db> for Drivers {
+    fn from(db: Db) -> Self {
+        Drivers {
+            collections: Box::new(db.clone()),
+            #[cfg(feature = "features")]
+            features: Box::new(db.clone()),
+            #[cfg(feature = "edr")]
+            edr: Box::new(db.clone()),
+            #[cfg(feature = "processes")]
+            jobs: Box::new(db.clone()),
+            #[cfg(feature = "styles")]
+            styles: Box::new(db.clone()),
+            #[cfg(feature = "tiles")]
+            tiles: Box::new(db.clone()),
+        }
+    }
+}
+
 impl AppState {
     pub async fn new() -> Self {
-        let config = Config::parse();
+        let config = Config::load();
         AppState::new_from(&config).await
     }
 
@@ -60,9 +88,21 @@ impl AppState {
             OpenAPI::from_slice(OPENAPI)
         };
 
-        let db = Db::setup(&config.database_url).await.unwrap();
+        let options = DbOptions {
+            schema: config.db_schema.clone(),
+            max_connections: config.db_max_connections,
+            statement_timeout: config.settings.statement_timeout(),
+            log_statements: config.db_log_statements,
+            read_attempts: config.db_read_attempts,
+            ..Default::default()
+        };
+        let db = Db::setup_with(&config.database_url, &options)
+            .await
+            .unwrap();
 
-        AppState::new_with(db, openapi).await
+        AppState::new_with(db, openapi)
+            .await
+            .settings(config.settings.clone())
     }
 
     pub async fn new_with(db: Db, openapi: OpenAPI) -> Self {
@@ -79,30 +119,23 @@ impl AppState {
         ]);
 
         // drivers
-        let drivers = Drivers {
-            collections: Box::new(db.clone()),
-            #[cfg(feature = "features")]
-            features: Box::new(db.clone()),
-            #[cfg(feature = "edr")]
-            edr: Box::new(db.clone()),
-            #[cfg(feature = "processes")]
-            jobs: Box::new(db.clone()),
-            #[cfg(feature = "styles")]
-            styles: Box::new(db.clone()),
-            #[cfg(feature = "tiles")]
-            tiles: Box::new(db.clone()),
-        };
+        let drivers = Drivers::from(db.clone());
 
         AppState {
             root: Arc::new(RwLock::new(LandingPage::new("root").description("root"))),
             conformance: Arc::new(RwLock::new(conformace)),
             openapi,
+            settings: Default::default(),
             drivers: Arc::new(drivers),
             db,
             #[cfg(feature = "stac")]
             s3: ogcapi_drivers::s3::S3::new().await,
             #[cfg(feature = "processes")]
             processors: Default::default(),
+            #[cfg(feature = "processes")]
+            process_limits: Default::default(),
+            #[cfg(feature = "processes")]
+            job_events: tokio::sync::broadcast::channel(64).0,
         }
     }
 
@@ -116,6 +149,17 @@ impl AppState {
         self
     }
 
+    pub fn settings(mut self, settings: Settings) -> Self {
+        self.settings = Arc::new(settings);
+        self
+    }
+
+    /// Replaces the drivers, e.g. with mocks in tests
+    pub fn drivers(mut self, drivers: Drivers) -> Self {
+        self.drivers = Arc::new(drivers);
+        self
+    }
+
     #[cfg(feature = "stac")]
     pub async fn s3_client(mut self, client: ogcapi_drivers::s3::S3) -> Self {
         self.s3 = client;
@@ -125,6 +169,11 @@ impl AppState {
     #[cfg(feature = "processes")]
     pub fn processors(self, processors: Vec<Box<dyn Processor>>) -> Self {
         for p in processors {
+            let mut limits = self.process_limits.write().unwrap();
+            match p.concurrency() {
+                Some(n) => limits.insert(p.id(), Arc::new(tokio::sync::Semaphore::new(n))),
+                None => limits.remove(&p.id()),
+            };
             self.processors.write().unwrap().insert(p.id(), p);
         }
         self
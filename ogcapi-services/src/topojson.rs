@@ -0,0 +1,219 @@
+//! TopoJSON encoding of feature collections
+//!
+//! Lines and polygon rings are cut into arcs at junctions, points where
+//! paths meet or diverge, so that shared boundaries are stored only once.
+//! Coordinates are not quantized.
+
+use std::collections::{HashMap, HashSet};
+
+use geojson::{Geometry, Value as GeometryValue};
+use serde_json::{json, Map, Value};
+
+use ogcapi_types::features::Feature;
+
+type Position = Vec<f64>;
+
+/// Hashable key of a position, ignoring any coordinates past the second
+type Key = (u64, u64);
+
+fn key(position: &Position) -> Key {
+    (
+        position.first().copied().unwrap_or_default().to_bits(),
+        position.get(1).copied().unwrap_or_default().to_bits(),
+    )
+}
+
+/// Encodes features as a TopoJSON topology with a single object `name`
+pub(crate) fn encode(name: &str, features: &[Feature]) -> Value {
+    let mut topology = Topology::default();
+
+    for feature in features {
+        topology.collect(&feature.geometry);
+    }
+
+    let geometries: Vec<Value> = features
+        .iter()
+        .map(|feature| {
+            let mut object = topology.object(&feature.geometry);
+            if let Some(id) = &feature.id {
+                object.insert("id".to_string(), json!(id));
+            }
+            if let Some(properties) = &feature.properties {
+                object.insert("properties".to_string(), json!(properties));
+            }
+            Value::Object(object)
+        })
+        .collect();
+
+    json!({
+        "type": "Topology",
+        "objects": {
+            name: {
+                "type": "GeometryCollection",
+                "geometries": geometries
+            }
+        },
+        "arcs": topology.arcs
+    })
+}
+
+#[derive(Default)]
+struct Topology {
+    /// Neighbours of every position over all paths
+    neighbours: HashMap<Key, HashSet<Key>>,
+    /// Endpoints of lines
+    endpoints: HashSet<Key>,
+    arcs: Vec<Vec<Position>>,
+    /// Index of every arc by its positions
+    index: HashMap<Vec<Key>, usize>,
+}
+
+impl Topology {
+    /// Records the neighbourhood of all positions of a geometry
+    fn collect(&mut self, geometry: &Geometry) {
+        match &geometry.value {
+            GeometryValue::Point(_) | GeometryValue::MultiPoint(_) => {}
+            GeometryValue::LineString(line) => self.collect_line(line),
+            GeometryValue::MultiLineString(lines) => {
+                lines.iter().for_each(|line| self.collect_line(line))
+            }
+            GeometryValue::Polygon(rings) => rings.iter().for_each(|ring| self.collect_ring(ring)),
+            GeometryValue::MultiPolygon(polygons) => polygons
+                .iter()
+                .flatten()
+                .for_each(|ring| self.collect_ring(ring)),
+            GeometryValue::GeometryCollection(geometries) => geometries
+                .iter()
+                .for_each(|geometry| self.collect(geometry)),
+        }
+    }
+
+    fn collect_line(&mut self, line: &[Position]) {
+        if let (Some(first), Some(last)) = (line.first(), line.last()) {
+            self.endpoints.insert(key(first));
+            self.endpoints.insert(key(last));
+        }
+        for pair in line.windows(2) {
+            self.connect(&pair[0], &pair[1]);
+        }
+    }
+
+    fn collect_ring(&mut self, ring: &[Position]) {
+        // the closing position repeats the first one
+        for pair in ring.windows(2) {
+            self.connect(&pair[0], &pair[1]);
+        }
+    }
+
+    fn connect(&mut self, a: &Position, b: &Position) {
+        let (a, b) = (key(a), key(b));
+        if a != b {
+            self.neighbours.entry(a).or_default().insert(b);
+            self.neighbours.entry(b).or_default().insert(a);
+        }
+    }
+
+    fn is_junction(&self, position: &Position) -> bool {
+        let key = key(position);
+        self.endpoints.contains(&key) || self.neighbours.get(&key).map_or(false, |n| n.len() > 2)
+    }
+
+    /// TopoJSON geometry object referencing the arcs of a geometry
+    fn object(&mut self, geometry: &Geometry) -> Map<String, Value> {
+        let mut object = Map::new();
+
+        let (r#type, member, value) = match &geometry.value {
+            GeometryValue::Point(point) => ("Point", "coordinates", json!(point)),
+            GeometryValue::MultiPoint(points) => ("MultiPoint", "coordinates", json!(points)),
+            GeometryValue::LineString(line) => ("LineString", "arcs", json!(self.line(line))),
+            GeometryValue::MultiLineString(lines) => {
+                let arcs: Vec<Vec<i64>> = lines.iter().map(|line| self.line(line)).collect();
+                ("MultiLineString", "arcs", json!(arcs))
+            }
+            GeometryValue::Polygon(rings) => ("Polygon", "arcs", json!(self.polygon(rings))),
+            GeometryValue::MultiPolygon(polygons) => {
+                let arcs: Vec<Vec<Vec<i64>>> =
+                    polygons.iter().map(|rings| self.polygon(rings)).collect();
+                ("MultiPolygon", "arcs", json!(arcs))
+            }
+            GeometryValue::GeometryCollection(geometries) => {
+                let geometries: Vec<Value> = geometries
+                    .iter()
+                    .map(|geometry| Value::Object(self.object(geometry)))
+                    .collect();
+                ("GeometryCollection", "geometries", json!(geometries))
+            }
+        };
+
+        object.insert("type".to_string(), json!(r#type));
+        object.insert(member.to_string(), value);
+        object
+    }
+
+    fn polygon(&mut self, rings: &[Vec<Position>]) -> Vec<Vec<i64>> {
+        rings.iter().map(|ring| self.ring(ring)).collect()
+    }
+
+    /// Arcs of a line, cut at its junctions
+    fn line(&mut self, line: &[Position]) -> Vec<i64> {
+        let mut arcs = Vec::new();
+        let mut start = 0;
+
+        for i in 1..line.len() {
+            if i == line.len() - 1 || self.is_junction(&line[i]) {
+                arcs.push(self.arc(&line[start..=i]));
+                start = i;
+            }
+        }
+
+        arcs
+    }
+
+    /// Arcs of a ring, starting at a junction if it has any
+    fn ring(&mut self, ring: &[Position]) -> Vec<i64> {
+        if ring.len() < 2 {
+            return Vec::new();
+        }
+
+        // open the ring and rotate it to a stable starting position
+        let open = &ring[..ring.len() - 1];
+        let start = match open.iter().position(|p| self.is_junction(p)) {
+            Some(start) => start,
+            None => (0..open.len())
+                .min_by(|&a, &b| {
+                    open[a]
+                        .partial_cmp(&open[b])
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .unwrap_or_default(),
+        };
+
+        let mut rotated: Vec<Position> = open[start..]
+            .iter()
+            .chain(open[..start].iter())
+            .cloned()
+            .collect();
+        rotated.push(rotated[0].clone());
+
+        self.line(&rotated)
+    }
+
+    /// Index of an arc, negative one's complement if the arc is reversed
+    fn arc(&mut self, positions: &[Position]) -> i64 {
+        let keys: Vec<Key> = positions.iter().map(key).collect();
+
+        if let Some(index) = self.index.get(&keys) {
+            return *index as i64;
+        }
+
+        let reversed: Vec<Key> = keys.iter().rev().copied().collect();
+        if let Some(index) = self.index.get(&reversed) {
+            return !(*index as i64);
+        }
+
+        let index = self.arcs.len();
+        self.arcs.push(positions.to_vec());
+        self.index.insert(keys, index);
+        index as i64
+    }
+}
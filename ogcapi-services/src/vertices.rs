@@ -0,0 +1,62 @@
+//! Limits on the size of client supplied filter geometries
+
+use axum::http::StatusCode;
+use geojson::{Geometry, Value};
+
+use ogcapi_types::features::FilterLang;
+
+use crate::{Error, Result};
+
+/// Counts the positions of a GeoJSON geometry
+pub(crate) fn count(geometry: &Geometry) -> usize {
+    match &geometry.value {
+        Value::Point(_) => 1,
+        Value::MultiPoint(l) | Value::LineString(l) => l.len(),
+        Value::MultiLineString(p) | Value::Polygon(p) => p.iter().map(Vec::len).sum(),
+        Value::MultiPolygon(mp) => mp.iter().flatten().map(Vec::len).sum(),
+        Value::GeometryCollection(gc) => gc.iter().map(count).sum(),
+    }
+}
+
+/// Counts the positions of a WKT geometry
+pub(crate) fn count_wkt(wkt: &str) -> usize {
+    wkt.split(['(', ')', ','])
+        .filter(|position| position.chars().any(|c| c.is_ascii_digit()))
+        .count()
+}
+
+/// Counts the positions of the geometries of a CQL2 filter, in the text
+/// encoding any list of numbers is counted
+pub(crate) fn count_filter(filter: &str, lang: &FilterLang) -> usize {
+    match lang {
+        FilterLang::CqlText => count_wkt(filter),
+        FilterLang::CqlJson => serde_json::from_str(filter)
+            .map(|value| count_json(&value))
+            .unwrap_or_default(),
+    }
+}
+
+/// Counts the arrays of numbers nested in a JSON value
+fn count_json(value: &serde_json::Value) -> usize {
+    match value {
+        serde_json::Value::Array(values)
+            if !values.is_empty() && values.iter().all(|v| v.is_number()) =>
+        {
+            1
+        }
+        serde_json::Value::Array(values) => values.iter().map(count_json).sum(),
+        serde_json::Value::Object(object) => object.values().map(count_json).sum(),
+        _ => 0,
+    }
+}
+
+/// Rejects filter geometries with more than `max` vertices
+pub(crate) fn check(parameter: &str, count: usize, max: usize) -> Result<()> {
+    if count > max {
+        return Err(Error::Exception(
+            StatusCode::BAD_REQUEST,
+            format!("`{parameter}` has {count} vertices, at most {max} are allowed"),
+        ));
+    }
+    Ok(())
+}
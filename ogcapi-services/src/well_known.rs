@@ -0,0 +1,148 @@
+//! Well-known text (WKT) and well-known binary (WKB) encodings of geometries
+
+use geojson::{Geometry, Value};
+
+type Position = Vec<f64>;
+
+/// Encodes a geometry as WKT, like `POINT(7 46)`
+pub(crate) fn to_wkt(geometry: &Geometry) -> String {
+    wkt(&geometry.value)
+}
+
+fn wkt(value: &Value) -> String {
+    let (name, body) = match value {
+        Value::Point(p) => ("POINT", wkt_positions(std::slice::from_ref(p))),
+        Value::MultiPoint(ps) => ("MULTIPOINT", wkt_positions(ps)),
+        Value::LineString(ps) => ("LINESTRING", wkt_positions(ps)),
+        Value::MultiLineString(ls) => ("MULTILINESTRING", wkt_lists(ls)),
+        Value::Polygon(rs) => ("POLYGON", wkt_lists(rs)),
+        Value::MultiPolygon(ps) => (
+            "MULTIPOLYGON",
+            ps.iter()
+                .map(|rs| format!("({})", wkt_lists(rs)))
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+        Value::GeometryCollection(gs) => (
+            "GEOMETRYCOLLECTION",
+            gs.iter()
+                .map(|g| wkt(&g.value))
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+    };
+
+    let z = if first_position(value).map_or(false, |p| p.len() > 2) {
+        " Z "
+    } else {
+        ""
+    };
+
+    if body.is_empty() {
+        format!("{name} EMPTY")
+    } else {
+        format!("{name}{z}({body})")
+    }
+}
+
+fn wkt_positions(positions: &[Position]) -> String {
+    positions
+        .iter()
+        .map(|p| {
+            p.iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn wkt_lists(lists: &[Vec<Position>]) -> String {
+    lists
+        .iter()
+        .map(|ps| format!("({})", wkt_positions(ps)))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn first_position(value: &Value) -> Option<&Position> {
+    match value {
+        Value::Point(p) => Some(p),
+        Value::MultiPoint(ps) | Value::LineString(ps) => ps.first(),
+        Value::MultiLineString(ls) | Value::Polygon(ls) => ls.iter().flatten().next(),
+        Value::MultiPolygon(ps) => ps.iter().flatten().flatten().next(),
+        Value::GeometryCollection(_) => None,
+    }
+}
+
+/// Encodes a geometry as little endian ISO WKB in hexadecimal
+pub(crate) fn to_wkb_hex(geometry: &Geometry) -> String {
+    let mut bytes = Vec::new();
+    wkb(&geometry.value, &mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn wkb(value: &Value, bytes: &mut Vec<u8>) {
+    let dimensions = first_position(value).map_or(2, |p| p.len().clamp(2, 3));
+
+    let r#type: u32 = match value {
+        Value::Point(_) => 1,
+        Value::LineString(_) => 2,
+        Value::Polygon(_) => 3,
+        Value::MultiPoint(_) => 4,
+        Value::MultiLineString(_) => 5,
+        Value::MultiPolygon(_) => 6,
+        Value::GeometryCollection(_) => 7,
+    };
+    let r#type = if dimensions == 3 {
+        r#type + 1000
+    } else {
+        r#type
+    };
+
+    // little endian
+    bytes.push(1);
+    bytes.extend(r#type.to_le_bytes());
+
+    let position = |p: &Position, bytes: &mut Vec<u8>| {
+        for i in 0..dimensions {
+            bytes.extend(p.get(i).copied().unwrap_or(f64::NAN).to_le_bytes());
+        }
+    };
+    let count = |n: usize, bytes: &mut Vec<u8>| bytes.extend((n as u32).to_le_bytes());
+
+    match value {
+        Value::Point(p) => position(p, bytes),
+        Value::LineString(ps) => {
+            count(ps.len(), bytes);
+            ps.iter().for_each(|p| position(p, bytes));
+        }
+        Value::Polygon(rs) => {
+            count(rs.len(), bytes);
+            for r in rs {
+                count(r.len(), bytes);
+                r.iter().for_each(|p| position(p, bytes));
+            }
+        }
+        Value::MultiPoint(ps) => {
+            count(ps.len(), bytes);
+            ps.iter()
+                .for_each(|p| wkb(&Value::Point(p.to_owned()), bytes));
+        }
+        Value::MultiLineString(ls) => {
+            count(ls.len(), bytes);
+            ls.iter()
+                .for_each(|l| wkb(&Value::LineString(l.to_owned()), bytes));
+        }
+        Value::MultiPolygon(ps) => {
+            count(ps.len(), bytes);
+            ps.iter()
+                .for_each(|rs| wkb(&Value::Polygon(rs.to_owned()), bytes));
+        }
+        Value::GeometryCollection(gs) => {
+            count(gs.len(), bytes);
+            gs.iter().for_each(|g| wkb(&g.value, bytes));
+        }
+    }
+}
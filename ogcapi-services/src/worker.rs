@@ -0,0 +1,304 @@
+use std::{future::Future, time::Duration};
+
+use serde::Serialize;
+use serde_json::Value;
+use tokio::{
+    sync::{broadcast::error::RecvError, OwnedSemaphorePermit},
+    task::JoinSet,
+};
+
+use ogcapi_types::processes::{Execute, Results, StatusCode, StatusInfo, Subscriber};
+
+use crate::{AppState, Error, Processor, Result};
+
+/// Number of attempts to deliver a callback
+const CALLBACK_ATTEMPTS: u32 = 5;
+
+/// Delay before the first retry of a callback, doubled on every attempt
+const CALLBACK_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Registers a job for the execution of a process and runs it in the background
+pub(crate) async fn spawn(
+    state: &AppState,
+    processor: Box<dyn Processor>,
+    mut execute: Execute,
+) -> Result<StatusInfo> {
+    let mut job = StatusInfo {
+        process_id: Some(processor.id()),
+        job_id: uuid::Uuid::new_v4().to_string(),
+        status: StatusCode::Accepted,
+        ..Default::default()
+    };
+
+    state.drivers.jobs.register(&job).await?;
+
+    let status = state
+        .drivers
+        .jobs
+        .status(&job.job_id)
+        .await?
+        .expect("job is registered");
+
+    let subscriber = execute.subscriber.take();
+    let job_id = job.job_id.clone();
+    let dismissal = dismissal(state, job_id.clone());
+    let state = state.clone();
+
+    tokio::spawn(async move {
+        // dropping the execution on dismissal cancels it
+        let result = tokio::select! {
+            result = run(&state, processor, execute, &mut job, subscriber) => result,
+            _ = dismissal => {
+                tracing::debug!("Job `{}` dismissed", job_id);
+                Ok(())
+            }
+        };
+        if let Err(e) = result {
+            tracing::error!("Job `{}` failed to update: {}", job_id, e);
+        }
+    });
+
+    Ok(status)
+}
+
+/// Waits for a free execution slot of a process with limited concurrency
+pub(crate) async fn permit(state: &AppState, process_id: &str) -> Option<OwnedSemaphorePermit> {
+    let limit = state
+        .process_limits
+        .read()
+        .unwrap()
+        .get(process_id)
+        .cloned();
+    match limit {
+        Some(slots) => slots.acquire_owned().await.ok(),
+        None => None,
+    }
+}
+
+/// Resolves once the job with the given id is dismissed
+fn dismissal(state: &AppState, job_id: String) -> impl Future<Output = ()> {
+    // subscribe right away to not miss an early dismissal
+    let mut events = state.job_events.subscribe();
+    let state = state.clone();
+
+    async move {
+        loop {
+            match events.recv().await {
+                Ok(info) if info.job_id == job_id && info.status == StatusCode::Dismissed => return,
+                Ok(_) => continue,
+                // the dismissal may be among the missed events
+                Err(RecvError::Lagged(_)) => match state.drivers.jobs.status(&job_id).await {
+                    Ok(Some(info)) if info.status == StatusCode::Dismissed => return,
+                    Ok(_) => continue,
+                    Err(e) => {
+                        tracing::warn!("Failed to read status of job `{}`: {}", job_id, e);
+                        continue;
+                    }
+                },
+                Err(RecvError::Closed) => std::future::pending().await,
+            }
+        }
+    }
+}
+
+async fn run(
+    state: &AppState,
+    processor: Box<dyn Processor>,
+    execute: Execute,
+    job: &mut StatusInfo,
+    subscriber: Option<Subscriber>,
+) -> anyhow::Result<()> {
+    // queued as accepted until a slot is free
+    let _permit = permit(state, &processor.id()).await;
+
+    job.status = StatusCode::Running;
+    state.drivers.jobs.update(job).await?;
+    state.job_events.send(job.clone()).ok();
+
+    if let Some(uri) = subscriber.as_ref().and_then(|s| s.in_progress_uri.as_ref()) {
+        callback(uri, job).await;
+    }
+
+    let id = processor.id();
+    let outputs = processor.process().outputs.schema;
+    let timeout = processor.timeout();
+
+    // run as separate task to catch panics, dropping the set aborts it
+    let mut execution = JoinSet::new();
+    let task_state = state.clone();
+    execution.spawn(async move { processor.run(execute, &task_state).await });
+    let joined = async {
+        match execution.join_next().await {
+            Some(Ok(result)) => result,
+            Some(Err(e)) if e.is_panic() => Err(Error::Exception(
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Process `{id}` panicked"),
+            )),
+            _ => Err(Error::Exception(
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Process `{id}` was aborted"),
+            )),
+        }
+    };
+
+    let result = match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, joined)
+            .await
+            .unwrap_or_else(|_| {
+                Err(Error::Exception(
+                    axum::http::StatusCode::GATEWAY_TIMEOUT,
+                    format!(
+                        "Process `{id}` timed out after {} seconds",
+                        timeout.as_secs_f64()
+                    ),
+                ))
+            }),
+        None => joined.await,
+    };
+    let result = result.and_then(|results| {
+        validate_outputs(&results, &outputs)
+            .map_err(|e| Error::Exception(axum::http::StatusCode::INTERNAL_SERVER_ERROR, e))?;
+        Ok(results)
+    });
+
+    match result {
+        Ok(results) => {
+            job.status = StatusCode::Successful;
+            state.drivers.jobs.finish(job, Some(&results)).await?;
+            state.job_events.send(job.clone()).ok();
+
+            if let Some(subscriber) = subscriber.as_ref() {
+                callback(&subscriber.success_uri, &results).await;
+            }
+        }
+        Err(e) => {
+            job.status = StatusCode::Failed;
+            job.message = Some(match e {
+                Error::Exception(_, message) => message,
+                e => e.to_string(),
+            });
+            state.drivers.jobs.finish(job, None).await?;
+            state.job_events.send(job.clone()).ok();
+
+            if let Some(uri) = subscriber.as_ref().and_then(|s| s.failed_uri.as_ref()) {
+                callback(uri, job).await;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks the results of a job against the declared outputs schema, either
+/// describing the results object or every single output
+fn validate_outputs(results: &Results, schema: &Value) -> Result<(), String> {
+    let value = serde_json::to_value(results).map_err(|e| e.to_string())?;
+
+    if schema.get("type").and_then(Value::as_str) == Some("object") {
+        conforms(&value, schema, "")
+    } else {
+        value
+            .as_object()
+            .into_iter()
+            .flatten()
+            .try_for_each(|(output, value)| conforms(value, schema, &format!("/{output}")))
+    }
+    .map_err(|e| format!("Process returned non-conforming outputs: {e}"))
+}
+
+/// Minimal JSON Schema check covering `type`, `enum`, `const`, `required`,
+/// `properties`, `additionalProperties` and `items`
+fn conforms(value: &Value, schema: &Value, path: &str) -> Result<(), String> {
+    let at = if path.is_empty() { "/" } else { path };
+
+    if let Some(types) = schema.get("type") {
+        let matches = |t: &Value| match t.as_str() {
+            Some("null") => value.is_null(),
+            Some("boolean") => value.is_boolean(),
+            Some("integer") => value.is_i64() || value.is_u64(),
+            Some("number") => value.is_number(),
+            Some("string") => value.is_string(),
+            Some("array") => value.is_array(),
+            Some("object") => value.is_object(),
+            _ => true,
+        };
+        let valid = match types {
+            Value::Array(types) => types.iter().any(matches),
+            t => matches(t),
+        };
+        if !valid {
+            return Err(format!("expected type {types} at `{at}`, found {value}"));
+        }
+    }
+
+    if let Some(Value::Array(values)) = schema.get("enum") {
+        if !values.contains(value) {
+            return Err(format!("value {value} at `{at}` is not one of {values:?}"));
+        }
+    }
+
+    if let Some(constant) = schema.get("const") {
+        if constant != value {
+            return Err(format!("expected {constant} at `{at}`, found {value}"));
+        }
+    }
+
+    if let Some(object) = value.as_object() {
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for key in required.iter().filter_map(Value::as_str) {
+                if !object.contains_key(key) {
+                    return Err(format!("missing required `{key}` at `{at}`"));
+                }
+            }
+        }
+
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for (key, value) in object {
+            let path = format!("{path}/{key}");
+            match properties.and_then(|p| p.get(key)) {
+                Some(schema) => conforms(value, schema, &path)?,
+                None => match schema.get("additionalProperties") {
+                    Some(Value::Bool(false)) => {
+                        return Err(format!("unexpected `{key}` at `{at}`"))
+                    }
+                    Some(schema) => conforms(value, schema, &path)?,
+                    None => {}
+                },
+            }
+        }
+    }
+
+    if let (Some(items), Value::Array(values)) = (schema.get("items"), value) {
+        for (i, value) in values.iter().enumerate() {
+            conforms(value, items, &format!("{path}/{i}"))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Posts a notification to a subscriber, retrying with exponential backoff
+async fn callback(uri: &str, body: &impl Serialize) {
+    let client = reqwest::Client::new();
+    let mut backoff = CALLBACK_BACKOFF;
+
+    for attempt in 1..=CALLBACK_ATTEMPTS {
+        let response = client
+            .post(uri)
+            .json(body)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status());
+
+        match response {
+            Ok(_) => return,
+            Err(e) => {
+                tracing::warn!("Callback to `{uri}` failed (attempt {attempt}): {e}");
+                if attempt < CALLBACK_ATTEMPTS {
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                }
+            }
+        }
+    }
+}
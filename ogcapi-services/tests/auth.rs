@@ -0,0 +1,131 @@
+mod setup;
+
+use axum::http::{Method, Request};
+use hyper::{Body, Client};
+use serde_json::json;
+
+use ogcapi_services::{ApiKey, Permission, Settings};
+use ogcapi_types::common::{Access, Collection};
+
+#[tokio::test]
+async fn api_key() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app_with(|state| {
+        state.settings(Settings {
+            api_keys: vec![ApiKey {
+                key: "reader".to_string(),
+                permission: Permission::Read,
+            }],
+            ..Default::default()
+        })
+    })
+    .await?;
+    let client = Client::new();
+
+    // missing key
+    let res = client
+        .get(format!("http://{}/collections", addr).parse()?)
+        .await?;
+    assert_eq!(401, res.status());
+
+    // read-only key is accepted on GET
+    let res = client
+        .request(
+            Request::builder()
+                .uri(format!("http://{}/collections", addr))
+                .header("X-API-Key", "reader")
+                .body(Body::empty())?,
+        )
+        .await?;
+    assert_eq!(200, res.status());
+
+    // read-only key is rejected on POST
+    let res = client
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("http://{}/collections", addr))
+                .header("Content-Type", "application/json")
+                .header("X-API-Key", "reader")
+                .body(Body::from(json!({ "id": "auth", "links": [] }).to_string()))?,
+        )
+        .await?;
+    assert_eq!(403, res.status());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn collection_access() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app_with(|state| {
+        state.settings(Settings {
+            api_keys: vec![ApiKey {
+                key: "writer".to_string(),
+                permission: Permission::Write,
+            }],
+            ..Default::default()
+        })
+    })
+    .await?;
+    let client = Client::new();
+
+    for (id, access) in [("open", Access::Public), ("closed", Access::Private)] {
+        let collection = Collection {
+            id: id.to_string(),
+            access: Some(access),
+            ..Default::default()
+        };
+        let res = client
+            .request(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri(format!("http://{}/collections", addr))
+                    .header("Content-Type", "application/json")
+                    .header("X-API-Key", "writer")
+                    .body(Body::from(serde_json::to_string(&collection)?))?,
+            )
+            .await?;
+        assert_eq!(201, res.status());
+    }
+
+    // anonymous reads
+    for (id, status) in [("open", 200), ("closed", 403)] {
+        for path in ["", "/items"] {
+            let res = client
+                .get(format!("http://{}/collections/{}{}", addr, id, path).parse()?)
+                .await?;
+            assert_eq!(status, res.status());
+        }
+    }
+
+    // authenticated read
+    let res = client
+        .request(
+            Request::builder()
+                .uri(format!("http://{}/collections/closed/items", addr))
+                .header("X-API-Key", "writer")
+                .body(Body::empty())?,
+        )
+        .await?;
+    assert_eq!(200, res.status());
+
+    // anonymous writes still require a key
+    let res = client
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("http://{}/collections/open/items", addr))
+                .header("Content-Type", "application/geo+json")
+                .body(Body::from(
+                    json!({
+                        "type": "Feature",
+                        "properties": {},
+                        "geometry": { "type": "Point", "coordinates": [0.0, 0.0] }
+                    })
+                    .to_string(),
+                ))?,
+        )
+        .await?;
+    assert_eq!(401, res.status());
+
+    Ok(())
+}
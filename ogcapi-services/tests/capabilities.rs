@@ -0,0 +1,40 @@
+mod setup;
+
+use hyper::Client;
+use serde_json::Value;
+
+#[tokio::test]
+async fn capabilities() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let res = client
+        .get(format!("http://{}/capabilities", addr).parse()?)
+        .await?;
+    assert_eq!(200, res.status());
+
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let capabilities: Value = serde_json::from_slice(&body)?;
+
+    assert_eq!(capabilities["tiles"], cfg!(feature = "tiles"));
+    assert_eq!(
+        capabilities["apis"]
+            .as_array()
+            .unwrap()
+            .contains(&Value::from("tiles")),
+        cfg!(feature = "tiles")
+    );
+    assert_eq!(
+        capabilities["apis"]
+            .as_array()
+            .unwrap()
+            .contains(&Value::from("maps")),
+        cfg!(feature = "maps")
+    );
+    assert!(capabilities["crs"]
+        .as_array()
+        .unwrap()
+        .contains(&Value::from("http://www.opengis.net/def/crs/EPSG/0/3857")));
+
+    Ok(())
+}
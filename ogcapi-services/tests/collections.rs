@@ -0,0 +1,344 @@
+mod setup;
+
+use std::{collections::HashMap, net::SocketAddr};
+
+use axum::http::{Method, Request};
+use hyper::{client::HttpConnector, Body, Client};
+
+use ogcapi_services::Settings;
+use ogcapi_types::common::{media_type::JSON, Collection, Exception, Translation};
+
+async fn create_collection(
+    client: &Client<HttpConnector>,
+    addr: &SocketAddr,
+    collection: &Collection,
+) -> anyhow::Result<()> {
+    let res = client
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("http://{}/collections", addr))
+                .header("Content-Type", JSON)
+                .body(Body::from(serde_json::to_string(collection)?))?,
+        )
+        .await?;
+
+    assert_eq!(201, res.status());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn accept_language() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "lakes".to_string(),
+        title: Some("Lakes".to_string()),
+        translations: Some(HashMap::from([(
+            "de".to_string(),
+            Translation {
+                title: Some("Seen".to_string()),
+                description: None,
+            },
+        )])),
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    for (accept_language, language, title) in [
+        ("de-CH, en;q=0.5", "de", "Seen"),
+        ("fr, en;q=0.8, de;q=0.5", "en", "Lakes"),
+        ("fr", "en", "Lakes"),
+    ] {
+        let res = client
+            .request(
+                Request::builder()
+                    .uri(format!("http://{}/collections/lakes", addr))
+                    .header("Accept-Language", accept_language)
+                    .body(Body::empty())?,
+            )
+            .await?;
+
+        assert_eq!(200, res.status());
+        assert_eq!(res.headers()["Content-Language"], language);
+
+        let body = hyper::body::to_bytes(res.into_body()).await?;
+        let collection: Collection = serde_json::from_slice(&body)?;
+
+        assert_eq!(collection.title.as_deref(), Some(title));
+        assert!(collection.translations.is_none());
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn language_links() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "rivers".to_string(),
+        title: Some("Rivers".to_string()),
+        translations: Some(HashMap::from([(
+            "fr".to_string(),
+            Translation {
+                title: Some("Rivières".to_string()),
+                description: None,
+            },
+        )])),
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    let res = client
+        .get(format!("http://{}/collections/rivers", addr).parse()?)
+        .await?;
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let collection: Collection = serde_json::from_slice(&body)?;
+
+    let link = collection.links.iter().find(|l| l.rel == "self").unwrap();
+    assert_eq!(link.hreflang.as_deref(), Some("en"));
+    assert_eq!(link.r#type.as_deref(), Some(JSON));
+
+    let alternates: Vec<_> = collection
+        .links
+        .iter()
+        .filter(|l| l.rel == "alternate" && l.hreflang.is_some())
+        .collect();
+    assert_eq!(alternates.len(), 1);
+    assert_eq!(alternates[0].hreflang.as_deref(), Some("fr"));
+    assert_eq!(alternates[0].r#type.as_deref(), Some(JSON));
+
+    // the alternate link serves the translation
+    let res = client.get(alternates[0].href.parse()?).await?;
+    assert_eq!(res.headers()["Content-Language"], "fr");
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let collection: Collection = serde_json::from_slice(&body)?;
+    assert_eq!(collection.title.as_deref(), Some("Rivières"));
+
+    let alternate = collection
+        .links
+        .iter()
+        .find(|l| l.rel == "alternate" && l.hreflang.is_some())
+        .unwrap();
+    assert_eq!(alternate.hreflang.as_deref(), Some("en"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sorted_json_keys() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app_with(|state| {
+        state.settings(Settings {
+            sort_json_keys: true,
+            ..Default::default()
+        })
+    })
+    .await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "snapshot".to_string(),
+        description: Some("Snapshot".to_string()),
+        keywords: vec!["b".to_string(), "a".to_string()],
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    let mut snapshots = Vec::new();
+    for _ in 0..2 {
+        let res = client
+            .get(format!("http://{}/collections/snapshot", addr).parse()?)
+            .await?;
+        assert_eq!(200, res.status());
+
+        let body = hyper::body::to_bytes(res.into_body()).await?;
+        snapshots.push(String::from_utf8(body.to_vec())?);
+    }
+
+    let snapshot = &snapshots[0];
+    assert_eq!(snapshot, &snapshots[1]);
+
+    // object keys are sorted, array order is preserved
+    assert!(snapshot.starts_with(r#"{"crs":["#));
+    assert!(snapshot.contains(r#""description":"Snapshot","id":"snapshot","keywords":["b","a"],"#));
+    assert!(snapshot.contains(r#"{"href":"#));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn unknown_crs() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = serde_json::json!({
+        "id": "unknown-crs",
+        "links": [],
+        "crs": [
+            "http://www.opengis.net/def/crs/OGC/1.3/CRS84",
+            "http://www.opengis.net/def/crs/EPSG/0/999999"
+        ]
+    });
+
+    let res = client
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("http://{}/collections", addr))
+                .header("Content-Type", JSON)
+                .body(Body::from(collection.to_string()))?,
+        )
+        .await?;
+    assert_eq!(400, res.status());
+
+    let res = client
+        .get(format!("http://{}/collections/unknown-crs", addr).parse()?)
+        .await?;
+    assert_eq!(404, res.status());
+
+    // known crs are accepted
+    let collection = Collection {
+        id: "known-crs".to_string(),
+        crs: vec![
+            "http://www.opengis.net/def/crs/OGC/1.3/CRS84"
+                .parse()
+                .unwrap(),
+            "http://www.opengis.net/def/crs/EPSG/0/2056"
+                .parse()
+                .unwrap(),
+        ],
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn server_timing() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app_with(|state| {
+        state.settings(Settings {
+            server_timing: true,
+            ..Default::default()
+        })
+    })
+    .await?;
+    let client = Client::new();
+
+    let res = client
+        .get(format!("http://{}/collections", addr).parse()?)
+        .await?;
+    assert_eq!(200, res.status());
+
+    let header = res.headers()["Server-Timing"].to_str()?;
+
+    let mut durations = HashMap::new();
+    for metric in header.split(',') {
+        let mut params = metric.split(';').map(str::trim);
+        let name = params.next().unwrap();
+        let duration: f64 = params
+            .find_map(|p| p.strip_prefix("dur="))
+            .unwrap()
+            .parse()?;
+        durations.insert(name.to_string(), duration);
+    }
+
+    assert!(durations["db"] > 0.0);
+    assert!(durations["total"] >= durations["db"]);
+
+    // disabled by default
+    let (addr, _) = setup::spawn_app().await?;
+    let res = client
+        .get(format!("http://{}/collections", addr).parse()?)
+        .await?;
+    assert!(res.headers().get("Server-Timing").is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn problem_type() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app_with(|state| {
+        state.settings(Settings {
+            problem_type_base: Some("https://errors.example.com/ogcapi/".parse().unwrap()),
+            ..Default::default()
+        })
+    })
+    .await?;
+    let client = Client::new();
+
+    let res = client
+        .get(format!("http://{}/collections/unknown", addr).parse()?)
+        .await?;
+    assert_eq!(404, res.status());
+
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let exception: Exception = serde_json::from_slice(&body)?;
+    assert_eq!(
+        exception.r#type,
+        "https://errors.example.com/ogcapi/not-found"
+    );
+    assert_eq!(exception.status, Some(404));
+    assert_eq!(exception.title.as_deref(), Some("Not Found"));
+
+    // generic by default
+    let (addr, _) = setup::spawn_app().await?;
+    let res = client
+        .get(format!("http://{}/collections/unknown", addr).parse()?)
+        .await?;
+    assert_eq!(404, res.status());
+
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let exception: Exception = serde_json::from_slice(&body)?;
+    assert_eq!(exception.r#type, "about:blank");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn item_type() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    for (id, item_type) in [("catalog", Some("Record")), ("lakes", None)] {
+        let collection = Collection {
+            id: id.to_string(),
+            item_type: item_type.map(ToString::to_string),
+            ..Default::default()
+        };
+        create_collection(&client, &addr, &collection).await?;
+    }
+
+    for (id, item_type) in [("catalog", "record"), ("lakes", "feature")] {
+        let res = client
+            .get(format!("http://{}/collections/{}", addr, id).parse()?)
+            .await?;
+        assert_eq!(200, res.status());
+
+        let body = hyper::body::to_bytes(res.into_body()).await?;
+        let collection: serde_json::Value = serde_json::from_slice(&body)?;
+        assert_eq!(collection["itemType"], item_type);
+    }
+
+    // unknown item types are rejected
+    let res = client
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("http://{}/collections", addr))
+                .header("Content-Type", JSON)
+                .body(Body::from(serde_json::to_string(&Collection {
+                    id: "tracks".to_string(),
+                    item_type: Some("coverage".to_string()),
+                    ..Default::default()
+                })?))?,
+        )
+        .await?;
+    assert_eq!(400, res.status());
+
+    Ok(())
+}
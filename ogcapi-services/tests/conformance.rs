@@ -0,0 +1,100 @@
+mod setup;
+
+use axum::http::{Method, Request};
+use hyper::Body;
+
+use ogcapi_services::Settings;
+use ogcapi_types::common::{media_type::JSON, Collection, Conformance, Crs};
+
+const COLLECTIONS: &str = "http://www.opengis.net/spec/ogcapi-common-2/1.0/conf/collections";
+
+const CRS: &str = "http://www.opengis.net/spec/ogcapi-features-2/1.0/conf/crs";
+
+#[tokio::test]
+async fn disabled_conformance_class() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app_with(|state| {
+        state.settings(Settings {
+            disabled_conformance_classes: vec![COLLECTIONS.to_string()],
+            ..Default::default()
+        })
+    })
+    .await?;
+    let client = hyper::Client::new();
+
+    let res = client
+        .get(format!("http://{}/conformance", addr).parse()?)
+        .await?;
+    assert_eq!(200, res.status());
+
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let conformance: Conformance = serde_json::from_slice(&body)?;
+
+    assert!(!conformance.conforms_to.is_empty());
+    assert!(!conformance.conforms_to.contains(&COLLECTIONS.to_string()));
+
+    Ok(())
+}
+
+#[cfg(feature = "features")]
+#[tokio::test]
+async fn crs_conformance_class() -> anyhow::Result<()> {
+    let client = hyper::Client::new();
+
+    for (crs, advertised) in [
+        (
+            vec![Crs::default(), Crs::from_epsg(3857), Crs::from_epsg(2056)],
+            true,
+        ),
+        (vec![Crs::default()], false),
+    ] {
+        let settings = Settings {
+            crs: crs.clone(),
+            ..Default::default()
+        };
+        let (addr, _) = setup::spawn_app_with(|state| state.settings(settings)).await?;
+
+        let res = client
+            .get(format!("http://{}/conformance", addr).parse()?)
+            .await?;
+        let body = hyper::body::to_bytes(res.into_body()).await?;
+        let conformance: Conformance = serde_json::from_slice(&body)?;
+        assert_eq!(
+            conformance.conforms_to.contains(&CRS.to_string()),
+            advertised
+        );
+
+        // collections without a list of their own inline the server CRS
+        let collection = Collection {
+            id: "peaks".to_string(),
+            crs: Vec::new(),
+            ..Default::default()
+        };
+        let res = client
+            .request(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri(format!("http://{}/collections", addr))
+                    .header("Content-Type", JSON)
+                    .body(Body::from(serde_json::to_string(&collection)?))?,
+            )
+            .await?;
+        assert_eq!(201, res.status());
+
+        let res = client
+            .get(format!("http://{}/collections/peaks", addr).parse()?)
+            .await?;
+        let body = hyper::body::to_bytes(res.into_body()).await?;
+        let collection: Collection = serde_json::from_slice(&body)?;
+        assert_eq!(collection.crs, crs);
+        assert_eq!(collection.storage_crs, Some(Crs::default()));
+
+        let res = client
+            .get(format!("http://{}/collections", addr).parse()?)
+            .await?;
+        let body = hyper::body::to_bytes(res.into_body()).await?;
+        let collections: serde_json::Value = serde_json::from_slice(&body)?;
+        assert_eq!(collections["crs"].as_array().unwrap().len(), crs.len());
+    }
+
+    Ok(())
+}
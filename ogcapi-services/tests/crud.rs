@@ -125,3 +125,121 @@ async fn minimal_feature_crud() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn feature_versioning() -> anyhow::Result<()> {
+    // setup app
+    let (addr, _) = setup::spawn_app().await?;
+    let client = hyper::Client::new();
+
+    let collection = Collection {
+        id: "versioned".to_string(),
+        links: vec![],
+        crs: vec![Crs::default()],
+        ..Default::default()
+    };
+
+    // create collection
+    let res = client
+        .request(
+            Request::builder()
+                .method(axum::http::Method::POST)
+                .uri(format!("http://{}/collections", addr))
+                .header("Content-Type", JSON)
+                .body(Body::from(serde_json::to_string(&collection)?))?,
+        )
+        .await?;
+
+    assert_eq!(201, res.status());
+
+    let mut feature: Feature = serde_json::from_value(json!({
+        "id": "one",
+        "type": "Feature",
+        "geometry": {
+            "type": "Point",
+            "coordinates": [7.428959, 1.513394]
+        }
+    }))?;
+
+    // create feature
+    let res = client
+        .request(
+            Request::builder()
+                .method(axum::http::Method::POST)
+                .uri(format!(
+                    "http://{}/collections/{}/items",
+                    addr, collection.id
+                ))
+                .header("Content-Type", JSON)
+                .body(Body::from(serde_json::to_string(&feature)?))?,
+        )
+        .await?;
+
+    assert_eq!(201, res.status());
+
+    // read feature
+    let uri = format!("http://{}/collections/{}/items/one", addr, collection.id);
+
+    let res = client.get(uri.parse()?).await?;
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let stored: Feature = serde_json::from_slice(&body)?;
+
+    assert_eq!(stored.version, Some(1));
+
+    // update with current version
+    feature.version = Some(1);
+    let res = client
+        .request(
+            Request::builder()
+                .method(axum::http::Method::PUT)
+                .uri(&uri)
+                .header("Content-Type", JSON)
+                .body(Body::from(serde_json::to_string(&feature)?))?,
+        )
+        .await?;
+
+    assert_eq!(204, res.status());
+
+    let res = client.get(uri.parse()?).await?;
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let stored: Feature = serde_json::from_slice(&body)?;
+
+    assert_eq!(stored.version, Some(2));
+
+    // update with stale version
+    let res = client
+        .request(
+            Request::builder()
+                .method(axum::http::Method::PUT)
+                .uri(&uri)
+                .header("Content-Type", JSON)
+                .body(Body::from(serde_json::to_string(&feature)?))?,
+        )
+        .await?;
+
+    assert_eq!(409, res.status());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn unsupported_media_type() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = hyper::Client::new();
+
+    let res = client
+        .request(
+            Request::builder()
+                .method(axum::http::Method::POST)
+                .uri(format!("http://{}/collections", addr))
+                .header("Content-Type", "text/plain")
+                .body(Body::from(
+                    json!({ "id": "plain", "links": [] }).to_string(),
+                ))?,
+        )
+        .await?;
+
+    assert_eq!(415, res.status());
+
+    Ok(())
+}
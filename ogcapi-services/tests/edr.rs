@@ -151,3 +151,115 @@ async fn edr() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[cfg(feature = "edr")]
+#[tokio::test]
+async fn instances() -> anyhow::Result<()> {
+    use axum::http::{Method, Request};
+    use hyper::Body;
+    use serde_json::json;
+
+    use ogcapi_drivers::{postgres::Db, CollectionTransactions, FeatureTransactions};
+    use ogcapi_types::{
+        common::{media_type::JSON, Collection},
+        edr::{Instances, Query},
+        features::FeatureCollection,
+    };
+
+    let (addr, database_url) = setup::spawn_app().await?;
+    let client = hyper::Client::new();
+
+    let db = Db::setup(&database_url).await?;
+    let collection = Collection {
+        id: "forecast".to_string(),
+        ..Default::default()
+    };
+    db.create_collection(&collection).await?;
+
+    // one feature per model run at the same position
+    for (run, temperature) in [("run-1", 12.5), ("run-2", 14.0)] {
+        let feature = serde_json::from_value(json!({
+            "collection": "forecast",
+            "type": "Feature",
+            "properties": { "instance": run, "temperature": temperature },
+            "geometry": { "type": "Point", "coordinates": [7.44, 46.95] }
+        }))?;
+        db.create_feature(&feature).await?;
+
+        let instance = json!({ "id": run, "title": format!("Model {run}"), "links": [] });
+        let res = client
+            .request(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri(format!("http://{addr}/collections/forecast/instances"))
+                    .header("Content-Type", JSON)
+                    .body(Body::from(instance.to_string()))?,
+            )
+            .await?;
+        assert_eq!(201, res.status());
+    }
+
+    // list instances
+    let res = client
+        .get(format!("http://{addr}/collections/forecast/instances").parse()?)
+        .await?;
+    assert_eq!(200, res.status());
+
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let instances: Instances = serde_json::from_slice(&body)?;
+    let ids: Vec<&str> = instances.instances.iter().map(|i| i.id.as_str()).collect();
+    assert_eq!(ids, ["run-1", "run-2"]);
+
+    // query position within an instance
+    let query = Query {
+        coords: "POINT(7.44 46.95)".to_string(),
+        ..Default::default()
+    };
+
+    let res = client
+        .get(
+            format!(
+                "http://{addr}/collections/forecast/instances/run-1/position?{}",
+                serde_qs::to_string(&query)?
+            )
+            .parse()?,
+        )
+        .await?;
+    assert_eq!(200, res.status());
+
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let fc: FeatureCollection = serde_json::from_slice(&body)?;
+    assert_eq!(fc.number_matched, Some(1));
+    assert_eq!(
+        fc.features[0].properties.as_ref().unwrap()["temperature"],
+        12.5
+    );
+
+    // without instance all runs match
+    let res = client
+        .get(
+            format!(
+                "http://{addr}/collections/forecast/position?{}",
+                serde_qs::to_string(&query)?
+            )
+            .parse()?,
+        )
+        .await?;
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let fc: FeatureCollection = serde_json::from_slice(&body)?;
+    assert_eq!(fc.number_matched, Some(2));
+
+    // unknown instance
+    let res = client
+        .get(
+            format!(
+                "http://{addr}/collections/forecast/instances/run-3/position?{}",
+                serde_qs::to_string(&query)?
+            )
+            .parse()?,
+        )
+        .await?;
+    assert_eq!(404, res.status());
+
+    Ok(())
+}
@@ -0,0 +1,2530 @@
+mod setup;
+
+use std::{collections::HashMap, net::SocketAddr};
+
+use axum::http::{Method, Request};
+use hyper::{client::HttpConnector, Body, Client};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use ogcapi_services::{ItemCount, PaginationStyle, Settings};
+use ogcapi_types::{
+    common::{
+        media_type::JSON, Bbox, Collection, Crs, Exception, Extent, IdStrategy, SpatialExtent,
+    },
+    features::{
+        Aggregation, Feature, FeatureCollection, GeometryValue, PropertyValue, PropertyValues,
+    },
+    records::{Concept, RecordProperties, Theme, RECORD},
+};
+
+async fn create_collection(
+    client: &Client<HttpConnector>,
+    addr: &SocketAddr,
+    collection: &Collection,
+) -> anyhow::Result<()> {
+    let res = client
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("http://{}/collections", addr))
+                .header("Content-Type", JSON)
+                .body(Body::from(serde_json::to_string(collection)?))?,
+        )
+        .await?;
+
+    assert_eq!(201, res.status());
+
+    Ok(())
+}
+
+async fn create_feature(
+    client: &Client<HttpConnector>,
+    addr: &SocketAddr,
+    collection: &str,
+    feature: &Value,
+) -> anyhow::Result<String> {
+    let res = client
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("http://{}/collections/{}/items", addr, collection))
+                .header("Content-Type", JSON)
+                .body(Body::from(serde_json::to_string(feature)?))?,
+        )
+        .await?;
+
+    assert_eq!(201, res.status());
+
+    let location = res.headers()["Location"].to_str()?;
+
+    Ok(location.rsplit('/').next().unwrap().to_string())
+}
+
+async fn items(
+    client: &Client<HttpConnector>,
+    addr: &SocketAddr,
+    collection: &str,
+    query: &str,
+) -> anyhow::Result<FeatureCollection> {
+    let res = client
+        .get(format!("http://{}/collections/{}/items?{}", addr, collection, query).parse()?)
+        .await?;
+
+    assert_eq!(200, res.status());
+
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+
+    Ok(serde_json::from_slice(&body)?)
+}
+
+#[tokio::test]
+async fn geometry_column() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    for (id, geometry_column) in [("default", None), ("ogr", Some("wkb_geometry"))] {
+        let collection = Collection {
+            id: id.to_string(),
+            crs: vec![Crs::default()],
+            geometry_column: geometry_column.map(ToString::to_string),
+            ..Default::default()
+        };
+        create_collection(&client, &addr, &collection).await?;
+
+        for (x, y) in [(7.0, 46.0), (-70.0, 40.0)] {
+            let feature = json!({
+                "type": "Feature",
+                "properties": {},
+                "geometry": { "type": "Point", "coordinates": [x, y] }
+            });
+            create_feature(&client, &addr, id, &feature).await?;
+        }
+
+        let fc = items(&client, &addr, id, "bbox=5,45,10,48").await?;
+
+        assert_eq!(fc.number_matched, Some(1));
+        assert_eq!(
+            fc.features[0].geometry.value,
+            geojson::Value::Point(vec![7.0, 46.0])
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn external_table() -> anyhow::Result<()> {
+    let (addr, database_url) = setup::spawn_app_with(|state| {
+        state.settings(Settings {
+            tables: vec!["public.places".to_string()],
+            ..Default::default()
+        })
+    })
+    .await?;
+    let client = Client::new();
+
+    let pool = sqlx::PgPool::connect(database_url.as_str()).await?;
+    sqlx::query(
+        r#"
+        CREATE TABLE public.places (
+            gid serial PRIMARY KEY,
+            name text,
+            the_geom geometry(Point, 4326)
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+    sqlx::query(
+        r#"
+        INSERT INTO public.places (name, the_geom) VALUES
+            ('Bern', ST_SetSRID(ST_MakePoint(7.44, 46.95), 4326)),
+            ('Boston', ST_SetSRID(ST_MakePoint(-71.06, 42.36), 4326))
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+
+    let collection = Collection {
+        id: "places".to_string(),
+        crs: vec![Crs::default()],
+        table: Some("public.places".to_string()),
+        id_column: Some("gid".to_string()),
+        geometry_column: Some("the_geom".to_string()),
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    // read
+    let fc = items(&client, &addr, "places", "").await?;
+    assert_eq!(fc.number_matched, Some(2));
+
+    let fc = items(&client, &addr, "places", "bbox=5,45,10,48").await?;
+    assert_eq!(fc.number_matched, Some(1));
+    let feature = &fc.features[0];
+    assert_eq!(feature.id, Some("1".to_string()));
+    let properties = feature.properties.as_ref().unwrap();
+    assert_eq!(properties["name"], "Bern");
+    assert!(!properties.contains_key("gid"));
+
+    // storage details are not exposed
+    let res = client
+        .get(format!("http://{}/collections/places", addr).parse()?)
+        .await?;
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let collection: Value = serde_json::from_slice(&body)?;
+    assert!(collection["table"].is_null());
+    assert!(collection["idColumn"].is_null());
+
+    // only configured tables can back collections
+    let res = client
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("http://{}/collections", addr))
+                .header("Content-Type", JSON)
+                .body(Body::from(serde_json::to_string(&Collection {
+                    id: "roles".to_string(),
+                    table: Some("pg_catalog.pg_authid".to_string()),
+                    id_column: Some("oid".to_string()),
+                    ..Default::default()
+                })?))?,
+        )
+        .await?;
+    assert_eq!(403, res.status());
+
+    // write
+    let res = client
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("http://{}/collections/places/items", addr))
+                .header("Content-Type", JSON)
+                .body(Body::from(
+                    json!({
+                        "type": "Feature",
+                        "properties": { "name": "Zurich" },
+                        "geometry": { "type": "Point", "coordinates": [8.54, 47.37] }
+                    })
+                    .to_string(),
+                ))?,
+        )
+        .await?;
+    assert_eq!(405, res.status());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn distinct_values() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "trees".to_string(),
+        crs: vec![Crs::default()],
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    for (category, x) in [("oak", 7.0), ("oak", 8.0), ("pine", 9.0), ("elm", -70.0)] {
+        let feature = json!({
+            "type": "Feature",
+            "properties": { "category": category },
+            "geometry": { "type": "Point", "coordinates": [x, 46.0] }
+        });
+        create_feature(&client, &addr, "trees", &feature).await?;
+    }
+
+    let res = client
+        .get(
+            format!(
+                "http://{}/collections/trees/properties/category/values?bbox=5,45,10,48",
+                addr
+            )
+            .parse()?,
+        )
+        .await?;
+    assert_eq!(200, res.status());
+
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let values: PropertyValues = serde_json::from_slice(&body)?;
+
+    assert_eq!(
+        values.values,
+        vec![
+            PropertyValue {
+                value: json!("oak"),
+                count: 2
+            },
+            PropertyValue {
+                value: json!("pine"),
+                count: 1
+            }
+        ]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn aggregate() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "buildings".to_string(),
+        crs: vec![Crs::default()],
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    for (usage, height) in [("residential", 10), ("residential", 20), ("office", 50)] {
+        let feature = json!({
+            "type": "Feature",
+            "properties": { "usage": usage, "height": height },
+            "geometry": { "type": "Point", "coordinates": [7.0, 46.0] }
+        });
+        create_feature(&client, &addr, "buildings", &feature).await?;
+    }
+
+    let res = client
+        .get(
+            format!(
+                "http://{}/collections/buildings/properties/height/aggregate?group-by=usage",
+                addr
+            )
+            .parse()?,
+        )
+        .await?;
+    assert_eq!(200, res.status());
+
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let aggregation: Aggregation = serde_json::from_slice(&body)?;
+
+    assert_eq!(aggregation.groups.len(), 2);
+    assert_eq!(aggregation.groups[0].key, Some(json!("office")));
+    assert_eq!(aggregation.groups[0].avg, Some(50.0));
+    assert_eq!(aggregation.groups[1].key, Some(json!("residential")));
+    assert_eq!(aggregation.groups[1].count, 2);
+    assert_eq!(aggregation.groups[1].avg, Some(15.0));
+
+    // unknown property
+    let res = client
+        .get(
+            format!(
+                "http://{}/collections/buildings/properties/floors/aggregate",
+                addr
+            )
+            .parse()?,
+        )
+        .await?;
+    assert_eq!(400, res.status());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn cluster() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "sightings".to_string(),
+        crs: vec![Crs::default()],
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    for (x, y) in [(7.0, 46.0), (7.01, 46.01), (7.02, 46.0), (-70.0, 40.0)] {
+        let feature = json!({
+            "type": "Feature",
+            "properties": {},
+            "geometry": { "type": "Point", "coordinates": [x, y] }
+        });
+        create_feature(&client, &addr, "sightings", &feature).await?;
+    }
+
+    let fc = items(&client, &addr, "sightings", "cluster=0.1").await?;
+
+    assert_eq!(fc.number_matched, Some(2));
+
+    let mut counts: Vec<u64> = fc
+        .features
+        .iter()
+        .map(|f| f.properties.as_ref().unwrap()["count"].as_u64().unwrap())
+        .collect();
+    counts.sort();
+    assert_eq!(counts, vec![1, 3]);
+
+    for distance in ["NaN", "inf", "-1"] {
+        let res = client
+            .get(
+                format!(
+                    "http://{}/collections/sightings/items?cluster={}",
+                    addr, distance
+                )
+                .parse()?,
+            )
+            .await?;
+        assert_eq!(400, res.status(), "{distance}");
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn records_search() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "catalog".to_string(),
+        item_type: Some(RECORD.to_string()),
+        crs: vec![Crs::default()],
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    let records = [
+        RecordProperties {
+            r#type: "dataset".to_string(),
+            title: "Sea surface temperature".to_string(),
+            keywords: vec!["Ocean".to_string(), "temperature".to_string()],
+            ..Default::default()
+        },
+        RecordProperties {
+            r#type: "dataset".to_string(),
+            title: "Bathymetry".to_string(),
+            description: Some("Depth of the ocean floor".to_string()),
+            ..Default::default()
+        },
+        RecordProperties {
+            r#type: "dataset".to_string(),
+            title: "Land cover".to_string(),
+            themes: vec![Theme {
+                concepts: vec![Concept {
+                    id: "forest".to_string(),
+                    ..Default::default()
+                }],
+                scheme: "https://example.com/landcover".to_string(),
+            }],
+            ..Default::default()
+        },
+    ];
+    for properties in records {
+        let feature = json!({
+            "type": "Feature",
+            "properties": properties,
+            "geometry": { "type": "Point", "coordinates": [0.0, 0.0] }
+        });
+        create_feature(&client, &addr, "catalog", &feature).await?;
+    }
+
+    let fc = items(&client, &addr, "catalog", "q=ocean").await?;
+    assert_eq!(fc.number_matched, Some(2));
+
+    let fc = items(&client, &addr, "catalog", "q=forest,bathymetry").await?;
+    assert_eq!(fc.number_matched, Some(2));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn text_search() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "shops".to_string(),
+        crs: vec![Crs::default()],
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    let shops = [
+        json!({ "name": "Corner Bakery", "street": "Main Street" }),
+        json!({ "name": "Bakery Bakery", "opening_hours": "daily" }),
+        json!({ "name": "Butcher", "note": "next to the bakery" }),
+        json!({ "name": "Florist", "floors": 2 }),
+    ];
+    for properties in shops {
+        let feature = json!({
+            "type": "Feature",
+            "properties": properties,
+            "geometry": { "type": "Point", "coordinates": [0.0, 0.0] }
+        });
+        create_feature(&client, &addr, "shops", &feature).await?;
+    }
+
+    let fc = items(&client, &addr, "shops", "q=BAKERY").await?;
+    assert_eq!(fc.number_matched, Some(3));
+    assert_eq!(
+        fc.features[0].properties.as_ref().unwrap()["name"],
+        "Bakery Bakery"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn id_strategy() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let feature = json!({
+        "type": "Feature",
+        "properties": {},
+        "geometry": { "type": "Point", "coordinates": [0.0, 0.0] }
+    });
+
+    // uuid
+    let collection = Collection {
+        id: "uuid".to_string(),
+        id_strategy: Some(IdStrategy::Uuid),
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    let id = create_feature(&client, &addr, "uuid", &feature).await?;
+    assert!(Uuid::parse_str(&id).is_ok());
+
+    // sequential
+    let collection = Collection {
+        id: "sequential".to_string(),
+        id_strategy: Some(IdStrategy::Sequential),
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    let mut ids = Vec::new();
+    for _ in 0..3 {
+        let id = create_feature(&client, &addr, "sequential", &feature).await?;
+        ids.push(id.parse::<i64>()?);
+    }
+    assert!(ids.windows(2).all(|w| w[0] < w[1]));
+
+    // client supplied ids are rejected
+    let mut with_id = feature.clone();
+    with_id["id"] = json!("mine");
+
+    let res = client
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("http://{}/collections/sequential/items", addr))
+                .header("Content-Type", JSON)
+                .body(Body::from(serde_json::to_string(&with_id)?))?,
+        )
+        .await?;
+    assert_eq!(400, res.status());
+
+    // sequence of a collection with an id requiring quotes
+    let collection = Collection {
+        id: "Sequential's".to_string(),
+        id_strategy: Some(IdStrategy::Sequential),
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    let id = create_feature(&client, &addr, "Sequential%27s", &feature).await?;
+    assert_eq!(id, "1");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn return_representation() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "representation".to_string(),
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    let feature = json!({
+        "type": "Feature",
+        "properties": { "name": "Bern" },
+        "geometry": { "type": "Point", "coordinates": [7.44, 46.95] }
+    });
+
+    let res = client
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("http://{}/collections/representation/items", addr))
+                .header("Content-Type", JSON)
+                .header("Prefer", "return=representation")
+                .body(Body::from(serde_json::to_string(&feature)?))?,
+        )
+        .await?;
+
+    assert_eq!(201, res.status());
+    assert_eq!(res.headers()["Preference-Applied"], "return=representation");
+
+    let location = res.headers()["Location"].to_str()?.to_string();
+
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let created: Feature = serde_json::from_slice(&body)?;
+
+    let id = created.id.expect("generated id");
+    assert!(location.ends_with(&format!("/items/{}", id)));
+    assert_eq!(created.properties.unwrap()["name"], "Bern");
+    assert!(created.links.iter().any(|l| l.rel == "self"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn property_existence() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "huts".to_string(),
+        crs: vec![Crs::default()],
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    let huts = [
+        json!({ "name": "Capanna", "phone": "+41 91 000 00 00" }),
+        json!({ "name": "Refuge", "phone": null }),
+        json!({ "name": "Bivouac" }),
+    ];
+    for properties in huts {
+        let feature = json!({
+            "type": "Feature",
+            "properties": properties,
+            "geometry": { "type": "Point", "coordinates": [0.0, 0.0] }
+        });
+        create_feature(&client, &addr, "huts", &feature).await?;
+    }
+
+    // a null value still counts as existing
+    let fc = items(&client, &addr, "huts", "exists=phone").await?;
+    assert_eq!(fc.number_matched, Some(2));
+    assert!(fc
+        .features
+        .iter()
+        .all(|f| f.properties.as_ref().unwrap()["name"] != "Bivouac"));
+
+    let fc = items(&client, &addr, "huts", "not-exists=phone").await?;
+    assert_eq!(fc.number_matched, Some(1));
+    assert_eq!(
+        fc.features[0].properties.as_ref().unwrap()["name"],
+        "Bivouac"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn envelope_bbox() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "summits".to_string(),
+        crs: vec![Crs::default()],
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    let fc = items(&client, &addr, "summits", "").await?;
+    assert!(fc.bbox.is_none());
+
+    for coordinates in [[7.66, 45.98], [8.0, 46.58], [7.86, 45.94]] {
+        let feature = json!({
+            "type": "Feature",
+            "properties": {},
+            "geometry": { "type": "Point", "coordinates": coordinates }
+        });
+        create_feature(&client, &addr, "summits", &feature).await?;
+    }
+
+    let fc = items(&client, &addr, "summits", "").await?;
+    assert_eq!(fc.bbox, Some(Bbox::Bbox2D([7.66, 45.94, 8.0, 46.58])));
+
+    // only bounds the returned features
+    let fc = items(&client, &addr, "summits", "limit=1&sortby=id").await?;
+    let Some(Bbox::Bbox2D([xmin, ymin, xmax, ymax])) = fc.bbox else {
+        panic!("expected 2D bbox")
+    };
+    assert_eq!((xmin, ymin), (xmax, ymax));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn offset_limit_validation() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app_with(|state| {
+        state.settings(Settings {
+            max_limit: 2,
+            ..Default::default()
+        })
+    })
+    .await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "benches".to_string(),
+        crs: vec![Crs::default()],
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    for i in 0..3 {
+        let feature = json!({
+            "type": "Feature",
+            "properties": { "number": i },
+            "geometry": { "type": "Point", "coordinates": [0.0, 0.0] }
+        });
+        create_feature(&client, &addr, "benches", &feature).await?;
+    }
+
+    // negative offset
+    let res = client
+        .get(format!("http://{}/collections/benches/items?offset=-1", addr).parse()?)
+        .await?;
+    assert_eq!(400, res.status());
+
+    // limit is clamped to the maximum
+    let fc = items(&client, &addr, "benches", "limit=1000000000").await?;
+    assert_eq!(fc.number_matched, Some(3));
+    assert_eq!(fc.features.len(), 2);
+
+    let next = fc.links.iter().find(|l| l.rel == "next").unwrap();
+    assert!(next.href.contains("limit=2"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn crafted_offset() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "kiosks".to_string(),
+        crs: vec![Crs::default()],
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    for i in 0..3 {
+        let feature = json!({
+            "type": "Feature",
+            "properties": { "number": i },
+            "geometry": { "type": "Point", "coordinates": [0.0, 0.0] }
+        });
+        create_feature(&client, &addr, "kiosks", &feature).await?;
+    }
+
+    // sql appended to the offset is rejected instead of being executed
+    for offset in ["0%20OR%201=1", "0;%20DELETE%20FROM%20items.kiosks", "1--"] {
+        let res = client
+            .get(format!("http://{}/collections/kiosks/items?offset={}", addr, offset).parse()?)
+            .await?;
+        assert_eq!(400, res.status());
+    }
+
+    let fc = items(&client, &addr, "kiosks", "").await?;
+    assert_eq!(fc.number_matched, Some(3));
+
+    // the largest offset postgres accepts selects an empty page
+    let fc = items(&client, &addr, "kiosks", &format!("offset={}", i64::MAX)).await?;
+    assert_eq!(fc.number_matched, Some(3));
+    assert!(fc.features.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn gzip_request_body() -> anyhow::Result<()> {
+    use std::io::Write;
+
+    use flate2::{write::GzEncoder, Compression};
+
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "springs".to_string(),
+        crs: vec![Crs::default()],
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    let features = (0..3)
+        .map(|i| {
+            json!({
+                "type": "Feature",
+                "properties": { "number": i },
+                "geometry": { "type": "Point", "coordinates": [i, i] }
+            })
+        })
+        .collect::<Vec<Value>>();
+    let fc = json!({ "type": "FeatureCollection", "features": features });
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(fc.to_string().as_bytes())?;
+    let body = encoder.finish()?;
+
+    let res = client
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("http://{}/collections/springs/items", addr))
+                .header("Content-Type", "application/geo+json")
+                .header("Content-Encoding", "gzip")
+                .body(Body::from(body.clone()))?,
+        )
+        .await?;
+    assert_eq!(201, res.status());
+
+    let fc = items(&client, &addr, "springs", "").await?;
+    assert_eq!(fc.number_matched, Some(3));
+
+    // unknown encodings are rejected
+    let res = client
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("http://{}/collections/springs/items", addr))
+                .header("Content-Type", "application/geo+json")
+                .header("Content-Encoding", "br")
+                .body(Body::from(body))?,
+        )
+        .await?;
+    assert_eq!(415, res.status());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn max_body_size() -> anyhow::Result<()> {
+    use std::io::Write;
+
+    use flate2::{write::GzEncoder, Compression};
+
+    let (addr, _) = setup::spawn_app_with(|state| {
+        state.settings(Settings {
+            max_body_size: 1024,
+            ..Default::default()
+        })
+    })
+    .await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "wells".to_string(),
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    // compresses to a fraction of the limit
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&vec![b' '; 100 * 1024])?;
+    let body = encoder.finish()?;
+    assert!(body.len() < 1024);
+
+    let res = client
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("http://{}/collections/wells/items", addr))
+                .header("Content-Type", "application/geo+json")
+                .header("Content-Encoding", "gzip")
+                .body(Body::from(body))?,
+        )
+        .await?;
+    assert_eq!(413, res.status());
+
+    // uncompressed bodies are limited as well
+    let res = client
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("http://{}/collections/wells/items", addr))
+                .header("Content-Type", "application/geo+json")
+                .body(Body::from(vec![b' '; 2048]))?,
+        )
+        .await?;
+    assert_eq!(413, res.status());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn pagination_styles() -> anyhow::Result<()> {
+    for (style, parameter) in [
+        (PaginationStyle::Offset, "offset="),
+        (PaginationStyle::Token, "token="),
+    ] {
+        let (addr, _) = setup::spawn_app_with(|state| {
+            state.settings(Settings {
+                pagination: style,
+                ..Default::default()
+            })
+        })
+        .await?;
+        let client = Client::new();
+
+        let collection = Collection {
+            id: "pages".to_string(),
+            crs: vec![Crs::default()],
+            ..Default::default()
+        };
+        create_collection(&client, &addr, &collection).await?;
+
+        for i in 0..3 {
+            let feature = json!({
+                "type": "Feature",
+                "properties": { "number": i },
+                "geometry": { "type": "Point", "coordinates": [0.0, 0.0] }
+            });
+            create_feature(&client, &addr, "pages", &feature).await?;
+        }
+
+        let first = items(&client, &addr, "pages", "limit=2").await?;
+        assert_eq!(first.features.len(), 2);
+
+        let next = first.links.iter().find(|l| l.rel == "next").unwrap();
+        assert!(next.href.contains(parameter), "{:?}: {}", style, next.href);
+
+        // follow the next link
+        let res = client.get(next.href.parse()?).await?;
+        assert_eq!(200, res.status());
+
+        let body = hyper::body::to_bytes(res.into_body()).await?;
+        let second: FeatureCollection = serde_json::from_slice(&body)?;
+
+        assert_eq!(second.features.len(), 1);
+        assert!(!second.links.iter().any(|l| l.rel == "next"));
+        assert!(first.features.iter().all(|f| f.id != second.features[0].id));
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn topojson() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "parcels".to_string(),
+        crs: vec![Crs::default()],
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    let geometries = [
+        json!({ "type": "Polygon", "coordinates": [[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0], [0.0, 0.0]]] }),
+        json!({ "type": "Polygon", "coordinates": [[[1.0, 0.0], [2.0, 0.0], [2.0, 1.0], [1.0, 1.0], [1.0, 0.0]]] }),
+        json!({ "type": "Point", "coordinates": [5.0, 5.0] }),
+    ];
+    for geometry in geometries {
+        let feature = json!({ "type": "Feature", "properties": {}, "geometry": geometry });
+        create_feature(&client, &addr, "parcels", &feature).await?;
+    }
+
+    let fc = items(&client, &addr, "parcels", "").await?;
+
+    let res = client
+        .get(format!("http://{}/collections/parcels/items?f=topojson", addr).parse()?)
+        .await?;
+    assert_eq!(200, res.status());
+
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let topology: Value = serde_json::from_slice(&body)?;
+
+    assert_eq!(topology["type"], "Topology");
+
+    // the shared edge is stored once
+    let arcs: Vec<Vec<Vec<f64>>> = serde_json::from_value(topology["arcs"].clone())?;
+    assert_eq!(arcs.len(), 3);
+
+    let decode_ring = |indices: &Value| -> Vec<Vec<f64>> {
+        let mut ring: Vec<Vec<f64>> = Vec::new();
+        for index in indices.as_array().unwrap() {
+            let index = index.as_i64().unwrap();
+            let mut arc = if index >= 0 {
+                arcs[index as usize].clone()
+            } else {
+                arcs[!index as usize].iter().rev().cloned().collect()
+            };
+            if !ring.is_empty() {
+                arc.remove(0);
+            }
+            ring.extend(arc);
+        }
+        ring
+    };
+
+    // rings are equal up to their starting position
+    let same_ring = |a: &[Vec<f64>], b: &[Vec<f64>]| {
+        let (a, b) = (&a[..a.len() - 1], &b[..b.len() - 1]);
+        a.len() == b.len()
+            && (0..a.len()).any(|r| (0..a.len()).all(|i| a[i] == b[(i + r) % b.len()]))
+    };
+
+    let objects = topology["objects"]["parcels"]["geometries"]
+        .as_array()
+        .unwrap();
+    assert_eq!(objects.len(), fc.features.len());
+
+    for (object, feature) in objects.iter().zip(fc.features.iter()) {
+        assert_eq!(object["id"], json!(feature.id));
+
+        match &feature.geometry.value {
+            geojson::Value::Polygon(rings) => {
+                assert_eq!(object["type"], "Polygon");
+                let decoded = decode_ring(&object["arcs"][0]);
+                assert!(
+                    same_ring(&decoded, &rings[0]),
+                    "{:?} != {:?}",
+                    decoded,
+                    rings[0]
+                );
+            }
+            geojson::Value::Point(point) => {
+                assert_eq!(object["type"], "Point");
+                assert_eq!(object["coordinates"], json!(point));
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn geometry_format() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "wells".to_string(),
+        crs: vec![Crs::default()],
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    let feature = json!({
+        "type": "Feature",
+        "properties": {},
+        "geometry": { "type": "Point", "coordinates": [7.5, 46.0] }
+    });
+    create_feature(&client, &addr, "wells", &feature).await?;
+
+    let fc = items(&client, &addr, "wells", "geometry-format=wkt").await?;
+    let properties = fc.features[0].properties.as_ref().unwrap();
+    assert_eq!(properties["wkt"], "POINT(7.5 46)");
+
+    let fc = items(&client, &addr, "wells", "geometry-format=wkb").await?;
+    let properties = fc.features[0].properties.as_ref().unwrap();
+    assert!(properties["wkb"]
+        .as_str()
+        .unwrap()
+        .starts_with("0101000000"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn default_sort() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "observations".to_string(),
+        crs: vec![Crs::default()],
+        default_sort: Some(vec!["-datetime".to_string()]),
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    for (id, datetime) in [
+        ("b", "2023-01-02T00:00:00Z"),
+        ("c", "2023-01-01T00:00:00Z"),
+        ("a", "2023-01-02T00:00:00Z"),
+        ("d", "2023-01-03T00:00:00Z"),
+    ] {
+        let feature = json!({
+            "id": id,
+            "type": "Feature",
+            "properties": { "datetime": datetime },
+            "geometry": { "type": "Point", "coordinates": [0.0, 0.0] }
+        });
+        create_feature(&client, &addr, "observations", &feature).await?;
+    }
+
+    let ids = |fc: FeatureCollection| -> Vec<String> {
+        fc.features.into_iter().map(|f| f.id.unwrap()).collect()
+    };
+
+    // default sort with the id as tiebreaker
+    let fc = items(&client, &addr, "observations", "").await?;
+    assert_eq!(ids(fc), vec!["d", "a", "b", "c"]);
+
+    // explicit sortby takes precedence
+    let fc = items(&client, &addr, "observations", "sortby=%2Bdatetime,-id").await?;
+    assert_eq!(ids(fc), vec!["c", "b", "a", "d"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn json_fg_profile() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "stations".to_string(),
+        crs: vec![Crs::default()],
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    let feature = json!({
+        "type": "Feature",
+        "properties": { "name": "Jungfraujoch" },
+        "geometry": { "type": "Point", "coordinates": [7.985, 46.547] }
+    });
+    create_feature(&client, &addr, "stations", &feature).await?;
+
+    let profile =
+        r#"application/geo+json; profile="http://www.opengis.net/def/profile/OGC/0/jsonfg""#;
+    for (accept, content_type) in [
+        (profile, profile),
+        ("application/vnd.ogc.fg+json", "application/vnd.ogc.fg+json"),
+    ] {
+        let res = client
+            .request(
+                Request::builder()
+                    .uri(format!("http://{}/collections/stations/items", addr))
+                    .header("Accept", accept)
+                    .body(Body::empty())?,
+            )
+            .await?;
+        assert_eq!(200, res.status());
+        assert_eq!(res.headers()["Content-Type"], content_type);
+
+        let body = hyper::body::to_bytes(res.into_body()).await?;
+        let fc: Value = serde_json::from_slice(&body)?;
+
+        assert_eq!(fc["type"], "FeatureCollection");
+        assert!(fc["conformsTo"].as_array().unwrap().contains(&json!(
+            "http://www.opengis.net/spec/json-fg-1/0.1/conf/core"
+        )));
+
+        let feature = &fc["features"][0];
+        assert_eq!(
+            feature["coordRefSys"],
+            "http://www.opengis.net/def/crs/OGC/1.3/CRS84"
+        );
+        assert!(feature.get("place").is_some());
+        assert!(feature.get("time").is_some());
+        assert_eq!(feature["geometry"]["type"], "Point");
+    }
+
+    // plain GeoJSON without the profile
+    let res = client
+        .request(
+            Request::builder()
+                .uri(format!("http://{}/collections/stations/items", addr))
+                .header("Accept", "application/geo+json")
+                .body(Body::empty())?,
+        )
+        .await?;
+    assert_eq!(res.headers()["Content-Type"], "application/geo+json");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn json_fg() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "observations".to_string(),
+        crs: vec![Crs::default(), Crs::from_epsg(3857)],
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    let feature = json!({
+        "type": "Feature",
+        "properties": { "datetime": "2023-06-21T12:00:00Z", "temperature": 21.5 },
+        "geometry": { "type": "Point", "coordinates": [7.44, 46.95] }
+    });
+    create_feature(&client, &addr, "observations", &feature).await?;
+
+    let res = client
+        .get(format!("http://{}/collections/observations/items?f=jsonfg", addr).parse()?)
+        .await?;
+    assert_eq!(200, res.status());
+    assert_eq!(res.headers()["Content-Type"], "application/vnd.ogc.fg+json");
+
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let fc: Value = serde_json::from_slice(&body)?;
+
+    let feature = &fc["features"][0];
+    assert_eq!(feature["featureType"], "observations");
+    assert_eq!(
+        feature["coordRefSys"],
+        "http://www.opengis.net/def/crs/OGC/1.3/CRS84"
+    );
+    assert_eq!(feature["time"]["timestamp"], "2023-06-21T12:00:00Z");
+    assert_eq!(feature["geometry"]["type"], "Point");
+    assert!(feature["place"].is_null());
+
+    // other crs are encoded in place
+    let res = client
+        .get(
+            format!(
+                "http://{}/collections/observations/items?f=jsonfg&crs=http://www.opengis.net/def/crs/EPSG/0/3857",
+                addr
+            )
+            .parse()?,
+        )
+        .await?;
+    assert_eq!(200, res.status());
+
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let fc: Value = serde_json::from_slice(&body)?;
+
+    let feature = &fc["features"][0];
+    assert_eq!(
+        feature["coordRefSys"],
+        "http://www.opengis.net/def/crs/EPSG/0/3857"
+    );
+    assert_eq!(feature["place"]["type"], "Point");
+    assert!(feature["geometry"].is_null());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn empty_collection() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "empty".to_string(),
+        crs: vec![Crs::default()],
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    for query in ["", "limit=1&offset=5", "sortby=-id", "q=nothing"] {
+        let fc = items(&client, &addr, "empty", query).await?;
+
+        assert!(fc.features.is_empty());
+        assert_eq!(fc.number_matched, Some(0));
+        assert_eq!(fc.number_returned, Some(0));
+        assert!(fc.links.iter().any(|l| l.rel == "self"));
+        assert!(fc.links.iter().all(|l| l.rel != "next"));
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn unknown_collection() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    for path in [
+        "collections/does-not-exist/items",
+        "collections/does-not-exist/items/1",
+        "collections/does-not-exist/properties/name/values",
+    ] {
+        let res = client
+            .get(format!("http://{}/{}", addr, path).parse()?)
+            .await?;
+        assert_eq!(404, res.status(), "{path}");
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn skip_geometry() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "poles".to_string(),
+        crs: vec![Crs::default(), Crs::from_epsg(3857)],
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    // not representable in web mercator
+    let feature = json!({
+        "type": "Feature",
+        "properties": { "name": "North Pole" },
+        "geometry": { "type": "Point", "coordinates": [0.0, 90.0] }
+    });
+    create_feature(&client, &addr, "poles", &feature).await?;
+
+    let res = client
+        .get(
+            format!(
+                "http://{}/collections/poles/items?skipGeometry=true&crs=http://www.opengis.net/def/crs/EPSG/0/3857",
+                addr
+            )
+            .parse()?,
+        )
+        .await?;
+    assert_eq!(200, res.status());
+    assert!(res.headers().get("Content-Crs").is_none());
+
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let fc: Value = serde_json::from_slice(&body)?;
+
+    assert_eq!(fc["numberReturned"], 1);
+    assert!(fc["features"][0]["geometry"].is_null());
+    assert_eq!(fc["features"][0]["properties"]["name"], "North Pole");
+    assert!(fc.get("bbox").is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn radius() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "places".to_string(),
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    // Bern, a few hundred meters from the center, and Zurich
+    for (name, lon, lat) in [
+        ("Zytglogge", 7.4479, 46.9480),
+        ("Bundeshaus", 7.4441, 46.9465),
+        ("Zurich", 8.5417, 47.3769),
+    ] {
+        let feature = json!({
+            "type": "Feature",
+            "properties": { "name": name },
+            "geometry": { "type": "Point", "coordinates": [lon, lat] }
+        });
+        create_feature(&client, &addr, "places", &feature).await?;
+    }
+
+    let res = client
+        .get(
+            format!(
+                "http://{}/collections/places/items?center=7.4474,46.9480&radius=1000",
+                addr
+            )
+            .parse()?,
+        )
+        .await?;
+    assert_eq!(200, res.status());
+
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let fc: Value = serde_json::from_slice(&body)?;
+
+    assert_eq!(fc["numberMatched"], 2);
+    let mut names: Vec<&str> = fc["features"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["properties"]["name"].as_str().unwrap())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["Bundeshaus", "Zytglogge"]);
+
+    // radius requires a center
+    let res = client
+        .get(format!("http://{}/collections/places/items?radius=1000", addr).parse()?)
+        .await?;
+    assert_eq!(400, res.status());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn reserved_characters_in_id() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "sensors".to_string(),
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    let feature = json!({
+        "id": "station/1 a",
+        "type": "Feature",
+        "properties": {},
+        "geometry": { "type": "Point", "coordinates": [0.0, 0.0] }
+    });
+    let res = client
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("http://{}/collections/sensors/items", addr))
+                .header("Content-Type", JSON)
+                .body(Body::from(serde_json::to_string(&feature)?))?,
+        )
+        .await?;
+    assert_eq!(201, res.status());
+
+    let location = res.headers()["Location"].to_str()?.to_owned();
+    assert!(location.ends_with("/items/station%2F1%20a"));
+
+    let res = client.get(location.parse()?).await?;
+    assert_eq!(200, res.status());
+
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let feature: Feature = serde_json::from_slice(&body)?;
+    assert_eq!(feature.id.as_deref(), Some("station/1 a"));
+
+    // self links of listed items are encoded as well
+    let fc = items(&client, &addr, "sensors", "").await?;
+    assert!(fc.features[0]
+        .links
+        .iter()
+        .any(|l| l.rel == "self" && l.href == location));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn timeout() -> anyhow::Result<()> {
+    let (addr, database_url) = setup::spawn_app_with(|state| {
+        state.settings(Settings {
+            features_timeout: Some(200),
+            tables: vec!["public.slow".to_string()],
+            ..Default::default()
+        })
+    })
+    .await?;
+    let client = Client::new();
+
+    // every row of the view takes a second
+    let pool = sqlx::PgPool::connect(database_url.as_str()).await?;
+    sqlx::query(
+        r#"
+        CREATE VIEW public.slow AS
+        SELECT 1 AS gid, ST_SetSRID(ST_MakePoint(0, 0), 4326) AS geom
+        FROM pg_sleep(1)
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+
+    let collection = Collection {
+        id: "slow".to_string(),
+        crs: vec![Crs::default()],
+        table: Some("public.slow".to_string()),
+        id_column: Some("gid".to_string()),
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    let res = client
+        .get(format!("http://{}/collections/slow/items", addr).parse()?)
+        .await?;
+    assert_eq!(504, res.status());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn filter_crs() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "towers".to_string(),
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    for (name, lon, lat) in [("Zytglogge", 7.4479, 46.9480), ("Prime", 8.5417, 47.3769)] {
+        let feature = json!({
+            "type": "Feature",
+            "properties": { "name": name },
+            "geometry": { "type": "Point", "coordinates": [lon, lat] }
+        });
+        create_feature(&client, &addr, "towers", &feature).await?;
+    }
+
+    // web mercator polygon around Bern
+    let filter = "S_INTERSECTS(geometry,POLYGON((810000 5910000,850000 5910000,850000 5960000,810000 5960000,810000 5910000)))"
+        .replace(' ', "%20");
+
+    let fc = items(
+        &client,
+        &addr,
+        "towers",
+        &format!(
+            "filter={}&filter-crs=http://www.opengis.net/def/crs/EPSG/0/3857",
+            filter
+        ),
+    )
+    .await?;
+    assert_eq!(fc.number_matched, Some(1));
+    assert_eq!(
+        fc.features[0].properties.as_ref().unwrap()["name"],
+        "Zytglogge"
+    );
+
+    // without `filter-crs` the coordinates are taken as longitude and latitude
+    let fc = items(&client, &addr, "towers", &format!("filter={}", filter)).await?;
+    assert_eq!(fc.number_matched, Some(0));
+
+    let res = client
+        .get(
+            format!(
+                "http://{}/collections/towers/items?filter=S_INTERSECTS(geometry,POINT(0))&filter-crs=http://www.opengis.net/def/crs/EPSG/0/9999",
+                addr
+            )
+            .parse()?,
+        )
+        .await?;
+    assert_eq!(400, res.status());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn filter_limits() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app_with(|state| {
+        state.settings(Settings {
+            max_filter_vertices: 5,
+            ..Default::default()
+        })
+    })
+    .await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "limits".to_string(),
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    let square = "S_INTERSECTS(geometry,POLYGON((0 0,1 0,1 1,0 1,0 0)))".replace(' ', "%20");
+    items(&client, &addr, "limits", &format!("filter={}", square)).await?;
+
+    let status = |filter: String| {
+        let client = client.clone();
+        async move {
+            let uri = format!(
+                "http://{}/collections/limits/items?filter={}",
+                addr,
+                filter.replace(' ', "%20")
+            );
+            anyhow::Ok(client.get(uri.parse()?).await?.status())
+        }
+    };
+
+    // too many vertices
+    let hexagon = "S_INTERSECTS(geometry,POLYGON((0 0,1 0,1.5 0.5,1 1,0 1,-0.5 0.5,0 0)))";
+    assert_eq!(400, status(hexagon.to_string()).await?);
+
+    // invalid geometries
+    for wkt in [
+        "POINT(1)",
+        "POLYGON(0 0,1 1)",
+        "LINESTRING((0 0,1 1))",
+        "CIRCLE(0 0)",
+    ] {
+        assert_eq!(
+            400,
+            status(format!("S_INTERSECTS(geometry,{})", wkt)).await?
+        );
+    }
+
+    // too deeply nested
+    let nested = format!("{}id%3D'a'{}", "NOT(".repeat(100), ")".repeat(100));
+    assert_eq!(400, status(nested).await?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn extent() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "buoys".to_string(),
+        extent: Some(Extent {
+            spatial: Some(SpatialExtent {
+                bbox: vec![Bbox::Bbox2D([0.0, 0.0, 1.0, 1.0])],
+                crs: Crs::default(),
+            }),
+            temporal: None,
+        }),
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    let bbox = |client: Client<HttpConnector>| async move {
+        let res = client
+            .get(format!("http://{}/collections/buoys", addr).parse()?)
+            .await?;
+        let body = hyper::body::to_bytes(res.into_body()).await?;
+        let collection: Collection = serde_json::from_slice(&body)?;
+        anyhow::Ok(collection.extent.unwrap().spatial.unwrap().bbox)
+    };
+
+    // within the prior extent
+    let feature = json!({
+        "type": "Feature",
+        "properties": {},
+        "geometry": { "type": "Point", "coordinates": [0.5, 0.5] }
+    });
+    create_feature(&client, &addr, "buoys", &feature).await?;
+    assert_eq!(
+        bbox(client.clone()).await?,
+        [Bbox::Bbox2D([0.0, 0.0, 1.0, 1.0])]
+    );
+
+    // outside of the prior extent
+    let feature = json!({
+        "type": "Feature",
+        "properties": {},
+        "geometry": { "type": "Point", "coordinates": [5.0, -2.0] }
+    });
+    create_feature(&client, &addr, "buoys", &feature).await?;
+    assert_eq!(
+        bbox(client.clone()).await?,
+        [Bbox::Bbox2D([0.0, -2.0, 5.0, 1.0])]
+    );
+
+    // the z range of a 3D extent is kept and grown
+    let collection = Collection {
+        id: "floats".to_string(),
+        extent: Some(Extent {
+            spatial: Some(SpatialExtent {
+                bbox: vec![Bbox::Bbox3D([0.0, 0.0, -5.0, 1.0, 1.0, 5.0])],
+                crs: Crs::default(),
+            }),
+            temporal: None,
+        }),
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    let bbox = |client: Client<HttpConnector>| async move {
+        let res = client
+            .get(format!("http://{}/collections/floats", addr).parse()?)
+            .await?;
+        let body = hyper::body::to_bytes(res.into_body()).await?;
+        let collection: Collection = serde_json::from_slice(&body)?;
+        anyhow::Ok(collection.extent.unwrap().spatial.unwrap().bbox)
+    };
+
+    let feature = json!({
+        "type": "Feature",
+        "properties": {},
+        "geometry": { "type": "Point", "coordinates": [2.0, 3.0] }
+    });
+    create_feature(&client, &addr, "floats", &feature).await?;
+    assert_eq!(
+        bbox(client.clone()).await?,
+        [Bbox::Bbox3D([0.0, 0.0, -5.0, 2.0, 3.0, 5.0])]
+    );
+
+    let feature = json!({
+        "type": "Feature",
+        "properties": {},
+        "geometry": { "type": "Point", "coordinates": [0.5, 0.5, 10.0] }
+    });
+    create_feature(&client, &addr, "floats", &feature).await?;
+    assert_eq!(
+        bbox(client.clone()).await?,
+        [Bbox::Bbox3D([0.0, 0.0, -5.0, 2.0, 3.0, 10.0])]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn extent_refresh() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app_with(|state| {
+        state.settings(Settings {
+            extent_refresh_interval: Some(1),
+            ..Default::default()
+        })
+    })
+    .await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "drifters".to_string(),
+        extent: Some(Extent {
+            spatial: Some(SpatialExtent {
+                bbox: vec![Bbox::Bbox2D([0.0, 0.0, 1.0, 1.0])],
+                crs: Crs::default(),
+            }),
+            temporal: None,
+        }),
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    let bbox = |client: Client<HttpConnector>| async move {
+        let res = client
+            .get(format!("http://{}/collections/drifters", addr).parse()?)
+            .await?;
+        let body = hyper::body::to_bytes(res.into_body()).await?;
+        let collection: Collection = serde_json::from_slice(&body)?;
+        anyhow::Ok(collection.extent.unwrap().spatial.unwrap().bbox)
+    };
+
+    let feature = json!({
+        "type": "Feature",
+        "properties": { "datetime": "2020-01-01T12:00:00+02:00" },
+        "geometry": { "type": "Point", "coordinates": [2.0, 3.0] }
+    });
+    create_feature(&client, &addr, "drifters", &feature).await?;
+
+    let feature = json!({
+        "type": "Feature",
+        "properties": { "datetime": "2021-01-01T00:00:00Z" },
+        "geometry": { "type": "Point", "coordinates": [5.0, -2.0] }
+    });
+    let id = create_feature(&client, &addr, "drifters", &feature).await?;
+
+    let res = client
+        .request(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri(format!("http://{}/collections/drifters/items/{}", addr, id))
+                .body(Body::empty())?,
+        )
+        .await?;
+    assert_eq!(204, res.status());
+
+    // the deleted feature is gone and the prior extent is replaced on the
+    // next refresh
+    let mut refreshed = false;
+    for _ in 0..30 {
+        if bbox(client.clone()).await? == [Bbox::Bbox2D([2.0, 3.0, 2.0, 3.0])] {
+            refreshed = true;
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    assert!(refreshed, "{:?}", bbox(client.clone()).await?);
+
+    // along with the temporal extent
+    let res = client
+        .get(format!("http://{}/collections/drifters", addr).parse()?)
+        .await?;
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let collection: Collection = serde_json::from_slice(&body)?;
+    let datetime = "2020-01-01T10:00:00Z".parse()?;
+    assert_eq!(
+        collection.extent.unwrap().temporal.unwrap().interval,
+        [[Some(datetime), Some(datetime)]]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn property_names() -> anyhow::Result<()> {
+    let (addr, database_url) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "stations".to_string(),
+        property_names: Some(HashMap::from([(
+            "prop_internal".to_string(),
+            "displayName".to_string(),
+        )])),
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    let feature = json!({
+        "type": "Feature",
+        "properties": { "displayName": "Bern" },
+        "geometry": { "type": "Point", "coordinates": [7.44, 46.95] }
+    });
+    let id = create_feature(&client, &addr, "stations", &feature).await?;
+
+    // stored under the internal name
+    let pool = sqlx::PgPool::connect(database_url.as_str()).await?;
+    let (properties,): (Value,) =
+        sqlx::query_as(r#"SELECT properties FROM items.stations WHERE id = $1"#)
+            .bind(&id)
+            .fetch_one(&pool)
+            .await?;
+    assert_eq!(properties["prop_internal"], "Bern");
+    assert!(properties.get("displayName").is_none());
+
+    // returned under the exposed name
+    let res = client
+        .get(format!("http://{}/collections/stations/items/{}", addr, id).parse()?)
+        .await?;
+    assert_eq!(200, res.status());
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let feature: Feature = serde_json::from_slice(&body)?;
+    let properties = feature.properties.unwrap();
+    assert_eq!(properties["displayName"], "Bern");
+    assert!(!properties.contains_key("prop_internal"));
+
+    let fc = items(&client, &addr, "stations", "displayName=Bern").await?;
+    assert_eq!(fc.number_matched, Some(1));
+    assert_eq!(
+        fc.features[0].properties.as_ref().unwrap()["displayName"],
+        "Bern"
+    );
+
+    let fc = items(&client, &addr, "stations", "filter=displayName%3D'Bern'").await?;
+    assert_eq!(fc.number_matched, Some(1));
+
+    let fc = items(&client, &addr, "stations", "exists=displayName").await?;
+    assert_eq!(fc.number_matched, Some(1));
+
+    let fc = items(&client, &addr, "stations", "not-exists=displayName").await?;
+    assert_eq!(fc.number_matched, Some(0));
+
+    let res = client
+        .get(
+            format!(
+                "http://{}/collections/stations/properties/displayName/values",
+                addr
+            )
+            .parse()?,
+        )
+        .await?;
+    assert_eq!(200, res.status());
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let values: PropertyValues = serde_json::from_slice(&body)?;
+    assert_eq!(
+        values.values,
+        vec![PropertyValue {
+            value: json!("Bern"),
+            count: 1
+        }]
+    );
+
+    // the mapping is not exposed
+    let res = client
+        .get(format!("http://{}/collections/stations", addr).parse()?)
+        .await?;
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let collection: Value = serde_json::from_slice(&body)?;
+    assert!(collection.get("propertyNames").is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn timestamps() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "sensors".to_string(),
+        timestamps: Some(true),
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    let mut feature = json!({
+        "type": "Feature",
+        "properties": { "created": "1970-01-01T00:00:00Z" },
+        "geometry": { "type": "Point", "coordinates": [7.44, 46.95] }
+    });
+    let id = create_feature(&client, &addr, "sensors", &feature).await?;
+    let uri = format!("http://{}/collections/sensors/items/{}", addr, id);
+
+    let read = |client: Client<HttpConnector>, uri: String| async move {
+        let res = client.get(uri.parse()?).await?;
+        let body = hyper::body::to_bytes(res.into_body()).await?;
+        let feature: Feature = serde_json::from_slice(&body)?;
+        anyhow::Ok(feature.properties.unwrap())
+    };
+
+    // set on insert, ignoring the client value
+    let inserted = read(client.clone(), uri.clone()).await?;
+    let created = inserted["created"].as_str().unwrap().to_owned();
+    assert_ne!(created, "1970-01-01T00:00:00Z");
+    assert!(inserted["updated"].is_string());
+
+    // updated changes, created stays
+    feature["id"] = json!(id);
+    feature["properties"] = json!({ "created": "1970-01-01T00:00:00Z", "name": "Bern" });
+    let res = client
+        .request(
+            Request::builder()
+                .method(Method::PUT)
+                .uri(&uri)
+                .header("Content-Type", JSON)
+                .body(Body::from(feature.to_string()))?,
+        )
+        .await?;
+    assert_eq!(204, res.status());
+
+    let updated = read(client.clone(), uri.clone()).await?;
+    assert_eq!(updated["name"], "Bern");
+    assert_eq!(updated["created"], created.as_str());
+    assert_ne!(updated["updated"], inserted["updated"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn nested_filter() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "shops".to_string(),
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    for (city, zip) in [("Bern", 3011), ("Zurich", 8001)] {
+        let feature = json!({
+            "type": "Feature",
+            "properties": { "address": { "city": city, "zip": zip } },
+            "geometry": { "type": "Point", "coordinates": [7.44, 46.95] }
+        });
+        create_feature(&client, &addr, "shops", &feature).await?;
+    }
+
+    let fc = items(
+        &client,
+        &addr,
+        "shops",
+        "filter=properties.address.city%3D'Bern'",
+    )
+    .await?;
+    assert_eq!(fc.number_matched, Some(1));
+    let properties = fc.features[0].properties.as_ref().unwrap();
+    assert_eq!(properties["address"]["city"], "Bern");
+
+    let fc = items(&client, &addr, "shops", "filter=address.zip%3E5000").await?;
+    assert_eq!(fc.number_matched, Some(1));
+    let properties = fc.features[0].properties.as_ref().unwrap();
+    assert_eq!(properties["address"]["zip"], 8001);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn array_filter() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "trails".to_string(),
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    for (name, tags) in [
+        ("ridge", json!(["hiking", "scenic"])),
+        ("river", json!(["cycling", "scenic"])),
+        ("plain", json!("hiking")),
+    ] {
+        let feature = json!({
+            "type": "Feature",
+            "properties": { "name": name, "tags": tags },
+            "geometry": { "type": "Point", "coordinates": [7.44, 46.95] }
+        });
+        create_feature(&client, &addr, "trails", &feature).await?;
+    }
+
+    let fc = items(&client, &addr, "trails", "filter='hiking'%20IN%20tags").await?;
+    assert_eq!(fc.number_matched, Some(1));
+    let properties = fc.features[0].properties.as_ref().unwrap();
+    assert_eq!(properties["name"], "ridge");
+
+    let fc = items(
+        &client,
+        &addr,
+        "trails",
+        "filter=A_CONTAINS(tags,('scenic'))",
+    )
+    .await?;
+    assert_eq!(fc.number_matched, Some(2));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn grid_size() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "benches".to_string(),
+        grid_size: Some(0.01),
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    let feature = json!({
+        "type": "Feature",
+        "properties": {},
+        "geometry": {
+            "type": "LineString",
+            "coordinates": [[7.44449, 46.95123], [7.45551, 46.94876]]
+        }
+    });
+    let id = create_feature(&client, &addr, "benches", &feature).await?;
+
+    let res = client
+        .get(format!("http://{}/collections/benches/items/{}", addr, id).parse()?)
+        .await?;
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let feature: Feature = serde_json::from_slice(&body)?;
+
+    match feature.geometry.value {
+        geojson::Value::LineString(line) => {
+            let expected = [[7.44, 46.95], [7.46, 46.95]];
+            for (position, expected) in line.iter().zip(expected) {
+                assert!((position[0] - expected[0]).abs() < 1e-9);
+                assert!((position[1] - expected[1]).abs() < 1e-9);
+            }
+        }
+        value => panic!("unexpected geometry {value:?}"),
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn last_modified() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "hydrants".to_string(),
+        timestamps: Some(true),
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    let feature = json!({
+        "type": "Feature",
+        "properties": {},
+        "geometry": { "type": "Point", "coordinates": [7.44, 46.95] }
+    });
+    let id = create_feature(&client, &addr, "hydrants", &feature).await?;
+    let uri = format!("http://{}/collections/hydrants/items/{}", addr, id);
+
+    let res = client.get(uri.parse()?).await?;
+    assert_eq!(200, res.status());
+    let last_modified = res.headers()["Last-Modified"].to_str()?.to_string();
+
+    for (since, status) in [
+        (last_modified.as_str(), 304),
+        ("Thu, 01 Jan 1970 00:00:00 GMT", 200),
+    ] {
+        let res = client
+            .request(
+                Request::builder()
+                    .uri(&uri)
+                    .header("If-Modified-Since", since)
+                    .body(Body::empty())?,
+            )
+            .await?;
+        assert_eq!(status, res.status());
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn max_bbox_area() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app_with(|state| {
+        state.settings(Settings {
+            max_bbox_area: Some(100.0),
+            ..Default::default()
+        })
+    })
+    .await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "benches".to_string(),
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    let feature = json!({
+        "type": "Feature",
+        "properties": {},
+        "geometry": { "type": "Point", "coordinates": [7.44, 46.95] }
+    });
+    create_feature(&client, &addr, "benches", &feature).await?;
+
+    let res = client
+        .get(
+            format!(
+                "http://{}/collections/benches/items?bbox=-180,-90,180,90",
+                addr
+            )
+            .parse()?,
+        )
+        .await?;
+    assert_eq!(400, res.status());
+
+    let fc = items(&client, &addr, "benches", "bbox=5,45,10,48").await?;
+    assert_eq!(fc.number_matched, Some(1));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn time_stamp() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "benches".to_string(),
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    let before = chrono::Utc::now() - chrono::Duration::seconds(1);
+    let fc = items(&client, &addr, "benches", "").await?;
+
+    let time_stamp = chrono::DateTime::parse_from_rfc3339(fc.time_stamp.as_deref().unwrap())?;
+    assert!(time_stamp >= before);
+    assert!(time_stamp <= chrono::Utc::now());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn invalid_body() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "benches".to_string(),
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    let feature = json!({
+        "type": "Feature",
+        "properties": {},
+        "geometry": { "type": "Point", "coordinates": [7.44, 46.95] },
+        "version": "three"
+    });
+    let res = client
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("http://{}/collections/benches/items", addr))
+                .header("Content-Type", JSON)
+                .body(Body::from(serde_json::to_string(&feature)?))?,
+        )
+        .await?;
+    assert_eq!(400, res.status());
+
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let exception: Exception = serde_json::from_slice(&body)?;
+    let detail = exception.detail.unwrap();
+    assert!(detail.contains("`version`"), "{detail}");
+    assert!(detail.contains("expected i64"), "{detail}");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn bbox_crs() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "benches".to_string(),
+        crs: vec![Crs::default(), Crs::from_epsg(3857)],
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    let feature = json!({
+        "type": "Feature",
+        "properties": {},
+        "geometry": { "type": "Point", "coordinates": [7.44, 46.95] }
+    });
+    create_feature(&client, &addr, "benches", &feature).await?;
+
+    // CRS84 by default
+    let fc = items(&client, &addr, "benches", "bbox=7,46,8,47").await?;
+    assert_eq!(fc.number_matched, Some(1));
+
+    let fc = items(
+        &client,
+        &addr,
+        "benches",
+        &format!(
+            "bbox=800000,5900000,850000,5960000&bbox-crs={}",
+            Crs::from_epsg(3857)
+        ),
+    )
+    .await?;
+    assert_eq!(fc.number_matched, Some(1));
+
+    // not offered by the collection
+    let res = client
+        .get(
+            format!(
+                "http://{}/collections/benches/items?bbox=2600000,1200000,2610000,1210000&bbox-crs={}",
+                addr,
+                Crs::from_epsg(2056)
+            )
+            .parse()?,
+        )
+        .await?;
+    assert_eq!(400, res.status());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn geometry_columns() -> anyhow::Result<()> {
+    let (addr, database_url) = setup::spawn_app_with(|state| {
+        state.settings(Settings {
+            tables: vec!["public.parks".to_string()],
+            ..Default::default()
+        })
+    })
+    .await?;
+    let client = Client::new();
+
+    let pool = sqlx::PgPool::connect(database_url.as_str()).await?;
+    sqlx::query(
+        r#"
+        CREATE TABLE public.parks (
+            id serial PRIMARY KEY,
+            name text,
+            entrance geometry(Point, 4326),
+            boundary geometry(Polygon, 4326)
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+    sqlx::query(
+        r#"
+        INSERT INTO public.parks (name, entrance, boundary) VALUES (
+            'Rosengarten',
+            ST_SetSRID(ST_MakePoint(7.46, 46.95), 4326),
+            ST_MakeEnvelope(7.45, 46.94, 7.47, 46.96, 4326)
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await?;
+
+    let collection = Collection {
+        id: "parks".to_string(),
+        table: Some("public.parks".to_string()),
+        geometry_column: Some("entrance".to_string()),
+        geometry_columns: Some(vec!["boundary".to_string()]),
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    let fc = items(&client, &addr, "parks", "").await?;
+    assert!(matches!(
+        fc.features[0].geometry.value,
+        GeometryValue::Point(_)
+    ));
+
+    // only the boundary reaches into the bbox
+    let fc = items(
+        &client,
+        &addr,
+        "parks",
+        "geometry-column=boundary&bbox=7.465,46.955,7.5,47",
+    )
+    .await?;
+    assert_eq!(fc.number_matched, Some(1));
+    let feature = &fc.features[0];
+    assert!(matches!(feature.geometry.value, GeometryValue::Polygon(_)));
+    let properties = feature.properties.as_ref().unwrap();
+    assert_eq!(properties["name"], "Rosengarten");
+    assert!(!properties.contains_key("entrance"));
+    assert!(!properties.contains_key("boundary"));
+
+    let fc = items(&client, &addr, "parks", "bbox=7.465,46.955,7.5,47").await?;
+    assert_eq!(fc.number_matched, Some(0));
+
+    let res = client
+        .get(
+            format!(
+                "http://{}/collections/parks/items?geometry-column=name",
+                addr
+            )
+            .parse()?,
+        )
+        .await?;
+    assert_eq!(400, res.status());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn big_integers() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "accounts".to_string(),
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    // not representable as f64
+    let integers = [9007199254740993, i64::MAX, i64::MIN];
+    let feature = json!({
+        "type": "Feature",
+        "properties": { "integers": integers, "unsigned": u64::MAX },
+        "geometry": { "type": "Point", "coordinates": [7.44, 46.95] }
+    });
+    let id = create_feature(&client, &addr, "accounts", &feature).await?;
+
+    let res = client
+        .get(format!("http://{}/collections/accounts/items/{}", addr, id).parse()?)
+        .await?;
+    assert_eq!(200, res.status());
+
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let text = String::from_utf8(body.to_vec())?;
+    for integer in integers {
+        assert!(text.contains(&integer.to_string()), "{integer}");
+    }
+    assert!(text.contains(&u64::MAX.to_string()));
+
+    let feature: Value = serde_json::from_str(&text)?;
+    assert_eq!(feature["properties"]["integers"], json!(integers));
+    assert_eq!(feature["properties"]["unsigned"], json!(u64::MAX));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn read_feature_crs() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "fountains".to_string(),
+        crs: vec![Crs::default(), Crs::from_epsg(3857)],
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    let feature = json!({
+        "type": "Feature",
+        "properties": {},
+        "geometry": { "type": "Point", "coordinates": [7.44, 46.95] }
+    });
+    let id = create_feature(&client, &addr, "fountains", &feature).await?;
+
+    let res = client
+        .get(
+            format!(
+                "http://{}/collections/fountains/items/{}?crs={}",
+                addr,
+                id,
+                Crs::from_epsg(3857)
+            )
+            .parse()?,
+        )
+        .await?;
+    assert_eq!(200, res.status());
+    assert_eq!(
+        res.headers()["Content-Crs"],
+        Crs::from_epsg(3857).to_string().as_str()
+    );
+
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let feature: Value = serde_json::from_slice(&body)?;
+    let x = feature["geometry"]["coordinates"][0].as_f64().unwrap();
+    let y = feature["geometry"]["coordinates"][1].as_f64().unwrap();
+    assert!((x - 828_217.0).abs() < 1.0, "{x}");
+    assert!((5_900_000.0..5_960_000.0).contains(&y), "{y}");
+
+    // not offered by the collection
+    let res = client
+        .get(
+            format!(
+                "http://{}/collections/fountains/items/{}?crs={}",
+                addr,
+                id,
+                Crs::from_epsg(2056)
+            )
+            .parse()?,
+        )
+        .await?;
+    assert_eq!(400, res.status());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn max_batch_features() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app_with(|state| {
+        state.settings(Settings {
+            max_batch_features: 3,
+            ..Default::default()
+        })
+    })
+    .await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "hydrants".to_string(),
+        crs: vec![Crs::default()],
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    for (count, status) in [(4, 400), (3, 201)] {
+        let features = (0..count)
+            .map(|i| {
+                json!({
+                    "type": "Feature",
+                    "properties": { "number": i },
+                    "geometry": { "type": "Point", "coordinates": [i, i] }
+                })
+            })
+            .collect::<Vec<Value>>();
+        let fc = json!({ "type": "FeatureCollection", "features": features });
+
+        let res = client
+            .request(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri(format!("http://{}/collections/hydrants/items", addr))
+                    .header("Content-Type", "application/geo+json")
+                    .body(Body::from(fc.to_string()))?,
+            )
+            .await?;
+        assert_eq!(status, res.status());
+    }
+
+    // nothing of the rejected batch was created
+    let fc = items(&client, &addr, "hydrants", "").await?;
+    assert_eq!(fc.number_matched, Some(3));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sort_nulls() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "towers".to_string(),
+        crs: vec![Crs::default()],
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    for (id, height) in [("a", json!(20)), ("b", Value::Null), ("c", json!(10))] {
+        let feature = json!({
+            "id": id,
+            "type": "Feature",
+            "properties": { "height": height },
+            "geometry": { "type": "Point", "coordinates": [0.0, 0.0] }
+        });
+        create_feature(&client, &addr, "towers", &feature).await?;
+    }
+    let feature = json!({
+        "id": "d",
+        "type": "Feature",
+        "properties": {},
+        "geometry": { "type": "Point", "coordinates": [0.0, 0.0] }
+    });
+    create_feature(&client, &addr, "towers", &feature).await?;
+
+    let ids = |fc: FeatureCollection| -> Vec<String> {
+        fc.features.into_iter().map(|f| f.id.unwrap()).collect()
+    };
+
+    for (query, expected) in [
+        // last by default in both directions
+        ("sortby=height", ["c", "a", "b", "d"]),
+        ("sortby=-height", ["a", "c", "b", "d"]),
+        ("sortby=height&nulls=nullsFirst", ["b", "d", "c", "a"]),
+        ("sortby=-height&nulls=nullsFirst", ["b", "d", "a", "c"]),
+        ("sortby=-height&nulls=nullsLast", ["a", "c", "b", "d"]),
+    ] {
+        let fc = items(&client, &addr, "towers", query).await?;
+        assert_eq!(ids(fc), expected, "{query}");
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn item_count() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app_with(|state| {
+        state.settings(Settings {
+            item_count: Some(ItemCount::Exact),
+            ..Default::default()
+        })
+    })
+    .await?;
+    let client = Client::new();
+
+    // supplied counts are not stored
+    let collection = Collection {
+        id: "benches".to_string(),
+        item_count: Some(42),
+        ..Default::default()
+    };
+    create_collection(&client, &addr, &collection).await?;
+
+    for i in 0..5 {
+        let feature = json!({
+            "type": "Feature",
+            "properties": {},
+            "geometry": { "type": "Point", "coordinates": [i, i] }
+        });
+        create_feature(&client, &addr, "benches", &feature).await?;
+    }
+
+    let res = client
+        .get(format!("http://{}/collections/benches", addr).parse()?)
+        .await?;
+    assert_eq!(200, res.status());
+
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let collection: Value = serde_json::from_slice(&body)?;
+    assert_eq!(collection["itemCount"], 5);
+
+    // also in the listing
+    let res = client
+        .get(format!("http://{}/collections", addr).parse()?)
+        .await?;
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let collections: Value = serde_json::from_slice(&body)?;
+    assert_eq!(collections["collections"][0]["itemCount"], 5);
+
+    Ok(())
+}
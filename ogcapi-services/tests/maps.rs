@@ -0,0 +1,117 @@
+mod setup;
+
+#[cfg(feature = "maps")]
+#[tokio::test]
+async fn map_dimensions() -> anyhow::Result<()> {
+    use serde_json::json;
+
+    use ogcapi_drivers::{postgres::Db, CollectionTransactions, FeatureTransactions};
+    use ogcapi_types::common::{media_type::PNG, Collection};
+
+    let (addr, database_url) = setup::spawn_app().await?;
+    let client = hyper::Client::new();
+
+    let db = Db::setup(&database_url).await?;
+    let collection = Collection {
+        id: "lakes".to_string(),
+        ..Default::default()
+    };
+    db.create_collection(&collection).await?;
+
+    let feature = serde_json::from_value(json!({
+        "collection": "lakes",
+        "type": "Feature",
+        "properties": { "name": "Thunersee" },
+        "geometry": {
+            "type": "Polygon",
+            "coordinates": [[[7.6, 46.65], [7.85, 46.65], [7.85, 46.75], [7.6, 46.75], [7.6, 46.65]]]
+        }
+    }))?;
+    db.create_feature(&feature).await?;
+
+    // aspect ratio of the bbox differs from the image, and an empty area
+    for bbox in ["7.5,46.5,8.0,47.0", "0,0,1,1"] {
+        let res = client
+            .get(
+                format!(
+                    "http://{}/collections/lakes/map?bbox={}&width=400&height=300",
+                    addr, bbox
+                )
+                .parse()?,
+            )
+            .await?;
+        assert_eq!(200, res.status());
+        assert_eq!(res.headers()["Content-Type"], PNG);
+
+        let body = hyper::body::to_bytes(res.into_body()).await?;
+        assert!(body.starts_with(b"\x89PNG"));
+
+        // dimensions from the IHDR chunk
+        let width = u32::from_be_bytes(body[16..20].try_into()?);
+        let height = u32::from_be_bytes(body[20..24].try_into()?);
+        assert_eq!((width, height), (400, 300));
+    }
+
+    let res = client
+        .get(format!("http://{}/collections/lakes/map?width=400", addr).parse()?)
+        .await?;
+    assert_eq!(400, res.status());
+
+    Ok(())
+}
+
+#[cfg(feature = "maps")]
+#[tokio::test]
+async fn map_default_crs() -> anyhow::Result<()> {
+    use axum::http::{Method, Request};
+    use hyper::Body;
+    use serde_json::json;
+
+    use ogcapi_types::common::media_type::{JSON, PNG};
+
+    let (addr, _) = setup::spawn_app().await?;
+    let client = hyper::Client::new();
+
+    // without a list of CRS
+    let res = client
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("http://{}/collections", addr))
+                .header("Content-Type", JSON)
+                .body(Body::from(json!({ "id": "ponds" }).to_string()))?,
+        )
+        .await?;
+    assert_eq!(201, res.status());
+
+    // the CRS of the service are supported
+    for crs in [
+        "http://www.opengis.net/def/crs/OGC/1.3/CRS84",
+        "http://www.opengis.net/def/crs/EPSG/0/3857",
+    ] {
+        let res = client
+            .get(
+                format!(
+                    "http://{}/collections/ponds/map?bbox=0,0,1,1&crs={}",
+                    addr, crs
+                )
+                .parse()?,
+            )
+            .await?;
+        assert_eq!(200, res.status(), "{crs}");
+        assert_eq!(res.headers()["Content-Type"], PNG);
+    }
+
+    let res = client
+        .get(
+            format!(
+                "http://{}/collections/ponds/map?bbox=0,0,1,1&crs=http://www.opengis.net/def/crs/EPSG/0/2056",
+                addr
+            )
+            .parse()?,
+        )
+        .await?;
+    assert_eq!(400, res.status());
+
+    Ok(())
+}
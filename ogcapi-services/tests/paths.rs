@@ -0,0 +1,433 @@
+mod setup;
+
+use axum::http::{Method, Request};
+use hyper::{Body, Client};
+
+use ogcapi_services::{LandingPageLink, Settings, TrailingSlash};
+use ogcapi_types::common::{
+    media_type::{HTML, JSON, OPEN_API_JSON, OPEN_API_YAML},
+    Collection, Collections, LandingPage,
+};
+
+#[tokio::test]
+async fn trailing_slash() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let mut responses = Vec::new();
+    for path in ["/collections", "/collections/"] {
+        let res = client
+            .get(format!("http://{}{}", addr, path).parse()?)
+            .await?;
+        assert_eq!(200, res.status());
+
+        let body = hyper::body::to_bytes(res.into_body()).await?;
+        let collections: Collections = serde_json::from_slice(&body)?;
+        responses.push(collections.links);
+    }
+
+    assert_eq!(responses[0], responses[1]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn trailing_slash_redirect() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app_with(|state| {
+        state.settings(Settings {
+            trailing_slash: TrailingSlash::Redirect,
+            ..Default::default()
+        })
+    })
+    .await?;
+    let client = Client::new();
+
+    let res = client
+        .get(format!("http://{}/collections/?limit=1", addr).parse()?)
+        .await?;
+    assert_eq!(308, res.status());
+    assert_eq!(res.headers()["Location"], "/collections?limit=1");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn lowercase_collection_ids() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app_with(|state| {
+        state.settings(Settings {
+            lowercase_collection_ids: true,
+            ..Default::default()
+        })
+    })
+    .await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "lower".to_string(),
+        ..Default::default()
+    };
+    let res = client
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("http://{}/collections", addr))
+                .header("Content-Type", JSON)
+                .body(Body::from(serde_json::to_string(&collection)?))?,
+        )
+        .await?;
+    assert_eq!(201, res.status());
+
+    for path in [
+        "/collections/lower",
+        "/collections/LOWER",
+        "/collections/Lower/",
+    ] {
+        let res = client
+            .get(format!("http://{}{}", addr, path).parse()?)
+            .await?;
+        assert_eq!(200, res.status());
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn options() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "options".to_string(),
+        ..Default::default()
+    };
+    let res = client
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("http://{}/collections", addr))
+                .header("Content-Type", JSON)
+                .body(Body::from(serde_json::to_string(&collection)?))?,
+        )
+        .await?;
+    assert_eq!(201, res.status());
+
+    for (path, allow) in [
+        ("/collections/options/items", "GET, HEAD, POST, OPTIONS"),
+        (
+            "/collections/options/items/1",
+            "GET, HEAD, PUT, DELETE, OPTIONS",
+        ),
+    ] {
+        let res = client
+            .request(
+                Request::builder()
+                    .method(Method::OPTIONS)
+                    .uri(format!("http://{}{}", addr, path))
+                    .body(Body::empty())?,
+            )
+            .await?;
+
+        assert_eq!(204, res.status());
+        assert_eq!(res.headers()["Allow"], allow);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn method_not_allowed() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let collection = Collection {
+        id: "immutable".to_string(),
+        ..Default::default()
+    };
+    let res = client
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("http://{}/collections", addr))
+                .header("Content-Type", JSON)
+                .body(Body::from(serde_json::to_string(&collection)?))?,
+        )
+        .await?;
+    assert_eq!(201, res.status());
+
+    let res = client
+        .request(
+            Request::builder()
+                .method(Method::PUT)
+                .uri(format!("http://{}/collections/immutable/items", addr))
+                .header("Content-Type", JSON)
+                .body(Body::from("{}"))?,
+        )
+        .await?;
+
+    assert_eq!(405, res.status());
+    assert_eq!(res.headers()["Allow"], "GET, HEAD, POST, OPTIONS");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn landing_page_links() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app_with(|state| {
+        state.settings(Settings {
+            landing_page_links: vec!["about=https://example.com/docs".parse().unwrap()],
+            ..Default::default()
+        })
+    })
+    .await?;
+    let client = Client::new();
+
+    let res = client.get(format!("http://{}/", addr).parse()?).await?;
+    assert_eq!(200, res.status());
+
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let root: LandingPage = serde_json::from_slice(&body)?;
+
+    assert!(root
+        .links
+        .iter()
+        .any(|l| l.rel == "about" && l.href == "https://example.com/docs"));
+
+    // relative hrefs are rejected
+    assert!("about=/docs".parse::<LandingPageLink>().is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn forwarded_headers() -> anyhow::Result<()> {
+    let self_link = |addr: std::net::SocketAddr,
+                     headers: &'static [(&'static str, &'static str)]| async move {
+        let mut request = Request::builder().uri(format!("http://{}/collections", addr));
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        let res = Client::new().request(request.body(Body::empty())?).await?;
+        assert_eq!(200, res.status());
+
+        let body = hyper::body::to_bytes(res.into_body()).await?;
+        let collections: Collections = serde_json::from_slice(&body)?;
+        let link = collections.links.iter().find(|l| l.rel == "self").unwrap();
+        anyhow::Ok(link.href.clone())
+    };
+
+    let (addr, _) = setup::spawn_app_with(|state| {
+        state.settings(Settings {
+            trust_forwarded_headers: true,
+            ..Default::default()
+        })
+    })
+    .await?;
+
+    let href = self_link(
+        addr,
+        &[
+            ("X-Forwarded-Proto", "https"),
+            ("X-Forwarded-Host", "api.example.com"),
+        ],
+    )
+    .await?;
+    assert_eq!(href, "https://api.example.com/collections");
+
+    // `Forwarded` takes precedence, the last element is set by the proxy in
+    // front of the service
+    let href = self_link(
+        addr,
+        &[
+            (
+                "Forwarded",
+                "for=10.0.0.1;proto=http;host=spoofed.example.com, for=10.0.0.2;proto=https;host=geo.example.com",
+            ),
+            ("X-Forwarded-Host", "api.example.com"),
+        ],
+    )
+    .await?;
+    assert_eq!(href, "https://geo.example.com/collections");
+
+    // values sent by the client are ignored
+    let href = self_link(
+        addr,
+        &[
+            ("X-Forwarded-Proto", "http, https"),
+            ("X-Forwarded-Host", "spoofed.example.com, api.example.com"),
+        ],
+    )
+    .await?;
+    assert_eq!(href, "https://api.example.com/collections");
+
+    // ignored unless trusted
+    let (addr, _) = setup::spawn_app().await?;
+    let href = self_link(
+        addr,
+        &[
+            ("X-Forwarded-Proto", "https"),
+            ("X-Forwarded-Host", "api.example.com"),
+        ],
+    )
+    .await?;
+    assert_eq!(href, format!("http://{}/collections", addr));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn openapi_limits() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app_with(|state| {
+        state.settings(Settings {
+            default_limit: 25,
+            max_limit: 500,
+            ..Default::default()
+        })
+    })
+    .await?;
+    let client = Client::new();
+
+    let res = client.get(format!("http://{}/api", addr).parse()?).await?;
+    assert_eq!(200, res.status());
+
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let openapi: serde_json::Value = serde_json::from_slice(&body)?;
+
+    let limit = &openapi["components"]["parameters"]["limit"];
+    assert_eq!(limit["schema"]["default"], 25);
+    assert_eq!(limit["schema"]["maximum"], 500);
+    assert!(limit["description"]
+        .as_str()
+        .unwrap()
+        .ends_with("Maximum = 500. Default = 25."));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn openapi_yaml() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let res = client.get(format!("http://{}/api", addr).parse()?).await?;
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let json: serde_json::Value = serde_json::from_slice(&body)?;
+
+    let res = client
+        .get(format!("http://{}/api.yaml", addr).parse()?)
+        .await?;
+    assert_eq!(200, res.status());
+    assert_eq!(res.headers()["Content-Type"], OPEN_API_YAML);
+
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let yaml: serde_json::Value = serde_yaml::from_slice(&body)?;
+    assert_eq!(yaml, json);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn api_docs_assets() -> anyhow::Result<()> {
+    let dir = std::env::temp_dir().join(format!("ogcapi-docs-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(dir.join("swagger-ui-bundle.js"), "// bundle")?;
+
+    let (addr, _) = setup::spawn_app_with(|state| {
+        state.settings(Settings {
+            api_docs_assets: Some(dir.clone()),
+            ..Default::default()
+        })
+    })
+    .await?;
+    let client = Client::new();
+
+    let res = client
+        .get(format!("http://{}/swagger", addr).parse()?)
+        .await?;
+    assert_eq!(200, res.status());
+    assert!(res.headers()["Content-Type"]
+        .to_str()?
+        .starts_with("text/html"));
+
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let html = String::from_utf8(body.to_vec())?;
+    assert!(html.contains("url: 'api'"));
+    assert!(html.contains(r#"src="assets/docs/swagger-ui-bundle.js""#));
+    assert!(!html.contains("unpkg.com"));
+
+    let res = client
+        .get(format!("http://{}/assets/docs/swagger-ui-bundle.js", addr).parse()?)
+        .await?;
+    assert_eq!(200, res.status());
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    assert_eq!(&body[..], b"// bundle");
+
+    let res = client
+        .get(format!("http://{}/redoc", addr).parse()?)
+        .await?;
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let html = String::from_utf8(body.to_vec())?;
+    assert!(html.contains(r#"src="assets/docs/redoc.standalone.js""#));
+    assert!(!html.contains("fonts.googleapis.com"));
+
+    std::fs::remove_dir_all(&dir)?;
+
+    Ok(())
+}
+
+#[cfg(feature = "docs-assets")]
+#[tokio::test]
+async fn embedded_api_docs_assets() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    for (file, content_type) in [
+        ("redoc.standalone.js", "application/javascript"),
+        ("swagger-ui-bundle.js", "application/javascript"),
+        ("swagger-ui.css", "text/css"),
+    ] {
+        let res = client
+            .get(format!("http://{}/assets/docs/{}", addr, file).parse()?)
+            .await?;
+        assert_eq!(200, res.status());
+        assert_eq!(res.headers()["Content-Type"], content_type);
+    }
+
+    let res = client
+        .get(format!("http://{}/assets/docs/unknown.js", addr).parse()?)
+        .await?;
+    assert_eq!(404, res.status());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn landing_page_required_links() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let client = Client::new();
+
+    let res = client.get(format!("http://{}/", addr).parse()?).await?;
+    assert_eq!(200, res.status());
+
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let root: LandingPage = serde_json::from_slice(&body)?;
+
+    for (rel, href, media_type) in [
+        ("self", "/", JSON),
+        ("service-desc", "/api", OPEN_API_JSON),
+        ("service-doc", "/swagger", HTML),
+        ("service-doc", "/redoc", HTML),
+        ("conformance", "/conformance", JSON),
+        ("data", "/collections", JSON),
+    ] {
+        let href = format!("http://{}{}", addr, href);
+        let links: Vec<_> = root
+            .links
+            .iter()
+            .filter(|l| l.rel == rel && l.href == href)
+            .collect();
+        assert_eq!(links.len(), 1, "{rel} {href}");
+        assert_eq!(links[0].r#type.as_deref(), Some(media_type), "{rel}");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,555 @@
+#![cfg(feature = "processes")]
+
+mod setup;
+
+use std::{net::SocketAddr, time::Duration};
+
+use axum::{
+    extract::State,
+    http::{Method, Request, StatusCode},
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use hyper::{client::HttpConnector, Body, Client};
+use serde_json::{json, Value};
+use tokio::sync::mpsc::{self, Sender};
+use url::Url;
+
+use ogcapi_services::{AppState, Processor, Settings};
+use ogcapi_types::{
+    common::Link,
+    processes::{Execute, InlineOrRefData, InputValueNoObject, Process, Results},
+};
+
+/// Configurable process for the tests
+#[derive(Clone)]
+struct Fixture {
+    id: &'static str,
+    /// Schema of the outputs
+    outputs: Value,
+    /// Duration of a run
+    delay: Duration,
+    concurrency: Option<usize>,
+    timeout: Option<Duration>,
+    /// Results of a run
+    results: fn() -> Results,
+}
+
+impl Default for Fixture {
+    fn default() -> Self {
+        Self {
+            id: "fixture",
+            outputs: json!({}),
+            delay: Duration::ZERO,
+            concurrency: None,
+            timeout: None,
+            results: Results::default,
+        }
+    }
+}
+
+#[axum::async_trait]
+impl Processor for Fixture {
+    fn id(&self) -> String {
+        self.id.to_string()
+    }
+
+    fn process(&self) -> Process {
+        Process::new(self.id(), "0.1.0", &json!({}), &self.outputs)
+    }
+
+    fn concurrency(&self) -> Option<usize> {
+        self.concurrency
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    async fn execute(
+        &self,
+        _execute: Execute,
+        _state: &AppState,
+        _url: &Url,
+    ) -> ogcapi_services::Result<Response> {
+        tokio::time::sleep(self.delay).await;
+        Ok(StatusCode::NO_CONTENT.into_response())
+    }
+
+    async fn run(&self, _execute: Execute, _state: &AppState) -> ogcapi_services::Result<Results> {
+        tokio::time::sleep(self.delay).await;
+        Ok((self.results)())
+    }
+}
+
+/// Process taking a while to complete
+fn sleeper() -> Fixture {
+    Fixture {
+        id: "sleep",
+        delay: Duration::from_secs(1),
+        ..Default::default()
+    }
+}
+
+/// Byte at position `i` of the written result
+fn written(i: usize) -> u8 {
+    (i % 251) as u8
+}
+
+/// Process writing a large result to a file
+fn writer() -> Fixture {
+    Fixture {
+        id: "write",
+        results: || {
+            let path = std::env::temp_dir().join(format!("{}.bin", uuid::Uuid::new_v4()));
+            let bytes: Vec<u8> = (0..4 * 1024 * 1024).map(written).collect();
+            std::fs::write(&path, bytes).unwrap();
+
+            let mut link = Link::new(Url::from_file_path(&path).unwrap(), "result");
+            link.r#type = Some("application/octet-stream".to_string());
+
+            let mut results = Results::default();
+            results
+                .results
+                .insert("data".to_string(), InlineOrRefData::Link(link));
+            results
+        },
+        ..Default::default()
+    }
+}
+
+/// Executes a process asynchronously, returns the location of the job
+async fn execute(
+    client: &Client<HttpConnector>,
+    addr: &SocketAddr,
+    process: &str,
+    execute: &Value,
+) -> anyhow::Result<String> {
+    let res = client
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("http://{}/processes/{}/execution", addr, process))
+                .header("Content-Type", "application/json")
+                .header("Prefer", "respond-async")
+                .body(Body::from(execute.to_string()))?,
+        )
+        .await?;
+    assert_eq!(201, res.status());
+
+    Ok(res.headers()["Location"].to_str()?.to_string())
+}
+
+/// Executes a process and waits for the job to finish, returns its location
+async fn execute_and_wait(
+    client: &Client<HttpConnector>,
+    addr: &SocketAddr,
+    process: &str,
+) -> anyhow::Result<String> {
+    let location = execute(client, addr, process, &json!({})).await?;
+
+    // the stream of job events ends after the final status
+    let res = client.get(format!("{}/events", location).parse()?).await?;
+    tokio::time::timeout(Duration::from_secs(10), hyper::body::to_bytes(res)).await??;
+
+    Ok(location)
+}
+
+/// Status info of a job
+async fn status(client: &Client<HttpConnector>, location: &str) -> anyhow::Result<Value> {
+    let res = client.get(location.parse()?).await?;
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    Ok(serde_json::from_slice(&body)?)
+}
+
+/// Spawns a server recording the bodies posted to `/success` and `/failed`
+async fn spawn_subscriber() -> anyhow::Result<(SocketAddr, mpsc::Receiver<(String, Value)>)> {
+    async fn record(
+        State(tx): State<Sender<(String, Value)>>,
+        uri: axum::http::Uri,
+        Json(body): Json<Value>,
+    ) -> StatusCode {
+        tx.send((uri.path().to_string(), body)).await.unwrap();
+        StatusCode::OK
+    }
+
+    let (tx, rx) = mpsc::channel(8);
+
+    let router = Router::new()
+        .route("/success", post(record))
+        .route("/failed", post(record))
+        .with_state(tx);
+
+    let server = axum::Server::bind(&"127.0.0.1:0".parse()?).serve(router.into_make_service());
+    let addr = server.local_addr();
+
+    tokio::spawn(server);
+
+    Ok((addr, rx))
+}
+
+#[tokio::test]
+async fn callback() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app().await?;
+    let (subscriber, mut rx) = spawn_subscriber().await?;
+    let client = Client::new();
+
+    let execute = json!({
+        "inputs": { "name": "World" },
+        "subscriber": {
+            "successUri": format!("http://{}/success", subscriber),
+            "failedUri": format!("http://{}/failed", subscriber)
+        }
+    });
+
+    let res = client
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("http://{}/processes/greet/execution", addr))
+                .header("Content-Type", "application/json")
+                .body(Body::from(execute.to_string()))?,
+        )
+        .await?;
+
+    assert_eq!(201, res.status());
+    assert!(res.headers().contains_key("Location"));
+
+    let (path, body) = tokio::time::timeout(Duration::from_secs(10), rx.recv())
+        .await?
+        .expect("callback received");
+
+    assert_eq!(path, "/success");
+    assert_eq!(body["greeting"], "Hello, World!");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn job_events() -> anyhow::Result<()> {
+    let (addr, _) =
+        setup::spawn_app_with(|state| state.processors(vec![Box::new(sleeper())])).await?;
+    let client = Client::new();
+
+    let location = execute(&client, &addr, "sleep", &json!({})).await?;
+
+    let res = client.get(format!("{}/events", location).parse()?).await?;
+    assert_eq!(200, res.status());
+    assert_eq!(res.headers()["Content-Type"], "text/event-stream");
+
+    // the stream ends after the final status
+    let body = tokio::time::timeout(Duration::from_secs(10), hyper::body::to_bytes(res)).await??;
+    let body = String::from_utf8(body.to_vec())?;
+
+    assert!(body.contains(r#""status":"running""#));
+    assert!(body.contains(r#""status":"successful""#));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn process_status_results() -> anyhow::Result<()> {
+    let (addr, _) =
+        setup::spawn_app_with(|state| state.processors(vec![Box::new(sleeper())])).await?;
+    let client = Client::new();
+
+    // process
+    let res = client
+        .get(format!("http://{}/processes/sleep", addr).parse()?)
+        .await?;
+    assert_eq!(200, res.status());
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let process: Value = serde_json::from_slice(&body)?;
+    assert_eq!(process["id"], "sleep");
+
+    let res = client
+        .get(format!("http://{}/processes/unknown", addr).parse()?)
+        .await?;
+    assert_eq!(404, res.status());
+
+    // status
+    let location = execute(&client, &addr, "sleep", &json!({})).await?;
+    let job_id = location.rsplit('/').next().unwrap().to_string();
+
+    let res = client.get(location.parse()?).await?;
+    assert_eq!(200, res.status());
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let info: Value = serde_json::from_slice(&body)?;
+    assert_eq!(info["jobID"], job_id);
+    assert_eq!(info["processID"], "sleep");
+
+    // wait for the job to finish
+    let res = client.get(format!("{}/events", location).parse()?).await?;
+    tokio::time::timeout(Duration::from_secs(10), hyper::body::to_bytes(res)).await??;
+
+    assert_eq!(status(&client, &location).await?["status"], "successful");
+
+    // results
+    let res = client.get(format!("{}/results", location).parse()?).await?;
+    assert_eq!(200, res.status());
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let results: Results = serde_json::from_slice(&body)?;
+    assert!(results.results.is_empty());
+
+    for path in ["jobs/unknown", "jobs/unknown/results"] {
+        let res = client
+            .get(format!("http://{}/{}", addr, path).parse()?)
+            .await?;
+        assert_eq!(404, res.status());
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn output_validation() -> anyhow::Result<()> {
+    // returns a string for its declared numeric output
+    let miscounter = Fixture {
+        id: "count",
+        outputs: json!({ "type": "integer" }),
+        results: || {
+            let mut results = Results::default();
+            results.results.insert(
+                "count".to_string(),
+                InlineOrRefData::InputValueNoObject(InputValueNoObject::String(
+                    "three".to_string(),
+                )),
+            );
+            results
+        },
+        ..Default::default()
+    };
+    let (addr, _) =
+        setup::spawn_app_with(|state| state.processors(vec![Box::new(miscounter)])).await?;
+    let client = Client::new();
+
+    let location = execute_and_wait(&client, &addr, "count").await?;
+
+    let status = status(&client, &location).await?;
+    assert_eq!(status["status"], "failed");
+    let message = status["message"].as_str().unwrap();
+    assert!(message.contains("non-conforming outputs"));
+    assert!(message.contains("`/count`"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn result_range() -> anyhow::Result<()> {
+    let (addr, _) = setup::spawn_app_with(|state| {
+        state
+            .processors(vec![Box::new(writer())])
+            .settings(Settings {
+                results_dir: Some(std::env::temp_dir()),
+                ..Default::default()
+            })
+    })
+    .await?;
+    let client = Client::new();
+
+    let location = execute_and_wait(&client, &addr, "write").await?;
+    let results = format!("{}/results", location);
+
+    let res = client.get(results.parse()?).await?;
+    assert_eq!(200, res.status());
+    assert_eq!(res.headers()["Accept-Ranges"], "bytes");
+    assert_eq!(res.headers()["Content-Length"], "4194304");
+
+    // resume from an offset
+    let res = client
+        .request(
+            Request::builder()
+                .uri(&results)
+                .header("Range", "bytes=1000000-1000999")
+                .body(Body::empty())?,
+        )
+        .await?;
+    assert_eq!(206, res.status());
+    assert_eq!(
+        res.headers()["Content-Range"],
+        "bytes 1000000-1000999/4194304"
+    );
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    assert_eq!(body.len(), 1000);
+    assert!(body
+        .iter()
+        .enumerate()
+        .all(|(i, b)| *b == written(1000000 + i)));
+
+    let res = client
+        .request(
+            Request::builder()
+                .uri(&results)
+                .header("Range", "bytes=5000000-")
+                .body(Body::empty())?,
+        )
+        .await?;
+    assert_eq!(416, res.status());
+
+    // invalid ranges are ignored
+    for range in ["bytes=abc", "bytes=10-5", "bytes=0-1,5-6", "lines=1-2"] {
+        let res = client
+            .request(
+                Request::builder()
+                    .uri(&results)
+                    .header("Range", range)
+                    .body(Body::empty())?,
+            )
+            .await?;
+        assert_eq!(200, res.status(), "{range}");
+        assert_eq!(res.headers()["Content-Length"], "4194304");
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn result_outside_directory() -> anyhow::Result<()> {
+    let dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+    tokio::fs::create_dir(&dir).await?;
+
+    let (addr, _) = setup::spawn_app_with(|state| {
+        state
+            .processors(vec![Box::new(writer())])
+            .settings(Settings {
+                results_dir: Some(dir.clone()),
+                ..Default::default()
+            })
+    })
+    .await?;
+    let client = Client::new();
+
+    let location = execute_and_wait(&client, &addr, "write").await?;
+
+    // the result is written to the parent of the results directory
+    let res = client.get(format!("{}/results", location).parse()?).await?;
+    assert_eq!(404, res.status());
+
+    tokio::fs::remove_dir(&dir).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn dismiss() -> anyhow::Result<()> {
+    let (addr, _) =
+        setup::spawn_app_with(|state| state.processors(vec![Box::new(sleeper())])).await?;
+    let (subscriber, mut rx) = spawn_subscriber().await?;
+    let client = Client::new();
+
+    let body = json!({
+        "subscriber": {
+            "successUri": format!("http://{}/success", subscriber),
+            "failedUri": format!("http://{}/failed", subscriber)
+        }
+    });
+    let location = execute(&client, &addr, "sleep", &body).await?;
+
+    let res = client
+        .request(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri(&location)
+                .body(Body::empty())?,
+        )
+        .await?;
+    assert_eq!(200, res.status());
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let info: Value = serde_json::from_slice(&body)?;
+    assert_eq!(info["status"], "dismissed");
+
+    // the execution is cancelled and never completes
+    let callback = tokio::time::timeout(Duration::from_secs(3), rx.recv()).await;
+    assert!(callback.is_err());
+
+    assert_eq!(status(&client, &location).await?["status"], "dismissed");
+
+    // already dismissed
+    let res = client
+        .request(
+            Request::builder()
+                .method(Method::DELETE)
+                .uri(&location)
+                .body(Body::empty())?,
+        )
+        .await?;
+    assert_eq!(404, res.status());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn concurrency() -> anyhow::Result<()> {
+    // takes a while and may only run once at a time
+    let heavy = Fixture {
+        id: "heavy",
+        delay: Duration::from_secs(2),
+        concurrency: Some(1),
+        ..Default::default()
+    };
+    let (addr, _) = setup::spawn_app_with(|state| state.processors(vec![Box::new(heavy)])).await?;
+    let client = Client::new();
+
+    let mut locations = Vec::new();
+    for _ in 0..3 {
+        locations.push(execute(&client, &addr, "heavy", &json!({})).await?);
+    }
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let mut statuses = Vec::new();
+    for location in &locations {
+        let status = status(&client, location).await?;
+        statuses.push(status["status"].as_str().unwrap().to_string());
+    }
+    statuses.sort();
+
+    // one runs, the extras are queued
+    assert_eq!(statuses, ["accepted", "accepted", "running"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn timeout() -> anyhow::Result<()> {
+    // takes longer than it may
+    let overrunner = Fixture {
+        id: "overrun",
+        delay: Duration::from_secs(60),
+        timeout: Some(Duration::from_millis(200)),
+        ..Default::default()
+    };
+    let (addr, _) =
+        setup::spawn_app_with(|state| state.processors(vec![Box::new(overrunner)])).await?;
+    let client = Client::new();
+
+    let location = execute_and_wait(&client, &addr, "overrun").await?;
+
+    let status = status(&client, &location).await?;
+    assert_eq!(status["status"], "failed");
+    assert!(status["message"].as_str().unwrap().contains("timed out"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn panic() -> anyhow::Result<()> {
+    // panics while producing its results
+    let panicker = Fixture {
+        id: "panic",
+        results: || panic!("broken process"),
+        ..Default::default()
+    };
+    let (addr, _) =
+        setup::spawn_app_with(|state| state.processors(vec![Box::new(panicker)])).await?;
+    let client = Client::new();
+
+    let location = execute_and_wait(&client, &addr, "panic").await?;
+
+    let status = status(&client, &location).await?;
+    assert_eq!(status["status"], "failed");
+    assert!(status["message"].as_str().unwrap().contains("panicked"));
+
+    Ok(())
+}
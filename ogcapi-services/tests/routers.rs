@@ -0,0 +1,128 @@
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+
+use axum::{
+    body::Body,
+    http::{Method, Request},
+};
+use sqlx::postgres::PgPoolOptions;
+use tower::ServiceExt;
+
+use ogcapi_drivers::{postgres::Db, CollectionTransactions};
+use ogcapi_services::{AppState, Drivers, OpenAPI};
+use ogcapi_types::common::{media_type::JSON, Collection, Collections, Query};
+
+/// Collections kept in memory
+#[derive(Default)]
+struct MockCollections(Mutex<HashMap<String, Collection>>);
+
+#[axum::async_trait]
+impl CollectionTransactions for MockCollections {
+    async fn create_collection(&self, collection: &Collection) -> anyhow::Result<String> {
+        let mut collections = self.0.lock().unwrap();
+        collections.insert(collection.id.clone(), collection.clone());
+        Ok(collection.id.clone())
+    }
+
+    async fn read_collection(&self, id: &str) -> anyhow::Result<Option<Collection>> {
+        Ok(self.0.lock().unwrap().get(id).cloned())
+    }
+
+    async fn update_collection(&self, collection: &Collection) -> anyhow::Result<()> {
+        self.create_collection(collection).await.map(|_| ())
+    }
+
+    async fn delete_collection(&self, id: &str) -> anyhow::Result<()> {
+        self.0.lock().unwrap().remove(id);
+        Ok(())
+    }
+
+    async fn list_collections(&self, _query: &Query) -> anyhow::Result<Collections> {
+        let collections = self.0.lock().unwrap().values().cloned().collect();
+        Ok(Collections::new(collections))
+    }
+}
+
+#[tokio::test]
+async fn collections_router() -> anyhow::Result<()> {
+    // never connects, all requests are served by the mock
+    let db = Db::from(PgPoolOptions::new().connect_lazy("postgresql://localhost/unused")?);
+
+    let mut drivers = Drivers::from(db.clone());
+    drivers.collections = Box::<MockCollections>::default();
+
+    let state = AppState::new_with(db, OpenAPI::default())
+        .await
+        .drivers(drivers);
+    let router = ogcapi_services::routers::collections(&state).with_state(state);
+
+    let collection = Collection {
+        id: "lakes".to_string(),
+        ..Default::default()
+    };
+    let res = router
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method(Method::POST)
+                .uri("http://localhost/collections")
+                .header("Content-Type", JSON)
+                .body(Body::from(serde_json::to_string(&collection)?))?,
+        )
+        .await?;
+    assert_eq!(201, res.status());
+    assert_eq!(
+        res.headers()["Location"],
+        "http://localhost/collections/lakes"
+    );
+
+    let res = router
+        .clone()
+        .oneshot(Request::get("http://localhost/collections/lakes").body(Body::empty())?)
+        .await?;
+    assert_eq!(200, res.status());
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let collection: Collection = serde_json::from_slice(&body)?;
+    assert_eq!(collection.id, "lakes");
+
+    let res = router
+        .clone()
+        .oneshot(Request::get("http://localhost/collections").body(Body::empty())?)
+        .await?;
+    assert_eq!(200, res.status());
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let collections: Collections = serde_json::from_slice(&body)?;
+    assert_eq!(collections.collections.len(), 1);
+
+    // only the collections routes are mounted
+    let res = router
+        .oneshot(Request::get("http://localhost/conformance").body(Body::empty())?)
+        .await?;
+    assert_eq!(404, res.status());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn pool_exhaustion() -> anyhow::Result<()> {
+    dotenvy::dotenv().ok();
+
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .acquire_timeout(Duration::from_millis(100))
+        .connect(&std::env::var("DATABASE_URL")?)
+        .await?;
+
+    // hold the only connection
+    let _conn = pool.acquire().await?;
+
+    let state = AppState::new_with(Db::from(pool), OpenAPI::default()).await;
+    let router = ogcapi_services::routers::collections(&state).with_state(state);
+
+    let res = router
+        .oneshot(Request::get("http://localhost/collections").body(Body::empty())?)
+        .await?;
+    assert_eq!(503, res.status());
+    assert_eq!(res.headers()["Retry-After"], "1");
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+mod setup;
+
+use serde_json::json;
+
+use ogcapi::import::seed;
+use ogcapi_types::features::FeatureCollection;
+
+#[tokio::test]
+async fn seed_directory() -> anyhow::Result<()> {
+    let (addr, database_url) = setup::spawn_app().await?;
+    let client = hyper::Client::new();
+
+    // write two feature collections into a temporary directory
+    let dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+    std::fs::create_dir_all(&dir)?;
+
+    let point = |x: f64, y: f64| {
+        json!({
+            "type": "Feature",
+            "properties": {},
+            "geometry": { "type": "Point", "coordinates": [x, y] }
+        })
+    };
+
+    std::fs::write(
+        dir.join("lakes.geojson"),
+        json!({ "type": "FeatureCollection", "features": [point(8.0, 47.0)] }).to_string(),
+    )?;
+    std::fs::write(
+        dir.join("peaks.geojson"),
+        json!({
+            "type": "FeatureCollection",
+            "features": [point(7.6, 45.9), point(7.8, 45.9), point(8.0, 46.5)]
+        })
+        .to_string(),
+    )?;
+
+    let ids = seed::load(&dir, &database_url, ogcapi_drivers::postgres::SCHEMA).await?;
+    std::fs::remove_dir_all(&dir)?;
+
+    assert_eq!(ids, vec!["lakes", "peaks"]);
+
+    for (id, count) in [("lakes", 1), ("peaks", 3)] {
+        let res = client
+            .get(format!("http://{}/collections/{}/items", addr, id).parse()?)
+            .await?;
+        assert_eq!(200, res.status());
+
+        let body = hyper::body::to_bytes(res.into_body()).await?;
+        let fc: FeatureCollection = serde_json::from_slice(&body)?;
+        assert_eq!(fc.number_matched, Some(count));
+    }
+
+    Ok(())
+}
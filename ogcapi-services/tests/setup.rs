@@ -3,10 +3,18 @@ use std::net::SocketAddr;
 use url::Url;
 use uuid::Uuid;
 
-use ogcapi_services::{Config, ConfigParser};
+use ogcapi_services::{AppState, Config, ConfigParser};
 
 #[allow(dead_code)]
 pub async fn spawn_app() -> anyhow::Result<(SocketAddr, Url)> {
+    spawn_app_with(|state| state).await
+}
+
+/// Spawns the app with a customized state
+#[allow(dead_code)]
+pub async fn spawn_app_with(
+    customize: impl FnOnce(AppState) -> AppState,
+) -> anyhow::Result<(SocketAddr, Url)> {
     dotenvy::dotenv().ok();
 
     // ogcapi_services::telemetry::init();
@@ -17,6 +25,11 @@ pub async fn spawn_app() -> anyhow::Result<(SocketAddr, Url)> {
 
     let state = ogcapi_services::AppState::new_from(&config).await;
 
+    #[cfg(feature = "processes")]
+    let state = state.processors(vec![Box::new(ogcapi_services::Greeter)]);
+
+    let state = customize(state);
+
     let service = ogcapi_services::Service::new_with(&config, state).await;
 
     let addr = service.local_addr()?;
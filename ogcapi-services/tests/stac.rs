@@ -0,0 +1,115 @@
+mod setup;
+
+#[cfg(feature = "stac")]
+#[tokio::test]
+async fn bbox_and_intersects() -> anyhow::Result<()> {
+    use axum::http::{Method, Request};
+    use hyper::Body;
+    use serde_json::json;
+
+    use ogcapi_drivers::{postgres::Db, CollectionTransactions, FeatureTransactions};
+    use ogcapi_types::{
+        common::{media_type::JSON, Collection},
+        features::FeatureCollection,
+    };
+
+    let (addr, database_url) = setup::spawn_app().await?;
+    let client = hyper::Client::new();
+
+    let db = Db::setup(&database_url).await?;
+    let collection = Collection {
+        id: "towers".to_string(),
+        ..Default::default()
+    };
+    db.create_collection(&collection).await?;
+
+    for (name, x) in [("west", 1.0), ("middle", 2.2), ("east", 3.0)] {
+        let feature = serde_json::from_value(json!({
+            "collection": "towers",
+            "type": "Feature",
+            "properties": { "name": name },
+            "geometry": { "type": "Point", "coordinates": [x, 1.0] }
+        }))?;
+        db.create_feature(&feature).await?;
+    }
+
+    // bbox covers west and middle, the polygon middle and east
+    let body = json!({
+        "collections": ["towers"],
+        "bbox": [0.0, 0.0, 2.5, 4.0],
+        "intersects": {
+            "type": "Polygon",
+            "coordinates": [[[2.0, 0.0], [4.0, 0.0], [4.0, 4.0], [2.0, 4.0], [2.0, 0.0]]]
+        }
+    });
+
+    let res = client
+        .request(
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("http://{addr}/search"))
+                .header("Content-Type", JSON)
+                .body(Body::from(body.to_string()))?,
+        )
+        .await?;
+    assert_eq!(200, res.status());
+
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let fc: FeatureCollection = serde_json::from_slice(&body)?;
+
+    assert_eq!(fc.number_matched, Some(1));
+    assert_eq!(
+        fc.features[0].properties.as_ref().unwrap()["name"],
+        "middle"
+    );
+
+    Ok(())
+}
+
+#[cfg(feature = "stac")]
+#[tokio::test]
+async fn intersects_vertex_limit() -> anyhow::Result<()> {
+    use axum::http::{Method, Request};
+    use hyper::Body;
+    use serde_json::json;
+
+    use ogcapi_services::Settings;
+    use ogcapi_types::common::media_type::JSON;
+
+    let (addr, _) = setup::spawn_app_with(|state| {
+        state.settings(Settings {
+            max_filter_vertices: 5,
+            ..Default::default()
+        })
+    })
+    .await?;
+    let client = hyper::Client::new();
+
+    let square = json!([[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0], [0.0, 0.0]]);
+    let hexagon = json!([
+        [0.0, 0.0],
+        [1.0, 0.0],
+        [1.5, 0.5],
+        [1.0, 1.0],
+        [0.0, 1.0],
+        [-0.5, 0.5],
+        [0.0, 0.0]
+    ]);
+
+    for (ring, status) in [(square, 200), (hexagon, 400)] {
+        let body = json!({ "intersects": { "type": "Polygon", "coordinates": [ring] } });
+
+        let res = client
+            .request(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri(format!("http://{addr}/search"))
+                    .header("Content-Type", JSON)
+                    .body(Body::from(body.to_string()))?,
+            )
+            .await?;
+        assert_eq!(status, res.status());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+mod setup;
+
+#[cfg(feature = "tiles")]
+#[tokio::test]
+async fn png_tile() -> anyhow::Result<()> {
+    use axum::http::Request;
+    use hyper::Body;
+    use serde_json::json;
+
+    use ogcapi_drivers::{postgres::Db, CollectionTransactions, FeatureTransactions};
+    use ogcapi_types::common::{media_type::PNG, Collection};
+
+    let (addr, database_url) = setup::spawn_app().await?;
+    let client = hyper::Client::new();
+
+    let db = Db::setup(&database_url).await?;
+    let collection = Collection {
+        id: "parks".to_string(),
+        ..Default::default()
+    };
+    db.create_collection(&collection).await?;
+
+    let feature = serde_json::from_value(json!({
+        "collection": "parks",
+        "type": "Feature",
+        "properties": { "name": "Bern" },
+        "geometry": {
+            "type": "Polygon",
+            "coordinates": [[[7.0, 46.0], [8.0, 46.0], [8.0, 47.0], [7.0, 47.0], [7.0, 46.0]]]
+        }
+    }))?;
+    db.create_feature(&feature).await?;
+
+    let mut tiles = Vec::new();
+    for (path, accept) in [
+        // by extension, covering the park
+        ("1/0/1.png", "*/*"),
+        // by accept header, empty
+        ("1/1/0", PNG),
+    ] {
+        let res = client
+            .request(
+                Request::builder()
+                    .uri(format!(
+                        "http://{}/collections/parks/tiles/WebMercatorQuad/{}",
+                        addr, path
+                    ))
+                    .header("Accept", accept)
+                    .body(Body::empty())?,
+            )
+            .await?;
+        assert_eq!(200, res.status());
+        assert_eq!(res.headers()["Content-Type"], PNG);
+
+        let body = hyper::body::to_bytes(res.into_body()).await?;
+        assert!(body.starts_with(b"\x89PNG"));
+        tiles.push(body);
+    }
+
+    // the rendered park makes the tile differ from an empty one
+    assert_ne!(tiles[0], tiles[1]);
+
+    for path in [
+        // unknown tile matrix set
+        "Unknown/1/0/0.png",
+        // unknown tile matrix
+        "WebMercatorQuad/99/0/0.png",
+        "WebMercatorQuad/one/0/0.png",
+        // outside of the tile matrix
+        "WebMercatorQuad/1/5/5.png",
+    ] {
+        let res = client
+            .get(format!("http://{}/collections/parks/tiles/{}", addr, path).parse()?)
+            .await?;
+        assert_eq!(404, res.status(), "{path}");
+    }
+
+    Ok(())
+}
@@ -1,7 +1,10 @@
 use std::{fmt, str};
 
+use geojson::{Geometry, Value};
 use serde::{Deserialize, Serialize};
 
+use super::Crs;
+
 type Bbox2D = [f64; 4];
 type Bbox3D = [f64; 6];
 
@@ -12,6 +15,101 @@ pub enum Bbox {
     Bbox3D(Bbox3D),
 }
 
+impl Bbox {
+    /// Computes the envelope of the geometries, in three dimensions if all
+    /// positions have a height
+    pub fn envelope<'a>(geometries: impl IntoIterator<Item = &'a Geometry>) -> Option<Bbox> {
+        let mut positions = Vec::new();
+        for geometry in geometries {
+            collect_positions(&geometry.value, &mut positions);
+        }
+
+        if positions.is_empty() {
+            return None;
+        }
+
+        let dimensions = if positions.iter().all(|p| p.len() > 2) {
+            3
+        } else {
+            2
+        };
+
+        let mut min = vec![f64::INFINITY; dimensions];
+        let mut max = vec![f64::NEG_INFINITY; dimensions];
+        for position in positions {
+            for (i, c) in position.iter().take(dimensions).enumerate() {
+                min[i] = min[i].min(*c);
+                max[i] = max[i].max(*c);
+            }
+        }
+
+        Bbox::try_from([min, max].concat().as_slice()).ok()
+    }
+
+    /// Tests whether two bboxes overlap, touching counts as overlap. Heights
+    /// are only compared if both are three dimensional and a bbox with a
+    /// lower left east of its upper right crosses the antimeridian.
+    pub fn intersects(&self, other: &Bbox) -> bool {
+        let overlaps = |a: (f64, f64), b: (f64, f64)| a.0 <= b.1 && b.0 <= a.1;
+        let spans = |min: f64, max: f64| {
+            if min <= max {
+                vec![(min, max)]
+            } else {
+                vec![(min, 180.0), (-180.0, max)]
+            }
+        };
+
+        let (a, b) = (self.bounds(), other.bounds());
+
+        let x = spans(a.0[0], a.1[0])
+            .into_iter()
+            .any(|a| spans(b.0[0], b.1[0]).into_iter().any(|b| overlaps(a, b)));
+        let y = overlaps((a.0[1], a.1[1]), (b.0[1], b.1[1]));
+        let z = match (a.0.get(2), b.0.get(2)) {
+            (Some(_), Some(_)) => overlaps((a.0[2], a.1[2]), (b.0[2], b.1[2])),
+            _ => true,
+        };
+
+        x && y && z
+    }
+
+    /// Planar area in the units of the CRS, bboxes of a geographic CRS
+    /// crossing the antimeridian wrap around
+    pub fn area(&self, crs: &Crs) -> f64 {
+        let (min, max) = self.bounds();
+        let width = if min[0] <= max[0] {
+            max[0] - min[0]
+        } else if crs.is_geographic() {
+            max[0] - min[0] + 360.0
+        } else {
+            min[0] - max[0]
+        };
+        width * (max[1] - min[1])
+    }
+
+    /// Lower left and upper right corner
+    fn bounds(&self) -> (&[f64], &[f64]) {
+        match self {
+            Bbox::Bbox2D(bbox) => (&bbox[..2], &bbox[2..]),
+            Bbox::Bbox3D(bbox) => (&bbox[..3], &bbox[3..]),
+        }
+    }
+}
+
+fn collect_positions<'a>(value: &'a Value, positions: &mut Vec<&'a Vec<f64>>) {
+    match value {
+        Value::Point(p) => positions.push(p),
+        Value::MultiPoint(l) | Value::LineString(l) => positions.extend(l),
+        Value::MultiLineString(p) | Value::Polygon(p) => positions.extend(p.iter().flatten()),
+        Value::MultiPolygon(mp) => positions.extend(mp.iter().flatten().flatten()),
+        Value::GeometryCollection(gc) => {
+            for geometry in gc {
+                collect_positions(&geometry.value, positions);
+            }
+        }
+    }
+}
+
 impl fmt::Display for Bbox {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -113,4 +211,79 @@ mod tests {
             serde_json::to_string(&bbox).unwrap()
         );
     }
+
+    #[test]
+    fn envelope() {
+        let polygon = Geometry::new(Value::Polygon(vec![
+            vec![
+                vec![7.0, 46.0],
+                vec![9.5, 46.5],
+                vec![8.0, 47.8],
+                vec![6.5, 47.0],
+                vec![7.0, 46.0],
+            ],
+            vec![
+                vec![7.5, 46.8],
+                vec![8.0, 46.8],
+                vec![8.0, 47.0],
+                vec![7.5, 46.8],
+            ],
+        ]));
+        assert_eq!(
+            Bbox::envelope([&polygon]),
+            Some(Bbox::Bbox2D([6.5, 46.0, 9.5, 47.8]))
+        );
+
+        let point = Geometry::new(Value::Point(vec![10.0, 45.0, 300.0]));
+        assert_eq!(
+            Bbox::envelope([&point]),
+            Some(Bbox::Bbox3D([10.0, 45.0, 300.0, 10.0, 45.0, 300.0]))
+        );
+        assert_eq!(
+            Bbox::envelope([&polygon, &point]),
+            Some(Bbox::Bbox2D([6.5, 45.0, 10.0, 47.8]))
+        );
+
+        assert_eq!(Bbox::envelope(Vec::<&Geometry>::new()), None);
+    }
+
+    #[test]
+    fn intersects() {
+        let bbox = Bbox::Bbox2D([6.0, 46.0, 10.0, 48.0]);
+
+        assert!(bbox.intersects(&Bbox::Bbox2D([7.0, 47.0, 8.0, 47.5])));
+        assert!(bbox.intersects(&Bbox::Bbox2D([9.0, 40.0, 12.0, 46.5])));
+        assert!(bbox.intersects(&Bbox::Bbox2D([10.0, 48.0, 11.0, 49.0])));
+        assert!(!bbox.intersects(&Bbox::Bbox2D([10.1, 46.0, 12.0, 48.0])));
+        assert!(!bbox.intersects(&Bbox::Bbox2D([6.0, 48.1, 10.0, 49.0])));
+
+        // heights
+        let bbox = Bbox::Bbox3D([0.0, 0.0, 0.0, 1.0, 1.0, 100.0]);
+        assert!(bbox.intersects(&Bbox::Bbox3D([0.0, 0.0, 50.0, 1.0, 1.0, 150.0])));
+        assert!(!bbox.intersects(&Bbox::Bbox3D([0.0, 0.0, 150.0, 1.0, 1.0, 200.0])));
+        assert!(bbox.intersects(&Bbox::Bbox2D([0.5, 0.5, 2.0, 2.0])));
+
+        // antimeridian
+        let bbox = Bbox::Bbox2D([170.0, -10.0, -170.0, 10.0]);
+        assert!(bbox.intersects(&Bbox::Bbox2D([-175.0, 0.0, -172.0, 5.0])));
+        assert!(bbox.intersects(&Bbox::Bbox2D([172.0, 0.0, 175.0, 5.0])));
+        assert!(!bbox.intersects(&Bbox::Bbox2D([0.0, 0.0, 10.0, 5.0])));
+    }
+
+    #[test]
+    fn area() {
+        let crs = Crs::default();
+        assert_eq!(Bbox::Bbox2D([6.0, 46.0, 10.0, 48.0]).area(&crs), 8.0);
+        assert_eq!(
+            Bbox::Bbox3D([6.0, 46.0, 0.0, 10.0, 48.0, 100.0]).area(&crs),
+            8.0
+        );
+        assert_eq!(Bbox::Bbox2D([170.0, -10.0, -170.0, 10.0]).area(&crs), 400.0);
+
+        // no wrapping in projected CRS
+        assert_eq!(
+            Bbox::Bbox2D([10.0, 0.0, 5.0, 2.0]).area(&Crs::from_epsg(3857)),
+            10.0
+        );
+    }
 }
@@ -1,8 +1,10 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use serde_with::DisplayFromStr;
 
-use crate::common::{Crs, Extent, Links};
+use crate::common::{Crs, Extent, Links, Translation};
 
 pub const CRS_REF: &str = "#/crs";
 
@@ -19,6 +21,8 @@ pub struct Collection {
     pub id: String,
     pub title: Option<String>,
     pub description: Option<String>,
+    /// Title and description in other languages, keyed by language tag.
+    pub translations: Option<HashMap<String, Translation>>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub keywords: Vec<String>,
     /// Attribution for the collection.
@@ -26,6 +30,8 @@ pub struct Collection {
     pub extent: Option<Extent>,
     /// An indicator about the type of the items in the collection.
     pub item_type: Option<String>,
+    /// Number of items in the collection, possibly estimated.
+    pub item_count: Option<u64>,
     /// The list of coordinate reference systems supported by the API; the first item is the default coordinate reference system.
     #[serde(default)]
     #[serde_as(as = "Vec<DisplayFromStr>")]
@@ -34,6 +40,35 @@ pub struct Collection {
     #[serde_as(as = "Option<DisplayFromStr>")]
     pub storage_crs: Option<Crs>,
     pub storage_crs_coordinate_epoch: Option<f32>,
+    /// Name of the geometry column in the storage backend.
+    pub geometry_column: Option<String>,
+    /// Further geometry columns of an existing table, which item queries can
+    /// select by the `geometry-column` parameter.
+    pub geometry_columns: Option<Vec<String>>,
+    /// Existing table serving the items of a read-only collection.
+    pub table: Option<String>,
+    /// Name of the id column of an existing table, defaults to `id`.
+    pub id_column: Option<String>,
+    /// Strategy for assigning ids to new items, by default client supplied ids
+    /// are used if present and UUIDs generated otherwise.
+    pub id_strategy: Option<IdStrategy>,
+    /// Properties searched by the `q` parameter, defaults to all string properties.
+    pub search_properties: Option<Vec<String>>,
+    /// Access policy of the collection and its items, if authentication is
+    /// enabled. Defaults to `private`.
+    pub access: Option<Access>,
+    /// Properties items are sorted by if the query has no `sortby`, prefixed
+    /// with `-` for descending order.
+    pub default_sort: Option<Vec<String>>,
+    /// Names the properties of the items are exposed under, keyed by their
+    /// stored name. Unmapped properties keep their name.
+    pub property_names: Option<HashMap<String, String>>,
+    /// Set the `created` and `updated` properties of items on insert and
+    /// update, client supplied values are ignored.
+    pub timestamps: Option<bool>,
+    /// Size of the grid the coordinates of items are snapped to when stored,
+    /// in units of the storage CRS.
+    pub grid_size: Option<f64>,
     #[serde(default)]
     pub links: Links,
     /// Detailed information relevant to individual query types
@@ -93,6 +128,28 @@ fn collection() -> String {
     "Collection".to_string()
 }
 
+/// Strategy for assigning ids to new items of a collection
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum IdStrategy {
+    /// Generated random UUID, client supplied ids are rejected
+    Uuid,
+    /// Generated increasing integer, client supplied ids are rejected
+    Sequential,
+    /// Client supplied id, required
+    Client,
+}
+
+/// Access policy of a collection
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Access {
+    /// Readable without authentication
+    Public,
+    /// Readable with a valid API key
+    Private,
+}
+
 #[allow(clippy::derivable_impls)]
 impl Default for Collection {
     fn default() -> Self {
@@ -102,13 +159,26 @@ impl Default for Collection {
             id: Default::default(),
             title: Default::default(),
             description: Default::default(),
+            translations: Default::default(),
             keywords: Default::default(),
             attribution: Default::default(),
             extent: Default::default(),
             item_type: Default::default(),
+            item_count: Default::default(),
             crs: vec![Crs::default()],
             storage_crs: Default::default(),
             storage_crs_coordinate_epoch: Default::default(),
+            geometry_column: Default::default(),
+            geometry_columns: Default::default(),
+            table: Default::default(),
+            id_column: Default::default(),
+            id_strategy: Default::default(),
+            search_properties: Default::default(),
+            access: Default::default(),
+            default_sort: Default::default(),
+            property_names: Default::default(),
+            timestamps: Default::default(),
+            grid_size: Default::default(),
             links: Default::default(),
             #[cfg(feature = "edr")]
             data_queries: Default::default(),
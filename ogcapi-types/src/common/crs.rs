@@ -77,6 +77,15 @@ impl Crs {
         }
     }
 
+    /// Whether the coordinates are longitudes and latitudes, known for the
+    /// WGS 84, ETRS89 and NAD83 based CRS
+    pub fn is_geographic(&self) -> bool {
+        match self.authority {
+            Authority::OGC => matches!(self.code.as_str(), "CRS84" | "CRS84h"),
+            Authority::EPSG => matches!(self.code.as_str(), "4326" | "4979" | "4258" | "4269"),
+        }
+    }
+
     /// "AUTHORITY:CODE", like "EPSG:25832"
     pub fn as_known_crs(&self) -> String {
         format!("{}:{}", self.authority, self.code)
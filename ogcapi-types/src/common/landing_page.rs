@@ -1,10 +1,12 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 
 #[cfg(feature = "edr")]
 use crate::edr::{Contact, Provider};
 
-use super::Links;
+use super::{Links, Translation};
 
 /// The Landing page is the entry point of a OGC API
 ///
@@ -37,6 +39,8 @@ pub struct LandingPage {
     pub title: Option<String>,
     /// A textual description of the API
     pub description: Option<String>,
+    /// Title and description in other languages, keyed by language tag.
+    pub translations: Option<HashMap<String, Translation>>,
     /// The `attribution` should be short and intended for presentation to a
     /// user, for example, in a corner of a map. Parts of the text can be links
     /// to other resources if additional information is needed. The string can
@@ -73,6 +77,7 @@ impl Default for LandingPage {
             id: Default::default(),
             title: Default::default(),
             description: Default::default(),
+            translations: Default::default(),
             attribution: Default::default(),
             links: Default::default(),
             #[cfg(feature = "edr")]
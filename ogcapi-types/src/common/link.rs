@@ -39,12 +39,18 @@ impl Link {
         self
     }
 
-    /// Sets the language of the Link and returns the Value
-    pub fn language(mut self, language: impl ToString) -> Link {
+    /// Sets the language of the referenced resource and returns the Value
+    pub fn hreflang(mut self, language: impl ToString) -> Link {
         self.hreflang = Some(language.to_string());
         self
     }
 
+    /// Sets the language of the Link and returns the Value
+    #[deprecated(note = "renamed to `hreflang`")]
+    pub fn language(self, language: impl ToString) -> Link {
+        self.hreflang(language)
+    }
+
     /// Sets the title of the Link and returns the Value
     pub fn title(mut self, title: impl ToString) -> Link {
         self.title = Some(title.to_string());
@@ -46,7 +46,15 @@ impl Linked for Links {
         for link in others {
             self.iter_mut()
                 .find(|l| l.rel == link.rel)
-                .map(|l| l.href = link.href.to_owned())
+                .map(|l| {
+                    l.href = link.href.to_owned();
+                    if link.r#type.is_some() {
+                        l.r#type = link.r#type.to_owned();
+                    }
+                    if link.hreflang.is_some() {
+                        l.hreflang = link.hreflang.to_owned();
+                    }
+                })
                 .unwrap_or_else(|| self.push(link.to_owned()));
         }
     }
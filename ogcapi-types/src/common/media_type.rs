@@ -24,6 +24,9 @@ pub const OPEN_API_YAML: &str = "application/vnd.oai.openapi+yaml;version=3.0";
 /// Media Type for `application/vnd.mapbox.style+json`
 pub const MAPBOX_STYLE: &str = "application/vnd.mapbox.style+json";
 
+/// Media Type for `application/vnd.mapbox-vector-tile`
+pub const MVT: &str = "application/vnd.mapbox-vector-tile";
+
 /// Media Type for `image/png`
 pub const PNG: &str = "image/png";
 
@@ -32,3 +35,23 @@ pub const PROBLEM_JSON: &str = "application/problem+json";
 
 /// Media Type for `application/vnd.ogc.sld+xml;version=1.0`
 pub const SLD: &str = "application/vnd.ogc.sld+xml;version=1.0";
+
+/// Media Type for `application/vnd.ogc.fg+json`
+pub const JSON_FG: &str = "application/vnd.ogc.fg+json";
+
+/// Profile of `application/geo+json` for JSON-FG
+pub const JSON_FG_PROFILE: &str = "http://www.opengis.net/def/profile/OGC/0/jsonfg";
+
+/// Guesses the Media Type of a resource from the extension of its path
+pub fn from_extension(path: &str) -> Option<&'static str> {
+    let extension = path.rsplit_once('.')?.1.to_ascii_lowercase();
+    match extension.as_str() {
+        "json" => Some(JSON),
+        "geojson" => Some(GEO_JSON),
+        "html" | "htm" => Some(HTML),
+        "png" => Some(PNG),
+        "mvt" | "pbf" => Some(MVT),
+        "sld" => Some(SLD),
+        _ => None,
+    }
+}
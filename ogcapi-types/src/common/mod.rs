@@ -12,6 +12,8 @@ pub mod link_rel;
 mod links;
 pub mod media_type;
 mod query;
+mod transform;
+mod translation;
 
 pub use bbox::Bbox;
 pub use collection::*;
@@ -25,3 +27,5 @@ pub use landing_page::LandingPage;
 pub use link::Link;
 pub use links::{Linked, Links};
 pub use query::Query;
+pub use transform::{from_web_mercator, to_web_mercator, transform};
+pub use translation::Translation;
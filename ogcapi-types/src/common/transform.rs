@@ -0,0 +1,155 @@
+use geojson::{Geometry, Value};
+
+use super::Crs;
+
+/// Semi-major axis of the WGS 84 ellipsoid, used as sphere radius by Web Mercator
+const RADIUS: f64 = 6_378_137.0;
+
+/// Latitude beyond which Web Mercator is undefined, making the projected
+/// extent square
+const MAX_LATITUDE: f64 = 85.051_128_779_806_59;
+
+/// Projects a longitude/latitude position (EPSG:4326) to Web Mercator (EPSG:3857)
+pub fn to_web_mercator(position: &[f64]) -> [f64; 2] {
+    let lat = position[1].clamp(-MAX_LATITUDE, MAX_LATITUDE).to_radians();
+    [
+        RADIUS * position[0].to_radians(),
+        RADIUS * (std::f64::consts::FRAC_PI_4 + lat / 2.0).tan().ln(),
+    ]
+}
+
+/// Unprojects a Web Mercator position (EPSG:3857) to longitude/latitude (EPSG:4326)
+pub fn from_web_mercator(position: &[f64]) -> [f64; 2] {
+    [
+        (position[0] / RADIUS).to_degrees(),
+        (2.0 * (position[1] / RADIUS).exp().atan() - std::f64::consts::FRAC_PI_2).to_degrees(),
+    ]
+}
+
+/// Transforms a geometry between the supported CRS pairs in place, for now
+/// longitude/latitude and Web Mercator. Positions keep additional ordinates,
+/// geometries with positions of less than two ordinates are left unchanged
+/// and rejected.
+pub fn transform(geometry: &mut Geometry, from: &Crs, to: &Crs) -> Result<(), String> {
+    let project: fn(&[f64]) -> [f64; 2] = match (srid(from)?, srid(to)?) {
+        (from, to) if from == to => return Ok(()),
+        (4326, 3857) => to_web_mercator,
+        (3857, 4326) => from_web_mercator,
+        _ => {
+            return Err(format!(
+                "Unsupported transformation from `{from}` to `{to}`"
+            ))
+        }
+    };
+
+    let mut positions = Vec::new();
+    collect_positions(&mut geometry.value, &mut positions);
+    if let Some(p) = positions.iter().find(|p| p.len() < 2) {
+        return Err(format!(
+            "Invalid position `{p:?}`, expected at least two ordinates"
+        ));
+    }
+
+    for p in positions {
+        let [x, y] = project(p);
+        p[0] = x;
+        p[1] = y;
+    }
+    geometry.bbox = None;
+
+    Ok(())
+}
+
+fn srid(crs: &Crs) -> Result<i32, String> {
+    match crs.as_known_crs().as_str() {
+        "OGC:CRS84" | "EPSG:4326" => Ok(4326),
+        "EPSG:3857" => Ok(3857),
+        _ => Err(format!("Unsupported CRS `{crs}`")),
+    }
+}
+
+fn collect_positions<'a>(value: &'a mut Value, positions: &mut Vec<&'a mut Vec<f64>>) {
+    match value {
+        Value::Point(p) => positions.push(p),
+        Value::MultiPoint(ps) | Value::LineString(ps) => positions.extend(ps),
+        Value::MultiLineString(ls) | Value::Polygon(ls) => {
+            positions.extend(ls.iter_mut().flatten())
+        }
+        Value::MultiPolygon(ps) => positions.extend(ps.iter_mut().flatten().flatten()),
+        Value::GeometryCollection(gs) => gs
+            .iter_mut()
+            .for_each(|g| collect_positions(&mut g.value, positions)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: [f64; 2], b: [f64; 2], tolerance: f64) {
+        assert!(
+            (a[0] - b[0]).abs() < tolerance && (a[1] - b[1]).abs() < tolerance,
+            "{a:?} != {b:?}"
+        );
+    }
+
+    #[test]
+    fn web_mercator() {
+        // EPSG Guidance Note 7-2, Popular Visualisation Pseudo-Mercator example
+        let lonlat = [-(100.0 + 20.0 / 60.0), 24.0 + 22.0 / 60.0 + 54.433 / 3600.0];
+        let xy = [-11_169_055.58, 2_800_000.00];
+
+        assert_close(to_web_mercator(&lonlat), xy, 0.01);
+        assert_close(from_web_mercator(&xy), lonlat, 1e-7);
+
+        // corners of the projected extent
+        assert_close(
+            to_web_mercator(&[180.0, MAX_LATITUDE]),
+            [20_037_508.342_789_244, 20_037_508.342_789_244],
+            1e-6,
+        );
+        assert_close(to_web_mercator(&[0.0, 0.0]), [0.0, 0.0], 1e-9);
+    }
+
+    #[test]
+    fn geometry() {
+        let wgs84 = Crs::default();
+        let mercator = Crs::from_epsg(3857);
+
+        let mut geometry = Geometry::new(Value::Polygon(vec![vec![
+            vec![7.0, 46.0, 500.0],
+            vec![8.0, 46.0, 500.0],
+            vec![8.0, 47.0, 500.0],
+            vec![7.0, 46.0, 500.0],
+        ]]));
+        let original = geometry.clone();
+
+        transform(&mut geometry, &wgs84, &mercator).unwrap();
+        match &geometry.value {
+            Value::Polygon(rings) => {
+                let first = &rings[0][0];
+                assert_close([first[0], first[1]], to_web_mercator(&[7.0, 46.0]), 1e-9);
+                assert_eq!(first[2], 500.0);
+            }
+            _ => unreachable!(),
+        }
+
+        transform(&mut geometry, &mercator, &wgs84).unwrap();
+        match (&geometry.value, &original.value) {
+            (Value::Polygon(a), Value::Polygon(b)) => {
+                for (a, b) in a[0].iter().zip(&b[0]) {
+                    assert_close([a[0], a[1]], [b[0], b[1]], 1e-9);
+                }
+            }
+            _ => unreachable!(),
+        }
+
+        assert!(transform(&mut geometry, &wgs84, &Crs::from_epsg(2056)).is_err());
+
+        // positions lacking a latitude
+        let mut geometry = Geometry::new(Value::LineString(vec![vec![7.0, 46.0], vec![8.0]]));
+        let original = geometry.clone();
+        assert!(transform(&mut geometry, &wgs84, &mercator).is_err());
+        assert_eq!(geometry, original);
+    }
+}
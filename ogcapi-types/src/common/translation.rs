@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+/// Metadata of a resource in another language
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq, Clone)]
+pub struct Translation {
+    pub title: Option<String>,
+    pub description: Option<String>,
+}
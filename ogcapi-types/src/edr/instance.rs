@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+use crate::common::{Extent, Links};
+
+/// Named snapshot of a collection, e.g. the run of a forecast model
+///
+/// Items belong to an instance if their `instance` property equals its id.
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct Instance {
+    pub id: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub extent: Option<Extent>,
+    #[serde(default)]
+    pub links: Links,
+}
+
+/// Instances of a collection
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct Instances {
+    pub links: Links,
+    pub instances: Vec<Instance>,
+}
@@ -1,10 +1,12 @@
 mod data_queries;
+mod instance;
 mod observed_property;
 mod parameter_names;
 mod query;
 mod units;
 
 pub use data_queries::DataQueries;
+pub use instance::{Instance, Instances};
 pub use observed_property::ObservedPropertyCollection;
 pub use parameter_names::ParameterNames;
 pub use query::{Query, QueryType};
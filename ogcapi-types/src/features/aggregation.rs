@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::common::Links;
+
+/// Statistics of a numeric feature property, optionally grouped by another property
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Aggregation {
+    pub property: String,
+    pub group_by: Option<String>,
+    pub groups: Vec<AggregationGroup>,
+    #[serde(default)]
+    pub links: Links,
+}
+
+/// Statistics of the features sharing the same value of the grouping property
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct AggregationGroup {
+    pub key: Option<Value>,
+    pub count: u64,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub avg: Option<f64>,
+    pub sum: Option<f64>,
+}
@@ -28,6 +28,8 @@ pub struct Feature {
     pub geometry: Geometry,
     #[serde(default)]
     pub links: Links,
+    /// Version of the feature, incremented on every update.
+    pub version: Option<i64>,
     /// The STAC version the Item implements.
     #[cfg(feature = "stac")]
     #[serde(default = "crate::stac::stac_version")]
@@ -54,3 +56,80 @@ impl Feature {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use geojson::Value;
+    use serde_json::json;
+
+    use super::*;
+
+    fn roundtrip(geometry: Value) {
+        let feature = Feature {
+            id: Some("1".to_string()),
+            collection: None,
+            r#type: Type::Feature,
+            properties: Some(Map::new()),
+            geometry: Geometry::new(geometry),
+            links: Vec::new(),
+            version: None,
+            #[cfg(feature = "stac")]
+            stac_version: crate::stac::stac_version(),
+            #[cfg(feature = "stac")]
+            stac_extensions: Vec::new(),
+            #[cfg(feature = "stac")]
+            assets: HashMap::new(),
+            #[cfg(feature = "stac")]
+            bbox: None,
+        };
+
+        let value = serde_json::to_value(&feature).unwrap();
+        let geometry: Geometry = serde_json::from_value(value["geometry"].clone()).unwrap();
+        assert_eq!(geometry.value, feature.geometry.value);
+
+        let parsed: Feature = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed, feature);
+    }
+
+    #[test]
+    fn geometries() {
+        let point = vec![7.44, 46.95];
+        let line = vec![point.clone(), vec![8.54, 47.37]];
+        let ring = vec![
+            vec![0.0, 0.0],
+            vec![1.0, 0.0],
+            vec![1.0, 1.0],
+            vec![0.0, 0.0],
+        ];
+
+        roundtrip(Value::Point(point.clone()));
+        roundtrip(Value::MultiPoint(line.clone()));
+        roundtrip(Value::LineString(line.clone()));
+        roundtrip(Value::MultiLineString(vec![line.clone(), line]));
+        roundtrip(Value::Polygon(vec![ring.clone()]));
+        roundtrip(Value::MultiPolygon(vec![vec![ring.clone()], vec![ring]]));
+        roundtrip(Value::GeometryCollection(vec![
+            Geometry::new(Value::Point(point.clone())),
+            Geometry::new(Value::GeometryCollection(vec![Geometry::new(
+                Value::Point(point),
+            )])),
+        ]));
+    }
+
+    #[test]
+    fn invalid_geometry() {
+        let feature = json!({
+            "type": "Feature",
+            "properties": {},
+            "geometry": { "type": "Point", "coordinates": "7.44,46.95" }
+        });
+        assert!(serde_json::from_value::<Feature>(feature).is_err());
+
+        let feature = json!({
+            "type": "Feature",
+            "properties": {},
+            "geometry": { "type": "Circle", "coordinates": [7.44, 46.95] }
+        });
+        assert!(serde_json::from_value::<Feature>(feature).is_err());
+    }
+}
@@ -1,7 +1,7 @@
 use chrono::{SecondsFormat, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::common::Links;
+use crate::common::{Bbox, Links};
 
 use super::Feature;
 
@@ -19,6 +19,8 @@ pub struct FeatureCollection {
     #[serde(default)]
     pub r#type: Type,
     pub features: Vec<Feature>,
+    /// Envelope of the geometries of all features
+    pub bbox: Option<Bbox>,
     #[serde(default)]
     pub links: Links,
     pub time_stamp: Option<String>,
@@ -30,6 +32,7 @@ impl FeatureCollection {
     pub fn new(features: Vec<Feature>) -> Self {
         let number_returned = features.len();
         FeatureCollection {
+            bbox: Bbox::envelope(features.iter().map(|f| &f.geometry)),
             features,
             time_stamp: Some(Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)),
             number_returned: Some(number_returned as u64),
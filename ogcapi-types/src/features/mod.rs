@@ -1,9 +1,16 @@
+mod aggregation;
 mod feature;
 mod feature_collection;
 mod query;
+mod values;
 
+pub use aggregation::{Aggregation, AggregationGroup};
 pub use feature::Feature;
 pub use feature_collection::FeatureCollection;
-pub use query::Query;
+pub use query::{FilterLang, GeometryFormat, Nulls, Query};
+pub use values::{PropertyValue, PropertyValues};
 
-pub use geojson::Geometry;
+pub use geojson::{Geometry, Value as GeometryValue};
+
+/// Item type of collections holding features, the default
+pub const FEATURE: &str = "feature";
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
-use serde_with::DisplayFromStr;
+use serde_with::{formats::CommaSeparator, DisplayFromStr, StringWithSeparator};
 
 use crate::common::{Bbox, Crs, Datetime};
 
@@ -17,18 +17,50 @@ pub struct Query {
     #[serde(default)]
     #[serde_as(as = "DisplayFromStr")]
     pub bbox_crs: Crs,
+    /// Longitude and latitude of the center of a radius search
+    #[serde(default)]
+    #[serde_as(as = "Option<StringWithSeparator::<CommaSeparator, f64>>")]
+    pub center: Option<Vec<f64>>,
+    /// Radius in meters around the `center`
+    pub radius: Option<f64>,
     #[serde(default)]
     #[serde_as(as = "Option<DisplayFromStr>")]
     pub datetime: Option<Datetime>,
     #[serde(default)]
     #[serde_as(as = "DisplayFromStr")]
     pub crs: Crs,
+    /// Geometry column to return and filter by, one of the `geometryColumns`
+    /// of the collection
+    pub geometry_column: Option<String>,
+    /// Omit the geometry of the features, no reprojection takes place
+    #[serde(rename = "skipGeometry")]
+    pub skip_geometry: Option<bool>,
     pub filter: Option<String>,
     #[serde(default)]
     pub filter_lang: Option<FilterLang>,
     #[serde(default)]
     #[serde_as(as = "Option<DisplayFromStr>")]
     pub filter_crs: Option<Crs>,
+    /// Output format, defaults to GeoJSON
+    pub f: Option<String>,
+    /// Additional encoding of the geometry, added to the feature properties
+    pub geometry_format: Option<GeometryFormat>,
+    /// Comma separated list of properties to sort by, prefixed with `-` for
+    /// descending order
+    pub sortby: Option<String>,
+    /// Placement of features without a value of a sort property, last by
+    /// default regardless of the direction
+    pub nulls: Option<Nulls>,
+    /// Comma separated list of search terms, matching any of them
+    pub q: Option<String>,
+    /// Distance in units of the storage crs within which features are merged
+    /// into a single point with a `count` property
+    pub cluster: Option<f64>,
+    /// Comma separated list of properties features must have, regardless of
+    /// their value
+    pub exists: Option<String>,
+    /// Comma separated list of properties features must not have
+    pub not_exists: Option<String>,
     /// Parameters for filtering on feature properties
     #[serde(default, flatten)]
     pub additional_parameters: HashMap<String, String>,
@@ -41,3 +73,23 @@ pub enum FilterLang {
     CqlText,
     CqlJson,
 }
+
+/// Well-known encodings of geometries
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GeometryFormat {
+    /// Well-known text, added as property `wkt`
+    Wkt,
+    /// Hex encoded well-known binary, added as property `wkb`
+    Wkb,
+}
+
+/// Placement of null values when sorting
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Nulls {
+    #[serde(rename = "nullsFirst")]
+    First,
+    #[default]
+    #[serde(rename = "nullsLast")]
+    Last,
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::common::Links;
+
+/// Distinct values of a feature property
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PropertyValues {
+    pub property: String,
+    pub values: Vec<PropertyValue>,
+    #[serde(default)]
+    pub links: Links,
+}
+
+/// A distinct property value and the number of features having it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PropertyValue {
+    pub value: Value,
+    pub count: u64,
+}
@@ -8,6 +8,8 @@ pub mod edr;
 pub mod features;
 /// Types specified in the `OGC API - Processed` standard.
 pub mod processes;
+/// Types specified in the `OGC API - Records` standard.
+pub mod records;
 /// Types from the `SpatioTemporal Asset Catalog` specfication.
 #[cfg(feature = "stac")]
 pub mod stac;
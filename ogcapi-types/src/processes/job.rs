@@ -7,7 +7,7 @@ use crate::common::Links;
 
 use super::execute::InlineOrRefData;
 
-#[derive(Serialize, Deserialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
 pub struct StatusInfo {
     #[serde(rename = "processID", alias = "process_id")]
     pub process_id: Option<String>,
@@ -23,7 +23,7 @@ pub struct StatusInfo {
     pub links: Links,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum StatusCode {
     Accepted,
@@ -33,14 +33,21 @@ pub enum StatusCode {
     Dismissed,
 }
 
+impl StatusCode {
+    /// Whether the job has reached a final state
+    pub fn is_final(&self) -> bool {
+        matches!(self, Self::Successful | Self::Failed | Self::Dismissed)
+    }
+}
+
 impl Default for StatusCode {
     fn default() -> Self {
         Self::Accepted
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct Results {
     #[serde(flatten)]
-    results: HashMap<String, InlineOrRefData>,
+    pub results: HashMap<String, InlineOrRefData>,
 }
@@ -0,0 +1,6 @@
+mod record;
+
+pub use record::{Concept, Contact, RecordProperties, Theme};
+
+/// Item type of collections holding metadata records
+pub const RECORD: &str = "record";
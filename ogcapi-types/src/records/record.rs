@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Core properties of a metadata record
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordProperties {
+    /// The nature or genre of the resource described by the record.
+    pub r#type: String,
+    pub title: String,
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+    /// Knowledge organization systems used to classify the resource.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub themes: Vec<Theme>,
+    /// Parties associated with the resource.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contacts: Vec<Contact>,
+    pub language: Option<String>,
+    pub created: Option<String>,
+    pub updated: Option<String>,
+    pub license: Option<String>,
+    #[serde(flatten)]
+    pub additional_properties: Map<String, Value>,
+}
+
+/// Concepts of a knowledge organization system
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct Theme {
+    pub concepts: Vec<Concept>,
+    /// Identifier of the knowledge organization system.
+    pub scheme: String,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct Concept {
+    pub id: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub url: Option<String>,
+}
+
+/// A party associated with the resource
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct Contact {
+    pub name: Option<String>,
+    pub organization: Option<String>,
+    pub position: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub emails: Vec<Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub phones: Vec<Value>,
+    /// The roles of the party, e.g. `publisher` or `pointOfContact`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roles: Vec<String>,
+}
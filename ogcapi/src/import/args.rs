@@ -23,6 +23,11 @@ pub struct Args {
     /// Postgres database url
     #[clap(long, env, hide_env_values = true, value_parser)]
     pub database_url: url::Url,
+
+    /// Schema of the metadata tables, item tables are kept in `{schema}_items`
+    /// unless the default `meta` is used
+    #[clap(long, env, default_value = ogcapi_drivers::postgres::SCHEMA)]
+    pub db_schema: String,
 }
 
 impl Args {
@@ -38,6 +43,7 @@ impl Args {
             s_srs: None,
             t_srs: None,
             database_url: database_url.to_owned(),
+            db_schema: ogcapi_drivers::postgres::SCHEMA.to_string(),
         }
     }
 }
@@ -1,24 +1,37 @@
-use std::convert::TryInto;
+use std::{convert::TryInto, path::Path};
 
 use geo::Geometry;
 
 use ogcapi_drivers::{postgres::Db, CollectionTransactions};
-use ogcapi_types::common::{Collection, Crs, Extent, SpatialExtent};
+use ogcapi_types::{
+    common::{Collection, Crs, Extent, SpatialExtent},
+    features::FEATURE,
+};
 
 use super::Args;
 
 pub async fn load(args: Args) -> anyhow::Result<()> {
     // Setup driver
-    let db = Db::setup(&args.database_url).await?;
+    let db = Db::setup_with_schema(&args.database_url, &args.db_schema).await?;
 
     // Extract data
     let geojson_str = std::fs::read_to_string(&args.input)?;
     let geojson = geojson_str.parse::<geojson::FeatureCollection>()?;
 
+    import(&db, &args.collection, geojson, &args.input).await
+}
+
+/// Replaces the collection `id` with the features of a `FeatureCollection`
+pub(crate) async fn import(
+    db: &Db,
+    id: &str,
+    geojson: geojson::FeatureCollection,
+    #[allow(unused_variables)] input: &Path,
+) -> anyhow::Result<()> {
     // Create collection
     let collection = Collection {
-        id: args.collection.to_owned(),
-        item_type: Some("Feature".to_string()),
+        id: id.to_owned(),
+        item_type: Some(FEATURE.to_string()),
         extent: geojson
             .bbox
             .map(|bbox| Extent {
@@ -35,7 +48,7 @@ pub async fn load(args: Args) -> anyhow::Result<()> {
         crs: vec![Crs::default(), Crs::from_epsg(3857), Crs::from_epsg(2056)],
         storage_crs: Some(Crs::default()),
         #[cfg(feature = "stac")]
-        assets: crate::import::load_asset_from_path(&args.input).await?,
+        assets: crate::import::load_asset_from_path(&input.to_path_buf()).await?,
         ..Default::default()
     };
 
@@ -46,7 +59,7 @@ pub async fn load(args: Args) -> anyhow::Result<()> {
     let now = std::time::Instant::now();
     let count = geojson.features.len();
 
-    bulk_load_features(&collection.id, &geojson.features, &db.pool).await?;
+    bulk_load_features(db, &collection.id, &geojson.features).await?;
 
     // stats
     let elapsed = now.elapsed().as_millis() as f64 / 1000.0;
@@ -59,9 +72,9 @@ pub async fn load(args: Args) -> anyhow::Result<()> {
 }
 
 async fn bulk_load_features(
+    db: &Db,
     collection: &str,
     features: &[geojson::Feature],
-    pool: &sqlx::PgPool,
 ) -> anyhow::Result<()> {
     let mut ids = Vec::new();
     let mut properties = Vec::new();
@@ -84,7 +97,7 @@ async fn bulk_load_features(
         geoms.push(wkb::geom_to_wkb::<f64>(&geom).unwrap());
     }
 
-    super::bulk_load_items(collection, &ids, &properties[..], &geoms[..], pool).await?;
+    super::bulk_load_items(db, collection, &ids, &properties[..], &geoms[..]).await?;
 
     Ok(())
 }
@@ -3,6 +3,7 @@ mod boundaries;
 pub mod geojson;
 pub mod ogr;
 pub mod osm;
+pub mod seed;
 
 pub use args::Args;
 
@@ -43,11 +44,11 @@ pub(crate) async fn load_asset_from_path(
 }
 
 pub(crate) async fn bulk_load_items(
+    db: &ogcapi_drivers::postgres::Db,
     collection: &str,
     ids: &[String],
     properties: &[Option<sqlx::types::Json<serde_json::Map<String, serde_json::Value>>>],
     geoms: &[Vec<u8>],
-    connection: &sqlx::PgPool,
 ) -> Result<(), sqlx::Error> {
     let batch_size = 10000;
     let total = geoms.len();
@@ -71,15 +72,16 @@ pub(crate) async fn bulk_load_items(
         }
         sqlx::query(&format!(
             r#"
-            INSERT INTO items."{}" (id, properties, geom)
+            INSERT INTO "{}"."{}" (id, properties, geom)
             SELECT * FROM UNNEST($1::text[], $2::jsonb[], $3::bytea[])
             "#,
+            db.items_schema(),
             collection
         ))
         .bind(ids_batch)
         .bind(properties_batch)
         .bind(geoms_batch)
-        .execute(connection)
+        .execute(&db.pool)
         .await?;
 
         start = end;
@@ -13,7 +13,7 @@ use super::Args;
 
 pub async fn load(mut args: Args) -> Result<(), anyhow::Error> {
     // Setup a db connection pool
-    let db = Db::setup(&args.database_url).await?;
+    let db = Db::setup_with_schema(&args.database_url, &args.db_schema).await?;
 
     // Handle http & zip
     if args.input.starts_with("http") && args.input.extension() == Some(std::ffi::OsStr::new("zip"))
@@ -127,11 +127,13 @@ pub async fn load(mut args: Args) -> Result<(), anyhow::Error> {
                 }
             }
         {
-            sqlx::query("SELECT DropGeometryColumn ('items', $1, 'geom')")
+            sqlx::query("SELECT DropGeometryColumn ($1, $2, 'geom')")
+                .bind(db.items_schema())
                 .bind(&collection.id)
                 .execute(&db.pool)
                 .await?;
-            sqlx::query("SELECT AddGeometryColumn ('items', $1, 'geom', $2, $3, $4)")
+            sqlx::query("SELECT AddGeometryColumn ($1, $2, 'geom', $3, $4, $5)")
+                .bind(db.items_schema())
                 .bind(&collection.id)
                 .bind(storage_crs.as_srid())
                 .bind(geometry_type)
@@ -139,7 +141,8 @@ pub async fn load(mut args: Args) -> Result<(), anyhow::Error> {
                 .execute(&db.pool)
                 .await?;
             sqlx::query(&format!(
-                r#"CREATE INDEX ON items."{}" USING gist (geom)"#,
+                r#"CREATE INDEX ON "{}"."{}" USING gist (geom)"#,
+                db.items_schema(),
                 collection.id
             ))
             .execute(&db.pool)
@@ -192,7 +195,7 @@ pub async fn load(mut args: Args) -> Result<(), anyhow::Error> {
         }
 
         // load
-        super::bulk_load_items(&collection.id, &ids, &properties[..], &geoms[..], &db.pool).await?;
+        super::bulk_load_items(&db, &collection.id, &ids, &properties[..], &geoms[..]).await?;
 
         // stats
         let elapsed = now.elapsed().as_millis() as f64 / 1000.0;
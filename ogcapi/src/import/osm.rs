@@ -12,7 +12,7 @@ use super::Args;
 /// Import osm data from pbf file
 pub async fn load(args: Args) -> Result<(), anyhow::Error> {
     // Setup a db connection pool
-    let db = Db::setup(&args.database_url).await?;
+    let db = Db::setup_with_schema(&args.database_url, &args.db_schema).await?;
 
     // Create collection
     let collection = Collection {
@@ -66,11 +66,12 @@ pub async fn load(args: Args) -> Result<(), anyhow::Error> {
             geometry_from_obj(&obj, &objs).and_then(|g| wkb::geom_to_wkb(&g).ok())
         {
             sqlx::query(&format!(
-                r#"INSERT INTO items.{} (
+                r#"INSERT INTO "{}"."{}" (
                     id,
                     properties,
                     geom
                 ) VALUES ($1, $2, ST_GeomFromWKB($3, 4326))"#,
+                db.items_schema(),
                 collection.id
             ))
             .bind(id.to_string())
@@ -0,0 +1,62 @@
+use std::path::{Path, PathBuf};
+
+use ogcapi_drivers::postgres::Db;
+
+#[derive(clap::Parser, Debug)]
+pub struct Args {
+    /// Directory with GeoJSON files, one collection is created per file
+    #[clap(long, value_parser)]
+    pub dir: PathBuf,
+
+    /// Postgres database url
+    #[clap(long, env, hide_env_values = true, value_parser)]
+    pub database_url: url::Url,
+
+    /// Schema of the metadata tables, item tables are kept in `{schema}_items`
+    /// unless the default `meta` is used
+    #[clap(long, env, default_value = ogcapi_drivers::postgres::SCHEMA)]
+    pub db_schema: String,
+}
+
+/// Loads every GeoJSON `FeatureCollection` in a directory into a collection
+/// named after the file stem and returns the ids of the created collections
+pub async fn load(
+    dir: &Path,
+    database_url: &url::Url,
+    db_schema: &str,
+) -> anyhow::Result<Vec<String>> {
+    // Setup driver
+    let db = Db::setup_with_schema(database_url, db_schema).await?;
+
+    let mut paths = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<PathBuf>, _>>()?;
+    paths.sort();
+
+    let mut ids = Vec::new();
+
+    for path in paths {
+        let is_geojson = matches!(
+            path.extension().and_then(|e| e.to_str()),
+            Some("geojson") | Some("json")
+        );
+        if !path.is_file() || !is_geojson {
+            continue;
+        }
+
+        let id = match path.file_stem().and_then(|s| s.to_str()) {
+            Some(stem) => stem.to_string(),
+            None => continue,
+        };
+
+        tracing::debug!("Seeding collection `{id}` from `{}` ...", path.display());
+
+        let geojson = std::fs::read_to_string(&path)?.parse::<geojson::FeatureCollection>()?;
+
+        super::geojson::import(&db, &id, geojson, &path).await?;
+
+        ids.push(id);
+    }
+
+    Ok(ids)
+}
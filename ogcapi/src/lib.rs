@@ -3,6 +3,9 @@
 #[cfg(feature = "import")]
 pub mod import;
 
+#[cfg(feature = "tiles")]
+pub mod tiles;
+
 #[cfg(feature = "client")]
 pub mod client {
     pub use ogcapi_client::*;
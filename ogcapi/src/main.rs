@@ -13,9 +13,20 @@ pub enum Command {
     /// Import geodata into the database
     #[cfg(feature = "import")]
     Import(ogcapi::import::Args),
-    /// Start the ogcapi services
+    /// Seed the database with a directory of GeoJSON files
+    #[cfg(feature = "import")]
+    Seed(ogcapi::import::seed::Args),
+    /// Pre-generate the tiles of a collection into the tile cache
+    #[cfg(feature = "tiles")]
+    SeedTiles(ogcapi::tiles::Args),
+    /// Start the ogcapi services, see `ogcapi serve --help` for the options
     #[cfg(feature = "services")]
-    Serve(ogcapi_services::Config),
+    #[clap(disable_help_flag = true, allow_hyphen_values = true)]
+    Serve {
+        /// Options of the services, also read from the environment and the
+        /// configuration file
+        args: Vec<std::ffi::OsString>,
+    },
 }
 
 #[tokio::main]
@@ -47,8 +58,36 @@ async fn main() -> anyhow::Result<()> {
                 }
             }
         }
+        #[cfg(feature = "import")]
+        Command::Seed(args) => {
+            let ids =
+                ogcapi::import::seed::load(&args.dir, &args.database_url, &args.db_schema).await?;
+            tracing::info!("Seeded collections {:?}", ids);
+        }
+        #[cfg(feature = "tiles")]
+        Command::SeedTiles(args) => {
+            let db = ogcapi_drivers::postgres::Db::setup_with_schema(
+                &args.database_url,
+                &args.db_schema,
+            )
+            .await?;
+            let count = ogcapi::tiles::seed(
+                &db,
+                &args.collection,
+                args.min_zoom..=args.max_zoom,
+                &args.bbox,
+                args.concurrency,
+            )
+            .await?;
+            tracing::info!("Seeded {count} tiles of collection `{}`", args.collection);
+        }
         #[cfg(feature = "services")]
-        Command::Serve(config) => {
+        Command::Serve { args } => {
+            // read the configuration file as well
+            let config = ogcapi_services::Config::load_from(
+                std::iter::once(std::ffi::OsString::from("ogcapi serve")).chain(args),
+            );
+
             // Application state
             let state = ogcapi_services::AppState::new_from(&config)
                 .await
@@ -0,0 +1,144 @@
+//! Pre-generation of tiles into the tile cache
+
+use std::{ops::RangeInclusive, sync::Arc};
+
+use ogcapi_drivers::{postgres::Db, TileTransactions};
+use ogcapi_types::{
+    common::{Bbox, Crs},
+    tiles::{TileMatrixSet, TitleDescriptionKeywords},
+};
+use tokio::task::JoinSet;
+
+/// Latitude limit of the web mercator projection
+const MAX_LATITUDE: f64 = 85.051_128_779_806_6;
+
+/// Highest zoom level of the `WebMercatorQuad` tile matrix set
+const MAX_ZOOM: u8 = 24;
+
+#[derive(clap::Parser, Debug)]
+pub struct Args {
+    /// Collection to generate tiles for
+    #[clap(long)]
+    pub collection: String,
+
+    /// Lowest zoom level to generate
+    #[clap(long, default_value_t = 0, value_parser = clap::value_parser!(u8).range(0..=MAX_ZOOM as i64))]
+    pub min_zoom: u8,
+
+    /// Highest zoom level to generate
+    #[clap(long, value_parser = clap::value_parser!(u8).range(0..=MAX_ZOOM as i64))]
+    pub max_zoom: u8,
+
+    /// Limits the tiles to the ones intersecting `minx,miny,maxx,maxy` in
+    /// WGS 84 longitude and latitude
+    #[clap(long, default_value = "-180,-90,180,90")]
+    pub bbox: Bbox,
+
+    /// Number of tiles generated concurrently
+    #[clap(long, default_value_t = 4)]
+    pub concurrency: usize,
+
+    /// Postgres database url
+    #[clap(long, env, hide_env_values = true, value_parser)]
+    pub database_url: url::Url,
+
+    /// Schema of the metadata tables, item tables are kept in `{schema}_items`
+    /// unless the default `meta` is used
+    #[clap(long, env, default_value = ogcapi_drivers::postgres::SCHEMA)]
+    pub db_schema: String,
+}
+
+/// The `WebMercatorQuad` tile matrix set the tiles are generated for
+fn web_mercator_quad() -> TileMatrixSet {
+    TileMatrixSet {
+        title_description_keywords: TitleDescriptionKeywords {
+            title: None,
+            description: None,
+            keywords: None,
+        },
+        id: "WebMercatorQuad".to_string(),
+        uri: Some("http://www.opengis.net/def/tilematrixset/OGC/1.0/WebMercatorQuad".to_string()),
+        crs: Crs::from_epsg(3857),
+        ordered_axes: None,
+        well_known_scale_set: None,
+        bounding_box: None,
+        tile_matrices: Vec::new(),
+    }
+}
+
+/// Generates the tiles of a collection intersecting the bbox for all zoom
+/// levels and stores them in the tile cache, returns the number of tiles
+pub async fn seed(
+    db: &Db,
+    collection: &str,
+    zooms: RangeInclusive<u8>,
+    bbox: &Bbox,
+    concurrency: usize,
+) -> anyhow::Result<usize> {
+    anyhow::ensure!(
+        *zooms.end() <= MAX_ZOOM,
+        "Zoom level {} exceeds the maximum of {MAX_ZOOM}",
+        zooms.end()
+    );
+
+    let tms = Arc::new(web_mercator_quad());
+    let concurrency = concurrency.max(1);
+
+    let mut count = 0;
+    let mut tasks = JoinSet::new();
+    for zoom in zooms {
+        let (cols, rows) = tile_range(bbox, zoom);
+        for row in rows {
+            for col in cols.clone() {
+                // drain finished tasks to keep at most `concurrency` spawned
+                if tasks.len() >= concurrency {
+                    if let Some(result) = tasks.join_next().await {
+                        result??;
+                        count += 1;
+                    }
+                }
+
+                let db = db.clone();
+                let tms = tms.clone();
+                let collection = collection.to_owned();
+
+                tasks.spawn(async move {
+                    let matrix = zoom.to_string();
+                    let tile = db.tile(&collection, &tms, &matrix, row, col).await?;
+                    db.cache_tile(&collection, &tms, &matrix, row, col, &tile)
+                        .await?;
+                    anyhow::Ok(())
+                });
+            }
+        }
+    }
+
+    while let Some(result) = tasks.join_next().await {
+        result??;
+        count += 1;
+    }
+
+    tracing::debug!("Seeded {count} tiles of collection `{collection}`");
+
+    Ok(count)
+}
+
+/// Column and row ranges of the tiles intersecting the bbox at a zoom level
+fn tile_range(bbox: &Bbox, zoom: u8) -> (RangeInclusive<u32>, RangeInclusive<u32>) {
+    let (min_lon, min_lat, max_lon, max_lat) = match bbox {
+        Bbox::Bbox2D(b) => (b[0], b[1], b[2], b[3]),
+        Bbox::Bbox3D(b) => (b[0], b[1], b[3], b[4]),
+    };
+
+    let n = 2_u32.pow(zoom as u32);
+    let max = n - 1;
+
+    let col = |lon: f64| (((lon + 180.0) / 360.0 * n as f64).floor() as u32).min(max);
+    let row = |lat: f64| {
+        let lat = lat.clamp(-MAX_LATITUDE, MAX_LATITUDE).to_radians();
+        let y = (1.0 - lat.tan().asinh() / std::f64::consts::PI) / 2.0;
+        ((y * n as f64).floor() as u32).min(max)
+    };
+
+    (col(min_lon)..=col(max_lon), row(max_lat)..=row(min_lat))
+}
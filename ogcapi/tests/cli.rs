@@ -0,0 +1,35 @@
+#![cfg(feature = "import")]
+
+use std::process::Command;
+
+use ogcapi_drivers::{postgres::Db, CollectionTransactions};
+
+#[tokio::test]
+async fn import_subcommand() -> anyhow::Result<()> {
+    dotenvy::dotenv().ok();
+
+    // temporary database
+    let mut database_url = url::Url::parse(&std::env::var("DATABASE_URL")?)?;
+    database_url.set_path(&uuid::Uuid::new_v4().to_string());
+
+    let status = Command::new(env!("CARGO_BIN_EXE_ogcapi"))
+        .args([
+            "import",
+            "--input",
+            "../data/ne_110m_populated_places.geojson",
+            "--collection",
+            "places",
+            "--database-url",
+            database_url.as_str(),
+        ])
+        .status()?;
+
+    assert!(status.success());
+
+    let db = Db::setup(&database_url).await?;
+    let collection = db.read_collection("places").await?;
+
+    assert!(collection.is_some());
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+#![cfg(all(feature = "tiles", feature = "import"))]
+
+use ogcapi_drivers::{postgres::Db, CollectionTransactions, FeatureTransactions, TileTransactions};
+use ogcapi_types::{
+    common::{Bbox, Collection, Crs},
+    features::Feature,
+    tiles::TileMatrixSet,
+};
+
+#[tokio::test]
+async fn seed_tiles() -> anyhow::Result<()> {
+    dotenvy::dotenv().ok();
+
+    // temporary database
+    let mut database_url = url::Url::parse(&std::env::var("DATABASE_URL")?)?;
+    database_url.set_path(&uuid::Uuid::new_v4().to_string());
+
+    let db = Db::setup(&database_url).await?;
+
+    let collection = Collection {
+        id: "places".to_string(),
+        crs: vec![Crs::default()],
+        ..Default::default()
+    };
+    db.create_collection(&collection).await?;
+
+    let feature: Feature = serde_json::from_value(serde_json::json!({
+        "type": "Feature",
+        "collection": "places",
+        "properties": { "name": "Bern" },
+        "geometry": { "type": "Point", "coordinates": [7.44, 46.95] }
+    }))?;
+    db.create_feature(&feature).await?;
+
+    // zoom 0 and the single tile of zoom 1 covering the bbox
+    let bbox: Bbox = "7,46,8,47".parse().unwrap();
+    let count = ogcapi::tiles::seed(&db, "places", 0..=1, &bbox, 2).await?;
+    assert_eq!(count, 2);
+
+    let tms: TileMatrixSet = serde_json::from_str(include_str!(
+        "../../ogcapi-services/assets/tms/WebMercartorQuad.json"
+    ))?;
+
+    for (matrix, row, col) in [("0", 0, 0), ("1", 0, 1)] {
+        let tile = db
+            .read_cached_tile("places", &tms, matrix, row, col)
+            .await?;
+        assert!(tile.map_or(false, |tile| !tile.is_empty()));
+    }
+
+    // zoom levels beyond the tile matrix set are rejected
+    assert!(ogcapi::tiles::seed(&db, "places", 0..=32, &bbox, 2)
+        .await
+        .is_err());
+
+    // outside of the bbox
+    assert!(db
+        .read_cached_tile("places", &tms, "1", 1, 0)
+        .await?
+        .is_none());
+
+    Ok(())
+}